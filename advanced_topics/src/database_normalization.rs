@@ -3,6 +3,24 @@
 //! an intermediate Enrollment struct is introduced to decouple the relationships. This is similar
 //! to database normalization techniques, where a join table helps avoid complex cyclic dependencies
 //! and facilitates independent management of related entities.
+//!
+//! Like a real join table, each Enrollment also carries data about the relationship itself: the
+//! term it belongs to, its status, and the grade the student earned. Prerequisites between courses
+//! are stored the same way, as a separate table of (course, required course) pairs. Courses also
+//! carry their weekly meeting times, so the Platform can detect students whose courses overlap and
+//! rooms that are double-booked.
+//!
+//! The `query` submodule builds ad-hoc reporting on top of these tables, and `repl` lets you type
+//! such queries against a dataset loaded from a text file.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use crate::grades::{self, EnrollmentStatus, Grade, LetterGrade, Season, Term};
+use crate::schedule::{Meeting, Time, Weekday};
+
+mod query;
+pub mod repl;
 
 /// Represents a student with a name.
 struct Student {
@@ -10,24 +28,193 @@ struct Student {
 }
 
 impl Student {
-    /// Returns a list of course names that the student is enrolled in, based on the enrollments in the given platform.
+    /// Returns a list of course names that the student is actively enrolled in, based on the enrollments in the given platform.
     ///
     /// # Arguments
     ///
     /// * `platform` - A Platform instance containing enrollment records.
-    fn courses(&self, platform: Platform) -> Vec<String> {
+    fn courses(&self, platform: &Platform) -> Vec<String> {
         platform
             .enrollments
             .iter()
-            .filter(|&e| e.student.name == self.name)
+            .filter(|&e| e.student.name == self.name && e.status == EnrollmentStatus::Active)
             .map(|e| e.course.name.clone())
             .collect()
     }
+
+    /// Returns the student's cumulative GPA over all graded, completed enrollments, or None if
+    /// the student has no grades yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `platform` - A Platform instance containing enrollment records.
+    fn gpa(&self, platform: &Platform) -> Option<f64> {
+        grades::gpa(
+            platform
+                .enrollments
+                .iter()
+                .filter(|&e| e.student.name == self.name && e.status == EnrollmentStatus::Completed)
+                .filter_map(|e| e.grade.as_ref()),
+        )
+    }
+
+    /// Builds a transcript report listing every enrollment grouped by term, with a GPA for each
+    /// term and a cumulative GPA at the end.
+    ///
+    /// # Arguments
+    ///
+    /// * `platform` - A Platform instance containing enrollment records.
+    fn transcript(&self, platform: &Platform) -> String {
+        let mut by_term: BTreeMap<Term, Vec<&Enrollment>> = BTreeMap::new();
+        for e in platform
+            .enrollments
+            .iter()
+            .filter(|e| e.student.name == self.name)
+        {
+            by_term.entry(e.term).or_default().push(e);
+        }
+
+        let mut report = format!("Transcript for {}\n", self.name);
+        for (term, enrollments) in &by_term {
+            report.push_str(&format!("{}\n", term));
+            for e in enrollments {
+                let grade = match e.grade {
+                    Some(g) => g.to_string(),
+                    None => "-".into(),
+                };
+                report.push_str(&format!(
+                    "  {:<20} {:<10} {}\n",
+                    e.course.name,
+                    format!("{:?}", e.status),
+                    grade
+                ));
+            }
+            let term_gpa = grades::gpa(
+                enrollments
+                    .iter()
+                    .filter(|e| e.status == EnrollmentStatus::Completed)
+                    .filter_map(|e| e.grade.as_ref()),
+            );
+            report.push_str(&format!("  Term GPA: {}\n", format_gpa(term_gpa)));
+        }
+        report.push_str(&format!(
+            "Cumulative GPA: {}\n",
+            format_gpa(self.gpa(platform))
+        ));
+        report
+    }
 }
 
-/// Represents a course with a name.
+impl Student {
+    /// Renders the student's weekly timetable for a term as a text grid with one row per half
+    /// hour. Only active and completed enrollments are shown; overlapping courses share a cell.
+    ///
+    /// # Arguments
+    ///
+    /// * `platform` - A Platform instance containing enrollment records.
+    /// * `term` - The term to render.
+    fn timetable(&self, platform: &Platform, term: Term) -> String {
+        const SLOT: u16 = 30;
+        const WIDTH: usize = 14;
+
+        let courses: Vec<&Course> = platform
+            .enrollments
+            .iter()
+            .filter(|e| {
+                e.student.name == self.name
+                    && e.term == term
+                    && e.status != EnrollmentStatus::Dropped
+            })
+            .map(|e| e.course)
+            .collect();
+        let meetings: Vec<(&str, &Meeting)> = courses
+            .iter()
+            .flat_map(|c| c.meetings.iter().map(|m| (c.name.as_str(), m)))
+            .collect();
+
+        let mut grid = format!("Timetable for {}, {}\n", self.name, term);
+        let (Some(first), Some(last)) = (
+            meetings.iter().map(|(_, m)| m.start.minutes()).min(),
+            meetings.iter().map(|(_, m)| m.end.minutes()).max(),
+        ) else {
+            grid.push_str("(no scheduled classes)\n");
+            return grid;
+        };
+        // Show Monday to Friday, plus weekend days only when something is scheduled on them.
+        let days: Vec<Weekday> = Weekday::ALL
+            .into_iter()
+            .filter(|d| *d <= Weekday::Fri || meetings.iter().any(|(_, m)| m.day == *d))
+            .collect();
+
+        grid.push_str("      ");
+        for day in &days {
+            grid.push_str(&format!("|{:<WIDTH$}", day));
+        }
+        grid.push_str("|\n");
+        let mut slot = first / SLOT * SLOT;
+        while slot < last {
+            let time = Time::new(slot / 60, slot % 60);
+            grid.push_str(&format!("{} ", time));
+            for day in &days {
+                let names: Vec<&str> = meetings
+                    .iter()
+                    .filter(|(_, m)| {
+                        m.day == *day && m.start.minutes() < slot + SLOT && slot < m.end.minutes()
+                    })
+                    .map(|(name, _)| *name)
+                    .collect();
+                let cell: String = names.join("/").chars().take(WIDTH).collect();
+                grid.push_str(&format!("|{:<WIDTH$}", cell));
+            }
+            grid.push_str("|\n");
+            slot += SLOT;
+        }
+        grid
+    }
+}
+
+/// Formats an optional GPA with two decimal places, or "n/a" when there is nothing to average.
+fn format_gpa(gpa: Option<f64>) -> String {
+    match gpa {
+        Some(g) => format!("{:.2}", g),
+        None => "n/a".into(),
+    }
+}
+
+/// Represents a course with a name and the times it meets each week. Meeting times are assumed to
+/// be the same in every term the course is offered.
 struct Course {
     name: String,
+    meetings: Vec<Meeting>,
+}
+
+impl Course {
+    /// Counts how many completed, graded enrollments in this course received each letter grade.
+    /// Numeric grades are converted to their letter equivalent.
+    ///
+    /// # Arguments
+    ///
+    /// * `platform` - A Platform instance containing enrollment records.
+    fn grade_distribution(&self, platform: &Platform) -> BTreeMap<LetterGrade, usize> {
+        let mut distribution = BTreeMap::new();
+        for e in platform
+            .enrollments
+            .iter()
+            .filter(|&e| e.course.name == self.name && e.status == EnrollmentStatus::Completed)
+        {
+            if let Some(g) = e.grade {
+                *distribution.entry(g.letter()).or_insert(0) += 1;
+            }
+        }
+        distribution
+    }
+}
+
+/// Represents a prerequisite relationship: `course` may only be taken once `requires` has been
+/// completed. Like Enrollment, it is a join table between two rows rather than a field on Course.
+struct Prerequisite<'a> {
+    course: &'a Course,
+    requires: &'a Course,
 }
 
 /// Represents an enrollment record linking a student and a course. This acts as a join table in the
@@ -35,59 +222,571 @@ struct Course {
 struct Enrollment<'a> {
     student: &'a Student,
     course: &'a Course,
+    term: Term,
+    status: EnrollmentStatus,
+    grade: Option<Grade>,
 }
 
 impl<'a> Enrollment<'a> {
-    /// Creates a new Enrollment record linking a student and a course.
+    /// Creates a new, active Enrollment record linking a student and a course for a term.
     ///
     /// # Arguments
     ///
     /// * `student` - A reference to a Student.
     /// * `course` - A reference to a Course.
-    fn new(student: &'a Student, course: &'a Course) -> Enrollment<'a> {
-        Enrollment { student, course }
+    /// * `term` - The term the student is taking the course in.
+    fn new(student: &'a Student, course: &'a Course, term: Term) -> Enrollment<'a> {
+        Enrollment {
+            student,
+            course,
+            term,
+            status: EnrollmentStatus::Active,
+            grade: None,
+        }
+    }
+}
+
+/// Two class meetings that take place at the same time, either for the same student or in the
+/// same room.
+#[derive(Debug, Clone, PartialEq)]
+struct ScheduleConflict {
+    course: String,
+    meeting: Meeting,
+    other_course: String,
+    other_meeting: Meeting,
+}
+
+impl fmt::Display for ScheduleConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) overlaps {} ({})",
+            self.course, self.meeting, self.other_course, self.other_meeting
+        )
+    }
+}
+
+/// Lists every pair of overlapping meetings between two courses that also satisfies `same`.
+fn conflicts_between(
+    course: &Course,
+    other: &Course,
+    same: impl Fn(&Meeting, &Meeting) -> bool,
+) -> Vec<ScheduleConflict> {
+    let mut conflicts = Vec::new();
+    for m in &course.meetings {
+        for o in other
+            .meetings
+            .iter()
+            .filter(|o| m.overlaps(o) && same(m, o))
+        {
+            conflicts.push(ScheduleConflict {
+                course: course.name.clone(),
+                meeting: m.clone(),
+                other_course: other.name.clone(),
+                other_meeting: o.clone(),
+            });
+        }
+    }
+    conflicts
+}
+
+/// How the Platform reacts when an enrollment would give a student overlapping courses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConflictPolicy {
+    /// Reject the enrollment with a ScheduleConflict error.
+    Refuse,
+    /// Accept the enrollment and report the conflicts to the caller.
+    Warn,
+}
+
+/// Errors that can occur when changing enrollment records on a Platform.
+#[derive(Debug, PartialEq)]
+enum EnrollmentError {
+    /// The student already has an active enrollment in the course for the term.
+    AlreadyEnrolled { student: String, course: String },
+    /// No active enrollment exists for the student, course and term.
+    NotEnrolled { student: String, course: String },
+    /// The student has not passed every prerequisite of the course.
+    MissingPrerequisites {
+        student: String,
+        course: String,
+        missing: Vec<String>,
+    },
+    /// The course meets at the same time as another course the student is taking that term.
+    ScheduleConflict {
+        student: String,
+        conflicts: Vec<ScheduleConflict>,
+    },
+}
+
+impl fmt::Display for EnrollmentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EnrollmentError::AlreadyEnrolled { student, course } => {
+                write!(f, "{} is already enrolled in {}", student, course)
+            }
+            EnrollmentError::NotEnrolled { student, course } => {
+                write!(f, "{} is not enrolled in {}", student, course)
+            }
+            EnrollmentError::MissingPrerequisites {
+                student,
+                course,
+                missing,
+            } => write!(
+                f,
+                "{} cannot enroll in {} before completing {}",
+                student,
+                course,
+                missing.join(", ")
+            ),
+            EnrollmentError::ScheduleConflict { student, conflicts } => {
+                let conflicts: Vec<String> = conflicts.iter().map(|c| c.to_string()).collect();
+                write!(
+                    f,
+                    "schedule conflict for {}: {}",
+                    student,
+                    conflicts.join("; ")
+                )
+            }
+        }
+    }
+}
+
+/// Errors that can occur when defining prerequisites on a Platform.
+#[derive(Debug, PartialEq)]
+enum PrerequisiteError {
+    /// The new prerequisite would make a course (indirectly) require itself. The cycle is listed
+    /// starting and ending with the same course.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for PrerequisiteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PrerequisiteError::Cycle(path) => {
+                write!(f, "prerequisite cycle: {}", path.join(" -> "))
+            }
+        }
     }
 }
 
 /// Represents the platform that manages enrollments. This structure maintains a list of enrollment
-/// records, each representing a relationship between a Student and a Course.
+/// records, each representing a relationship between a Student and a Course, along with the roster
+/// of every student and course it knows about (including those without any enrollments).
 struct Platform<'a> {
+    conflict_policy: ConflictPolicy,
+    students: Vec<&'a Student>,
+    courses: Vec<&'a Course>,
     enrollments: Vec<Enrollment<'a>>,
+    prerequisites: Vec<Prerequisite<'a>>,
 }
 
 impl<'a> Platform<'a> {
     /// Creates a new, empty Platform.
     fn new() -> Platform<'a> {
         Platform {
+            conflict_policy: ConflictPolicy::Refuse,
+            students: Vec::new(),
+            courses: Vec::new(),
             enrollments: Vec::new(),
+            prerequisites: Vec::new(),
         }
     }
 
-    /// Enrolls a student in a course by creating a new enrollment record.
+    /// Chooses whether schedule conflicts refuse an enrollment or are only reported. New platforms
+    /// refuse conflicting enrollments.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy to apply to subsequent enrollments.
+    fn set_conflict_policy(&mut self, policy: ConflictPolicy) {
+        self.conflict_policy = policy;
+    }
+
+    /// Returns every pair of courses that meet in the same room at overlapping times.
+    fn room_conflicts(&self) -> Vec<ScheduleConflict> {
+        let mut conflicts = Vec::new();
+        for (i, course) in self.courses.iter().enumerate() {
+            for other in &self.courses[i + 1..] {
+                conflicts.extend(conflicts_between(course, other, |m, o| m.room == o.room));
+            }
+        }
+        conflicts
+    }
+
+    /// Returns the meetings of `course` that overlap a course the student is already taking
+    /// (actively or completed) in the same term.
+    fn schedule_conflicts(
+        &self,
+        student: &Student,
+        course: &Course,
+        term: Term,
+    ) -> Vec<ScheduleConflict> {
+        self.enrollments
+            .iter()
+            .filter(|e| {
+                e.student.name == student.name
+                    && e.term == term
+                    && e.status != EnrollmentStatus::Dropped
+                    && e.course.name != course.name
+            })
+            .flat_map(|e| conflicts_between(course, e.course, |_, _| true))
+            .collect()
+    }
+
+    /// Registers a student with the platform. Registering the same student twice has no effect.
     ///
     /// # Arguments
     ///
     /// * `student` - A reference to a Student.
+    fn add_student(&mut self, student: &'a Student) {
+        if !self.students.iter().any(|s| s.name == student.name) {
+            self.students.push(student);
+        }
+    }
+
+    /// Registers a course with the platform. Registering the same course twice has no effect.
+    ///
+    /// # Arguments
+    ///
     /// * `course` - A reference to a Course.
-    fn enroll(&mut self, student: &'a Student, course: &'a Course) {
-        self.enrollments.push(Enrollment::new(student, course));
+    fn add_course(&mut self, course: &'a Course) {
+        if !self.courses.iter().any(|c| c.name == course.name) {
+            self.courses.push(course);
+        }
+    }
+
+    /// Declares that `course` requires `requires` to be completed first. The relationship is
+    /// rejected if it would introduce a cycle, e.g. A requires B while B (indirectly) requires A.
+    ///
+    /// # Arguments
+    ///
+    /// * `course` - A reference to the Course that gains a prerequisite.
+    /// * `requires` - A reference to the Course that must be completed first.
+    fn add_prerequisite(
+        &mut self,
+        course: &'a Course,
+        requires: &'a Course,
+    ) -> Result<(), PrerequisiteError> {
+        // Adding course -> requires closes a cycle exactly when course is already reachable
+        // from requires by following existing prerequisite edges.
+        if let Some(mut path) = self.prerequisite_path(&requires.name, &course.name) {
+            path.insert(0, course.name.clone());
+            return Err(PrerequisiteError::Cycle(path));
+        }
+        if !self
+            .prerequisites_of(course)
+            .any(|p| p.name == requires.name)
+        {
+            self.add_course(course);
+            self.add_course(requires);
+            self.prerequisites.push(Prerequisite { course, requires });
+        }
+        Ok(())
+    }
+
+    /// Returns the direct prerequisites of a course.
+    fn prerequisites_of(&self, course: &Course) -> impl Iterator<Item = &'a Course> {
+        let name = course.name.clone();
+        self.prerequisites
+            .iter()
+            .filter(move |p| p.course.name == name)
+            .map(|p| p.requires)
+    }
+
+    /// Searches the prerequisite graph depth-first for a path from `from` to `to`, returning the
+    /// course names along the path (inclusive) if one exists.
+    fn prerequisite_path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        if from == to {
+            return Some(vec![from.to_string()]);
+        }
+        let mut visited = HashSet::new();
+        let mut stack = vec![vec![from.to_string()]];
+        while let Some(path) = stack.pop() {
+            let last = path.last().unwrap().clone();
+            if !visited.insert(last.clone()) {
+                continue;
+            }
+            for p in self.prerequisites.iter().filter(|p| p.course.name == last) {
+                let mut next = path.clone();
+                next.push(p.requires.name.clone());
+                if p.requires.name == to {
+                    return Some(next);
+                }
+                stack.push(next);
+            }
+        }
+        None
+    }
+
+    /// Returns a suggested order in which to study every course needed to take `target`, ending
+    /// with `target` itself. Each course appears after all of its prerequisites.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - A reference to the Course the student wants to take.
+    fn study_order(&self, target: &Course) -> Vec<String> {
+        let mut order = Vec::new();
+        let mut visited = HashSet::new();
+        self.visit_prerequisites(target, &mut visited, &mut order);
+        order
+    }
+
+    /// Post-order traversal used by study_order. Cycles are rejected by add_prerequisite, so the
+    /// recursion always terminates.
+    fn visit_prerequisites(
+        &self,
+        course: &Course,
+        visited: &mut HashSet<String>,
+        order: &mut Vec<String>,
+    ) {
+        if !visited.insert(course.name.clone()) {
+            return;
+        }
+        for p in self.prerequisites_of(course) {
+            self.visit_prerequisites(p, visited, order);
+        }
+        order.push(course.name.clone());
+    }
+
+    /// Returns whether the student has completed the course with a passing (non-F) grade.
+    /// Ungraded completions count as passed.
+    fn has_passed(&self, student: &Student, course: &Course) -> bool {
+        self.enrollments.iter().any(|e| {
+            e.student.name == student.name
+                && e.course.name == course.name
+                && e.status == EnrollmentStatus::Completed
+                && e.grade.is_none_or(|g| g.letter() != LetterGrade::F)
+        })
+    }
+
+    /// Enrolls a student in a course for a term by creating a new, active enrollment record.
+    /// Enrollment is refused unless the student has passed every prerequisite of the course.
+    /// If the course overlaps another of the student's courses that term, the enrollment is
+    /// refused or accepted with the conflicts returned, depending on the conflict policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `student` - A reference to a Student.
+    /// * `course` - A reference to a Course.
+    /// * `term` - The term the student is taking the course in.
+    fn enroll(
+        &mut self,
+        student: &'a Student,
+        course: &'a Course,
+        term: Term,
+    ) -> Result<Vec<ScheduleConflict>, EnrollmentError> {
+        if self.find_active(student, course, term).is_some() {
+            return Err(EnrollmentError::AlreadyEnrolled {
+                student: student.name.clone(),
+                course: course.name.clone(),
+            });
+        }
+        let missing: Vec<String> = self
+            .prerequisites_of(course)
+            .filter(|p| !self.has_passed(student, p))
+            .map(|p| p.name.clone())
+            .collect();
+        if !missing.is_empty() {
+            return Err(EnrollmentError::MissingPrerequisites {
+                student: student.name.clone(),
+                course: course.name.clone(),
+                missing,
+            });
+        }
+        let conflicts = self.schedule_conflicts(student, course, term);
+        if !conflicts.is_empty() && self.conflict_policy == ConflictPolicy::Refuse {
+            return Err(EnrollmentError::ScheduleConflict {
+                student: student.name.clone(),
+                conflicts,
+            });
+        }
+        self.add_student(student);
+        self.add_course(course);
+        self.enrollments
+            .push(Enrollment::new(student, course, term));
+        Ok(conflicts)
+    }
+
+    /// Marks an active enrollment as dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `student` - A reference to a Student.
+    /// * `course` - A reference to a Course.
+    /// * `term` - The term of the enrollment.
+    fn drop_course(
+        &mut self,
+        student: &Student,
+        course: &Course,
+        term: Term,
+    ) -> Result<(), EnrollmentError> {
+        let e = self.active_mut(student, course, term)?;
+        e.status = EnrollmentStatus::Dropped;
+        Ok(())
+    }
+
+    /// Marks an active enrollment as completed and records the grade earned, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `student` - A reference to a Student.
+    /// * `course` - A reference to a Course.
+    /// * `term` - The term of the enrollment.
+    /// * `grade` - The grade earned, or None for ungraded (e.g. pass/fail) courses.
+    fn complete(
+        &mut self,
+        student: &Student,
+        course: &Course,
+        term: Term,
+        grade: Option<Grade>,
+    ) -> Result<(), EnrollmentError> {
+        let e = self.active_mut(student, course, term)?;
+        e.status = EnrollmentStatus::Completed;
+        e.grade = grade;
+        Ok(())
+    }
+
+    /// Returns the index of the active enrollment for the student, course and term, if any.
+    fn find_active(&self, student: &Student, course: &Course, term: Term) -> Option<usize> {
+        self.enrollments.iter().position(|e| {
+            e.student.name == student.name
+                && e.course.name == course.name
+                && e.term == term
+                && e.status == EnrollmentStatus::Active
+        })
+    }
+
+    /// Returns the active enrollment for the student, course and term, or a NotEnrolled error.
+    fn active_mut(
+        &mut self,
+        student: &Student,
+        course: &Course,
+        term: Term,
+    ) -> Result<&mut Enrollment<'a>, EnrollmentError> {
+        match self.find_active(student, course, term) {
+            Some(i) => Ok(&mut self.enrollments[i]),
+            None => Err(EnrollmentError::NotEnrolled {
+                student: student.name.clone(),
+                course: course.name.clone(),
+            }),
+        }
     }
 }
 
-/// Demonstrates the normalized approach by enrolling a student in a course and printing the courses
-/// that the student is taking.
+/// Demonstrates the normalized approach by enrolling a student in courses across terms, grading
+/// them, and printing the resulting transcript, grade distribution and timetable.
 pub fn normalization_demo() {
     println!("Normalization demo:");
     let kenry = Student {
         name: "Kenry".into(),
     };
-    let course = Course {
+    let kary = Student {
+        name: "Kary".into(),
+    };
+    let at = |s: &str| s.parse::<Time>().unwrap();
+    let rust = Course {
         name: "Intro to Rust".into(),
+        meetings: vec![
+            Meeting::new(Weekday::Mon, at("09:00"), at("10:30"), "Room 101"),
+            Meeting::new(Weekday::Wed, at("09:00"), at("10:30"), "Room 101"),
+        ],
+    };
+    let algorithms = Course {
+        name: "Algorithms".into(),
+        meetings: vec![
+            Meeting::new(Weekday::Tue, at("10:00"), at("11:30"), "Room 102"),
+            Meeting::new(Weekday::Thu, at("10:00"), at("11:30"), "Room 102"),
+        ],
+    };
+    let compilers = Course {
+        name: "Compilers".into(),
+        meetings: vec!["Mon, 10:00, 11:30, Room 101".parse().unwrap()],
     };
+    let databases = Course {
+        name: "Databases".into(),
+        meetings: vec!["Wed, 10:00, 12:00, Room 103".parse().unwrap()],
+    };
+    let fall = Term::new(Season::Fall, 2024);
+    let spring = Term::new(Season::Spring, 2025);
+
     let mut p = Platform::new();
-    p.enroll(&kenry, &course);
+    p.add_prerequisite(&algorithms, &rust).unwrap();
+    p.add_prerequisite(&compilers, &algorithms).unwrap();
+    p.add_prerequisite(&compilers, &rust).unwrap();
+    if let Err(e) = p.add_prerequisite(&rust, &compilers) {
+        println!("Error: {}", e);
+    }
+    println!(
+        "Suggested study order for {}: {}",
+        compilers.name,
+        p.study_order(&compilers).join(" -> ")
+    );
 
-    for c in kenry.courses(p) {
+    p.enroll(&kenry, &rust, fall).unwrap();
+    p.enroll(&kary, &rust, fall).unwrap();
+    if let Err(e) = p.enroll(&kenry, &algorithms, fall) {
+        println!("Error: {}", e);
+    }
+
+    for c in kenry.courses(&p) {
         println!("Kenry is taking {}", c);
     }
+
+    if let Err(e) = p.enroll(&kenry, &rust, fall) {
+        println!("Error: {}", e);
+    }
+
+    if let Err(e) = p.enroll(&kenry, &databases, fall) {
+        println!("Error: {}", e);
+    }
+    p.set_conflict_policy(ConflictPolicy::Warn);
+    for conflict in p.enroll(&kenry, &databases, fall).unwrap() {
+        println!("Warning: {}", conflict);
+    }
+    p.set_conflict_policy(ConflictPolicy::Refuse);
+    print!("{}", kenry.timetable(&p, fall));
+    for conflict in p.room_conflicts() {
+        println!("Room double-booked: {}", conflict);
+    }
+    p.drop_course(&kenry, &databases, fall).unwrap();
+
+    p.complete(
+        &kenry,
+        &rust,
+        fall,
+        Some(Grade::Letter(LetterGrade::AMinus)),
+    )
+    .unwrap();
+    p.complete(&kary, &rust, fall, Some(Grade::Numeric(88.5)))
+        .unwrap();
+    p.enroll(&kenry, &algorithms, spring).unwrap();
+    p.enroll(&kary, &algorithms, spring).unwrap();
+    p.complete(&kenry, &algorithms, spring, Some(Grade::Numeric(95.0)))
+        .unwrap();
+    p.drop_course(&kary, &algorithms, spring).unwrap();
+
+    print!("{}", kenry.transcript(&p));
+    print!("{}", kary.transcript(&p));
+
+    for (letter, count) in rust.grade_distribution(&p) {
+        println!("{}: {} x {}", rust.name, letter, count);
+    }
+
+    let queries = [
+        query::Query::students()
+            .enrolled_in("Intro to Rust")
+            .enrolled_in("Algorithms"),
+        query::Query::courses().student_count(query::Cmp::Lt, 2),
+        query::Query::students().without_enrollments(),
+        "avg gpa of students where in \"Intro to Rust\""
+            .parse()
+            .unwrap(),
+    ];
+    for q in &queries {
+        match q.run(&p) {
+            Ok(result) => println!("{}:\n{}", q, result),
+            Err(e) => println!("Error: {}", e),
+        }
+    }
 }
@@ -0,0 +1,586 @@
+//! A small query language over a Platform. Queries can be assembled with the builder methods on
+//! Query, or parsed from text such as:
+//!
+//! ```text
+//! students where in "Intro to Rust" and in "Algorithms"
+//! courses where students < 2
+//! students where has no enrollments
+//! count students where not in "Algorithms"
+//! avg gpa of students where in "Intro to Rust"
+//! ```
+//!
+//! Filters act as joins against the enrollment table: a student is "in" a course when they have an
+//! active or completed (but not dropped) enrollment in it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Course, EnrollmentStatus, Platform, Student};
+use crate::grades;
+
+/// Comparison operators used by numeric filters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cmp {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl Cmp {
+    /// Applies the comparison as `lhs <op> rhs`.
+    fn test<T: PartialOrd>(self, lhs: T, rhs: T) -> bool {
+        match self {
+            Cmp::Lt => lhs < rhs,
+            Cmp::Le => lhs <= rhs,
+            Cmp::Eq => lhs == rhs,
+            Cmp::Ge => lhs >= rhs,
+            Cmp::Gt => lhs > rhs,
+        }
+    }
+}
+
+impl fmt::Display for Cmp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Cmp::Lt => "<",
+            Cmp::Le => "<=",
+            Cmp::Eq => "=",
+            Cmp::Ge => ">=",
+            Cmp::Gt => ">",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// The kind of row a query returns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target {
+    Students,
+    Courses,
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Target::Students => write!(f, "students"),
+            Target::Courses => write!(f, "courses"),
+        }
+    }
+}
+
+/// A single condition a row must satisfy.
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    /// Students enrolled in the named course.
+    EnrolledIn(String),
+    /// Students not enrolled in the named course.
+    NotEnrolledIn(String),
+    /// Students or courses without any enrollment.
+    NoEnrollments,
+    /// Students whose GPA satisfies the comparison. Students without grades never match.
+    Gpa(Cmp, f64),
+    /// Courses the named student is enrolled in.
+    HasStudent(String),
+    /// Courses whose number of enrolled students satisfies the comparison.
+    StudentCount(Cmp, usize),
+}
+
+impl Filter {
+    /// Returns whether this filter can be applied to the given target.
+    fn applies_to(&self, target: Target) -> bool {
+        match self {
+            Filter::NoEnrollments => true,
+            Filter::EnrolledIn(_) | Filter::NotEnrolledIn(_) | Filter::Gpa(_, _) => {
+                target == Target::Students
+            }
+            Filter::HasStudent(_) | Filter::StudentCount(_, _) => target == Target::Courses,
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Filter::EnrolledIn(c) => write!(f, "in \"{}\"", c),
+            Filter::NotEnrolledIn(c) => write!(f, "not in \"{}\"", c),
+            Filter::NoEnrollments => write!(f, "has no enrollments"),
+            Filter::Gpa(cmp, v) => write!(f, "gpa {} {}", cmp, v),
+            Filter::HasStudent(s) => write!(f, "has student \"{}\"", s),
+            Filter::StudentCount(cmp, n) => write!(f, "students {} {}", cmp, n),
+        }
+    }
+}
+
+/// What to compute from the matching rows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Aggregate {
+    /// List the names of the matching rows.
+    List,
+    /// Count the matching rows.
+    Count,
+    /// Average GPA: over each matching student's GPA, or over every grade given in the
+    /// matching courses.
+    AverageGpa,
+}
+
+/// A query over the students or courses of a Platform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    target: Target,
+    filters: Vec<Filter>,
+    aggregate: Aggregate,
+}
+
+/// Renders the query back in the text syntax accepted by `Query::from_str`.
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.aggregate {
+            Aggregate::List => {}
+            Aggregate::Count => write!(f, "count ")?,
+            Aggregate::AverageGpa => write!(f, "avg gpa of ")?,
+        }
+        write!(f, "{}", self.target)?;
+        for (i, filter) in self.filters.iter().enumerate() {
+            let sep = if i == 0 { "where" } else { "and" };
+            write!(f, " {} {}", sep, filter)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of running a Query.
+#[derive(Debug, PartialEq)]
+pub enum QueryResult {
+    Names(Vec<String>),
+    Count(usize),
+    Average(Option<f64>),
+}
+
+impl fmt::Display for QueryResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryResult::Names(names) if names.is_empty() => write!(f, "(no results)"),
+            QueryResult::Names(names) => write!(f, "{}", names.join("\n")),
+            QueryResult::Count(n) => write!(f, "{}", n),
+            QueryResult::Average(Some(avg)) => write!(f, "{:.2}", avg),
+            QueryResult::Average(None) => write!(f, "n/a"),
+        }
+    }
+}
+
+/// Errors produced while parsing or running a query.
+#[derive(Debug, PartialEq)]
+pub enum QueryError {
+    /// The query text does not follow the grammar.
+    Parse(String),
+    /// A filter was used on a target it does not apply to, e.g. `courses where gpa > 3`.
+    InvalidFilter { filter: String, target: String },
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryError::Parse(msg) => write!(f, "parse error: {}", msg),
+            QueryError::InvalidFilter { filter, target } => {
+                write!(f, "filter '{}' cannot be applied to {}", filter, target)
+            }
+        }
+    }
+}
+
+impl Query {
+    /// Starts a query that returns students.
+    pub fn students() -> Query {
+        Query {
+            target: Target::Students,
+            filters: Vec::new(),
+            aggregate: Aggregate::List,
+        }
+    }
+
+    /// Starts a query that returns courses.
+    pub fn courses() -> Query {
+        Query {
+            target: Target::Courses,
+            filters: Vec::new(),
+            aggregate: Aggregate::List,
+        }
+    }
+
+    /// Keeps students enrolled in the given course.
+    pub fn enrolled_in(mut self, course: &str) -> Query {
+        self.filters.push(Filter::EnrolledIn(course.into()));
+        self
+    }
+
+    /// Keeps students not enrolled in the given course.
+    pub fn not_enrolled_in(mut self, course: &str) -> Query {
+        self.filters.push(Filter::NotEnrolledIn(course.into()));
+        self
+    }
+
+    /// Keeps students or courses that have no enrollments.
+    pub fn without_enrollments(mut self) -> Query {
+        self.filters.push(Filter::NoEnrollments);
+        self
+    }
+
+    /// Keeps students whose GPA satisfies `gpa <cmp> value`.
+    pub fn gpa(mut self, cmp: Cmp, value: f64) -> Query {
+        self.filters.push(Filter::Gpa(cmp, value));
+        self
+    }
+
+    /// Keeps courses the given student is enrolled in.
+    pub fn with_student(mut self, student: &str) -> Query {
+        self.filters.push(Filter::HasStudent(student.into()));
+        self
+    }
+
+    /// Keeps courses whose enrolled student count satisfies `count <cmp> value`.
+    pub fn student_count(mut self, cmp: Cmp, value: usize) -> Query {
+        self.filters.push(Filter::StudentCount(cmp, value));
+        self
+    }
+
+    /// Returns the number of matching rows instead of their names.
+    pub fn count(mut self) -> Query {
+        self.aggregate = Aggregate::Count;
+        self
+    }
+
+    /// Returns the average GPA of the matching rows instead of their names.
+    pub fn average_gpa(mut self) -> Query {
+        self.aggregate = Aggregate::AverageGpa;
+        self
+    }
+
+    /// Runs the query against a platform.
+    ///
+    /// # Arguments
+    ///
+    /// * `platform` - The Platform whose students, courses and enrollments are queried.
+    pub(super) fn run(&self, platform: &Platform) -> Result<QueryResult, QueryError> {
+        if let Some(f) = self.filters.iter().find(|f| !f.applies_to(self.target)) {
+            return Err(QueryError::InvalidFilter {
+                filter: f.to_string(),
+                target: self.target.to_string(),
+            });
+        }
+
+        match self.target {
+            Target::Students => {
+                let rows: Vec<&Student> = platform
+                    .students
+                    .iter()
+                    .copied()
+                    .filter(|s| self.filters.iter().all(|f| student_matches(platform, s, f)))
+                    .collect();
+                Ok(match self.aggregate {
+                    Aggregate::List => {
+                        QueryResult::Names(rows.iter().map(|s| s.name.clone()).collect())
+                    }
+                    Aggregate::Count => QueryResult::Count(rows.len()),
+                    Aggregate::AverageGpa => {
+                        let gpas: Vec<f64> = rows.iter().filter_map(|s| s.gpa(platform)).collect();
+                        QueryResult::Average(average(&gpas))
+                    }
+                })
+            }
+            Target::Courses => {
+                let rows: Vec<&Course> = platform
+                    .courses
+                    .iter()
+                    .copied()
+                    .filter(|c| self.filters.iter().all(|f| course_matches(platform, c, f)))
+                    .collect();
+                Ok(match self.aggregate {
+                    Aggregate::List => {
+                        QueryResult::Names(rows.iter().map(|c| c.name.clone()).collect())
+                    }
+                    Aggregate::Count => QueryResult::Count(rows.len()),
+                    Aggregate::AverageGpa => QueryResult::Average(grades::gpa(
+                        platform
+                            .enrollments
+                            .iter()
+                            .filter(|e| {
+                                e.status == EnrollmentStatus::Completed
+                                    && rows.iter().any(|c| c.name == e.course.name)
+                            })
+                            .filter_map(|e| e.grade.as_ref()),
+                    )),
+                })
+            }
+        }
+    }
+}
+
+/// Returns whether the student has an active or completed enrollment in the named course.
+fn is_enrolled(platform: &Platform, student: &str, course: &str) -> bool {
+    platform.enrollments.iter().any(|e| {
+        e.student.name == student
+            && e.course.name == course
+            && e.status != EnrollmentStatus::Dropped
+    })
+}
+
+/// Returns the names of the students with an active or completed enrollment in the course.
+fn enrolled_students<'p>(platform: &'p Platform, course: &str) -> Vec<&'p str> {
+    let mut names: Vec<&str> = platform
+        .enrollments
+        .iter()
+        .filter(|e| e.course.name == course && e.status != EnrollmentStatus::Dropped)
+        .map(|e| e.student.name.as_str())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn student_matches(platform: &Platform, student: &Student, filter: &Filter) -> bool {
+    match filter {
+        Filter::EnrolledIn(c) => is_enrolled(platform, &student.name, c),
+        Filter::NotEnrolledIn(c) => !is_enrolled(platform, &student.name, c),
+        Filter::NoEnrollments => !platform
+            .enrollments
+            .iter()
+            .any(|e| e.student.name == student.name && e.status != EnrollmentStatus::Dropped),
+        Filter::Gpa(cmp, v) => student.gpa(platform).is_some_and(|g| cmp.test(g, *v)),
+        Filter::HasStudent(_) | Filter::StudentCount(_, _) => false,
+    }
+}
+
+fn course_matches(platform: &Platform, course: &Course, filter: &Filter) -> bool {
+    match filter {
+        Filter::NoEnrollments => enrolled_students(platform, &course.name).is_empty(),
+        Filter::HasStudent(s) => is_enrolled(platform, s, &course.name),
+        Filter::StudentCount(cmp, n) => {
+            cmp.test(enrolled_students(platform, &course.name).len(), *n)
+        }
+        Filter::EnrolledIn(_) | Filter::NotEnrolledIn(_) | Filter::Gpa(_, _) => false,
+    }
+}
+
+fn average(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+/// A lexical token of the query language.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A bare keyword, lowercased.
+    Word(String),
+    /// A double-quoted name.
+    Str(String),
+    Num(f64),
+    Op(Cmp),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Word(w) => write!(f, "'{}'", w),
+            Token::Str(s) => write!(f, "\"{}\"", s),
+            Token::Num(n) => write!(f, "{}", n),
+            Token::Op(op) => write!(f, "'{}'", op),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => s.push(c),
+                    None => return Err(QueryError::Parse("unterminated string".into())),
+                }
+            }
+            tokens.push(Token::Str(s));
+        } else if c == '<' || c == '>' || c == '=' {
+            chars.next();
+            let eq = chars.next_if_eq(&'=').is_some();
+            tokens.push(Token::Op(match (c, eq) {
+                ('<', false) => Cmp::Lt,
+                ('<', true) => Cmp::Le,
+                ('>', false) => Cmp::Gt,
+                ('>', true) => Cmp::Ge,
+                _ => Cmp::Eq,
+            }));
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut s = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                s.push(c);
+            }
+            let n = s
+                .parse()
+                .map_err(|_| QueryError::Parse(format!("invalid number '{}'", s)))?;
+            tokens.push(Token::Num(n));
+        } else if c.is_alphabetic() {
+            let mut s = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                s.push(c.to_ascii_lowercase());
+            }
+            tokens.push(Token::Word(s));
+        } else {
+            return Err(QueryError::Parse(format!("unexpected character '{}'", c)));
+        }
+    }
+    Ok(tokens)
+}
+
+/// A recursive-descent parser over the token stream.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    /// Consumes the next token if it is the given keyword.
+    fn eat(&mut self, word: &str) -> bool {
+        if matches!(self.tokens.get(self.pos), Some(Token::Word(w)) if w == word) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, word: &str) -> Result<(), QueryError> {
+        if self.eat(word) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{}'", word)))
+        }
+    }
+
+    fn string(&mut self) -> Result<String, QueryError> {
+        match self.next() {
+            Some(Token::Str(s)) => Ok(s),
+            _ => {
+                self.pos -= 1;
+                Err(self.unexpected("a quoted name"))
+            }
+        }
+    }
+
+    fn comparison(&mut self) -> Result<(Cmp, f64), QueryError> {
+        let cmp = match self.next() {
+            Some(Token::Op(op)) => op,
+            _ => {
+                self.pos -= 1;
+                return Err(self.unexpected("a comparison operator"));
+            }
+        };
+        match self.next() {
+            Some(Token::Num(n)) => Ok((cmp, n)),
+            _ => {
+                self.pos -= 1;
+                Err(self.unexpected("a number"))
+            }
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> QueryError {
+        match self.tokens.get(self.pos) {
+            Some(t) => QueryError::Parse(format!("expected {}, found {}", expected, t)),
+            None => QueryError::Parse(format!("expected {}, found end of query", expected)),
+        }
+    }
+
+    fn query(&mut self) -> Result<Query, QueryError> {
+        let aggregate = if self.eat("count") {
+            Aggregate::Count
+        } else if self.eat("avg") {
+            self.expect("gpa")?;
+            self.expect("of")?;
+            Aggregate::AverageGpa
+        } else {
+            Aggregate::List
+        };
+
+        let mut query = if self.eat("students") {
+            Query::students()
+        } else if self.eat("courses") {
+            Query::courses()
+        } else {
+            return Err(self.unexpected("'students' or 'courses'"));
+        };
+        query.aggregate = aggregate;
+
+        if self.eat("where") {
+            loop {
+                query.filters.push(self.filter()?);
+                if !self.eat("and") {
+                    break;
+                }
+            }
+        }
+        if self.pos < self.tokens.len() {
+            return Err(self.unexpected("'and' or end of query"));
+        }
+        Ok(query)
+    }
+
+    fn filter(&mut self) -> Result<Filter, QueryError> {
+        if self.eat("in") {
+            Ok(Filter::EnrolledIn(self.string()?))
+        } else if self.eat("not") {
+            self.expect("in")?;
+            Ok(Filter::NotEnrolledIn(self.string()?))
+        } else if self.eat("has") {
+            if self.eat("no") {
+                self.expect("enrollments")?;
+                Ok(Filter::NoEnrollments)
+            } else {
+                self.expect("student")?;
+                Ok(Filter::HasStudent(self.string()?))
+            }
+        } else if self.eat("gpa") {
+            let (cmp, v) = self.comparison()?;
+            Ok(Filter::Gpa(cmp, v))
+        } else if self.eat("students") {
+            let (cmp, v) = self.comparison()?;
+            if v < 0.0 || v.fract() != 0.0 {
+                return Err(QueryError::Parse(format!(
+                    "student count must be a whole number, found {}",
+                    v
+                )));
+            }
+            Ok(Filter::StudentCount(cmp, v as usize))
+        } else {
+            Err(self.unexpected("a condition"))
+        }
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Query, QueryError> {
+        Parser {
+            tokens: tokenize(s)?,
+            pos: 0,
+        }
+        .query()
+    }
+}
@@ -0,0 +1,269 @@
+//! An interactive prompt for running queries against a dataset loaded from a text file.
+//!
+//! A dataset lists one record per line; blank lines and lines starting with `#` are ignored:
+//!
+//! ```text
+//! student Kenry
+//! course Intro to Rust
+//! meeting Intro to Rust, Mon, 09:00, 10:30, Room 101
+//! prerequisite Algorithms, Intro to Rust
+//! enroll Kenry, Intro to Rust, Fall 2024
+//! complete Kenry, Intro to Rust, Fall 2024, A-
+//! drop Kenry, Algorithms, Spring 2025
+//! ```
+//!
+//! Students and courses are owned by the Dataset, and the Platform built from it borrows them,
+//! mirroring how the normalization demo works with references.
+
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+use super::query::Query;
+use super::{Course, Platform, Student};
+use crate::grades::Term;
+
+/// A relationship record from a dataset file, referring to students and courses by name.
+enum Record {
+    Prerequisite {
+        course: String,
+        requires: String,
+    },
+    Enroll {
+        student: String,
+        course: String,
+        term: Term,
+    },
+    Complete {
+        student: String,
+        course: String,
+        term: Term,
+        grade: Option<String>,
+    },
+    Drop {
+        student: String,
+        course: String,
+        term: Term,
+    },
+}
+
+/// The contents of a dataset file: the students and courses it declares, plus the records that
+/// relate them, each tagged with its line number for error reporting.
+struct Dataset {
+    students: Vec<Student>,
+    courses: Vec<Course>,
+    records: Vec<(usize, Record)>,
+}
+
+impl Dataset {
+    /// Parses a dataset from its text contents.
+    fn parse(text: &str) -> Result<Dataset, String> {
+        let mut dataset = Dataset {
+            students: Vec::new(),
+            courses: Vec::new(),
+            records: Vec::new(),
+        };
+        for (i, line) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let fields: Vec<&str> = rest.split(',').map(str::trim).collect();
+            let term = |i: usize| -> Result<Term, String> {
+                fields
+                    .get(i)
+                    .ok_or_else(|| "missing term".to_string())?
+                    .parse()
+            };
+            let record = match (keyword, fields.len()) {
+                ("student", 1) if !rest.is_empty() => {
+                    dataset.students.push(Student {
+                        name: rest.trim().into(),
+                    });
+                    continue;
+                }
+                ("course", 1) if !rest.is_empty() => {
+                    dataset.courses.push(Course {
+                        name: rest.trim().into(),
+                        meetings: Vec::new(),
+                    });
+                    continue;
+                }
+                ("meeting", 5) => {
+                    let (course, meeting) = rest.split_once(',').unwrap();
+                    let meeting = meeting
+                        .parse()
+                        .map_err(|e| format!("line {}: {}", line_no, e))?;
+                    match dataset.courses.iter_mut().find(|c| c.name == course.trim()) {
+                        Some(c) => c.meetings.push(meeting),
+                        None => {
+                            return Err(format!(
+                                "line {}: unknown course '{}'",
+                                line_no,
+                                course.trim()
+                            ));
+                        }
+                    }
+                    continue;
+                }
+                ("prerequisite", 2) => Record::Prerequisite {
+                    course: fields[0].into(),
+                    requires: fields[1].into(),
+                },
+                ("enroll", 3) => Record::Enroll {
+                    student: fields[0].into(),
+                    course: fields[1].into(),
+                    term: term(2).map_err(|e| format!("line {}: {}", line_no, e))?,
+                },
+                ("complete", 3 | 4) => Record::Complete {
+                    student: fields[0].into(),
+                    course: fields[1].into(),
+                    term: term(2).map_err(|e| format!("line {}: {}", line_no, e))?,
+                    grade: fields.get(3).map(|g| g.to_string()),
+                },
+                ("drop", 3) => Record::Drop {
+                    student: fields[0].into(),
+                    course: fields[1].into(),
+                    term: term(2).map_err(|e| format!("line {}: {}", line_no, e))?,
+                },
+                _ => return Err(format!("line {}: cannot understand '{}'", line_no, line)),
+            };
+            dataset.records.push((line_no, record));
+        }
+        Ok(dataset)
+    }
+
+    fn student(&self, name: &str) -> Result<&Student, String> {
+        self.students
+            .iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| format!("unknown student '{}'", name))
+    }
+
+    fn course(&self, name: &str) -> Result<&Course, String> {
+        self.courses
+            .iter()
+            .find(|c| c.name == name)
+            .ok_or_else(|| format!("unknown course '{}'", name))
+    }
+
+    /// Builds a Platform borrowing this dataset's students and courses and replays every record
+    /// against it, so the same validation (prerequisites, duplicate enrollments) applies.
+    fn platform(&self) -> Result<Platform<'_>, String> {
+        let mut p = Platform::new();
+        for s in &self.students {
+            p.add_student(s);
+        }
+        for c in &self.courses {
+            p.add_course(c);
+        }
+        for (line_no, record) in &self.records {
+            self.replay(&mut p, record)
+                .map_err(|e| format!("line {}: {}", line_no, e))?;
+        }
+        Ok(p)
+    }
+
+    /// Applies one record to the platform. Every failure, including a name the dataset does not
+    /// define, is returned so the caller can report the record's line.
+    fn replay<'a>(&'a self, p: &mut Platform<'a>, record: &Record) -> Result<(), String> {
+        match record {
+            Record::Prerequisite { course, requires } => p
+                .add_prerequisite(self.course(course)?, self.course(requires)?)
+                .map_err(|e| e.to_string()),
+            Record::Enroll {
+                student,
+                course,
+                term,
+            } => p
+                .enroll(self.student(student)?, self.course(course)?, *term)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Record::Complete {
+                student,
+                course,
+                term,
+                grade,
+            } => {
+                let grade = grade.as_deref().map(str::parse).transpose()?;
+                p.complete(self.student(student)?, self.course(course)?, *term, grade)
+                    .map_err(|e| e.to_string())
+            }
+            Record::Drop {
+                student,
+                course,
+                term,
+            } => p
+                .drop_course(self.student(student)?, self.course(course)?, *term)
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+const HELP: &str = r#"Queries:
+  [count | avg gpa of] students|courses [where <condition> {and <condition>}]
+Student conditions:
+  in "Course"    not in "Course"    has no enrollments    gpa <op> <number>
+Course conditions:
+  has student "Name"    students <op> <number>    has no enrollments
+Operators: <  <=  =  >=  >
+Type 'help' to see this message and 'quit' to exit."#;
+
+/// Loads the dataset at `path` and reads queries from standard input until EOF or `quit`,
+/// printing each result or error.
+///
+/// # Arguments
+///
+/// * `path` - Path to a dataset file.
+pub fn run(path: &str) {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("Cannot read {}: {}", path, e);
+            return;
+        }
+    };
+    let dataset = match Dataset::parse(&text) {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("Invalid dataset {}: {}", path, e);
+            return;
+        }
+    };
+    let platform = match dataset.platform() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Invalid dataset {}: {}", path, e);
+            return;
+        }
+    };
+
+    println!(
+        "Loaded {} students, {} courses and {} enrollments from {}",
+        platform.students.len(),
+        platform.courses.len(),
+        platform.enrollments.len(),
+        path
+    );
+    println!("{}", HELP);
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        match line.trim() {
+            "" => continue,
+            "quit" | "exit" => break,
+            "help" => println!("{}", HELP),
+            input => match input.parse::<Query>().and_then(|q| q.run(&platform)) {
+                Ok(result) => println!("{}", result),
+                Err(e) => println!("Error: {}", e),
+            },
+        }
+    }
+}
@@ -0,0 +1,647 @@
+//! Event sourcing stores every change to the platform as an entry in an append-only log instead of
+//! only keeping the latest state. The current state is just the result of replaying the log, which
+//! gives us an audit trail, undo/redo, and the ability to ask what the platform looked like at any
+//! earlier date.
+//!
+//! Each log entry records the low-level changes it made together with the changes that reverse
+//! them, worked out at the time the command ran (e.g. deleting a student also records every
+//! enrollment needed to restore them). Undo and redo never rewrite history: they append a new entry
+//! that applies the stored inverse (or re-applies the original changes). Replaying the log
+//! therefore always reproduces the state, and the undo/redo stacks can be rebuilt from it too.
+//!
+//! Replaying a long log is slow, so a snapshot of the state is kept every N entries; rebuilding a
+//! past state starts from the closest snapshot at or before it. New entries are applied to the
+//! current state in place; if one of their changes is rejected, the state is rebuilt from the
+//! latest snapshot instead of copying the whole platform before every entry.
+//!
+//! A log read back from storage is not trusted: replaying it checks each entry's sequence number
+//! and date, that every command's inverse really reverses its changes, and that every undo or redo
+//! entry reverses or re-applies exactly the entry the stacks say it should.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::shared_platform::{SharedPlatform, SharedPlatformError};
+
+/// A calendar date, ordered chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    /// Creates a new Date. The fields are not checked against the calendar; see `checked`.
+    pub fn new(year: u16, month: u8, day: u8) -> Date {
+        Date { year, month, day }
+    }
+
+    /// Creates a new Date, or returns None if the day does not exist in the Gregorian calendar.
+    pub fn checked(year: u16, month: u8, day: u8) -> Option<Date> {
+        ((1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day))
+            .then(|| Date::new(year, month, day))
+    }
+
+    /// Whether the date exists in the Gregorian calendar.
+    pub fn is_valid(&self) -> bool {
+        Date::checked(self.year, self.month, self.day).is_some()
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    /// Parses an ISO 8601 date such as "2024-09-01".
+    fn from_str(s: &str) -> Result<Date, String> {
+        let parts: Vec<&str> = s.trim().split('-').collect();
+        let invalid = || format!("invalid date '{}': expected YYYY-MM-DD", s);
+        if parts.len() != 3 {
+            return Err(invalid());
+        }
+        let year = parts[0].parse().map_err(|_| invalid())?;
+        let month = parts[1].parse().map_err(|_| invalid())?;
+        let day = parts[2].parse().map_err(|_| invalid())?;
+        Date::checked(year, month, day).ok_or_else(invalid)
+    }
+}
+
+/// The number of days in a month of the Gregorian calendar.
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// A single primitive change to the platform.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    CreateStudent { name: String },
+    DeleteStudent { name: String },
+    CreateCourse { name: String, capacity: usize },
+    DeleteCourse { name: String },
+    Enroll { student: String, course: String },
+    Unenroll { student: String, course: String },
+}
+
+impl Change {
+    /// Applies the change to a platform.
+    fn apply(&self, platform: &SharedPlatform) -> Result<(), SharedPlatformError> {
+        match self {
+            Change::CreateStudent { name } => platform.add_student(name),
+            Change::DeleteStudent { name } => platform.remove_student(name),
+            Change::CreateCourse { name, capacity } => platform.add_course(name, *capacity),
+            Change::DeleteCourse { name } => platform.remove_course(name),
+            Change::Enroll { student, course } => platform.enroll(student, course),
+            Change::Unenroll { student, course } => platform.unenroll(student, course),
+        }
+    }
+}
+
+/// Why an entry was appended to the log.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryKind {
+    /// A regular command such as enroll or create course.
+    Command,
+    /// Reverses the entry with the given sequence number.
+    Undo { of: usize },
+    /// Re-applies the entry with the given sequence number after it was undone.
+    Redo { of: usize },
+}
+
+/// One entry of the append-only log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// Position of the entry in the log, starting at 0.
+    pub seq: usize,
+    /// The date the entry took effect.
+    pub on: Date,
+    pub kind: EntryKind,
+    /// The changes applied, in order.
+    pub changes: Vec<Change>,
+    /// The changes that reverse `changes`, in order.
+    pub inverse: Vec<Change>,
+}
+
+/// Errors returned by EventSourcedPlatform operations.
+#[derive(Debug, PartialEq)]
+pub enum EventError {
+    /// The change was rejected by the platform; nothing was recorded.
+    Platform(SharedPlatformError),
+    /// Entries must be appended in chronological order.
+    OutOfOrder {
+        last: Date,
+        given: Date,
+    },
+    NothingToUndo,
+    NothingToRedo,
+    /// The replayed entry at position `seq` is inconsistent with the log before it, e.g. an undo of
+    /// the wrong entry.
+    InvalidEntry {
+        seq: usize,
+        reason: &'static str,
+    },
+}
+
+impl From<SharedPlatformError> for EventError {
+    fn from(e: SharedPlatformError) -> EventError {
+        EventError::Platform(e)
+    }
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EventError::Platform(e) => write!(f, "{}", e),
+            EventError::OutOfOrder { last, given } => {
+                write!(f, "cannot record {} after an entry dated {}", given, last)
+            }
+            EventError::NothingToUndo => write!(f, "nothing to undo"),
+            EventError::NothingToRedo => write!(f, "nothing to redo"),
+            EventError::InvalidEntry { seq, reason } => {
+                write!(f, "invalid log entry #{}: {}", seq, reason)
+            }
+        }
+    }
+}
+
+/// A platform whose state is derived from an append-only event log.
+pub struct EventSourcedPlatform {
+    events: Vec<Event>,
+    current: SharedPlatform,
+    /// Snapshots of the state after the first `n` entries, as (n, state), in increasing order.
+    snapshots: Vec<(usize, SharedPlatform)>,
+    snapshot_every: usize,
+    /// Sequence numbers of entries that can be undone, most recent last.
+    undo_stack: Vec<usize>,
+    /// Sequence numbers of undone entries that can be redone, most recent last.
+    redo_stack: Vec<usize>,
+}
+
+impl EventSourcedPlatform {
+    /// Creates an empty platform that snapshots its state every `snapshot_every` entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot_every` - How many entries to record between snapshots; must be at least 1.
+    pub fn new(snapshot_every: usize) -> EventSourcedPlatform {
+        assert!(snapshot_every > 0, "snapshot_every must be at least 1");
+        EventSourcedPlatform {
+            events: Vec::new(),
+            current: SharedPlatform::new(),
+            snapshots: vec![(0, SharedPlatform::new())],
+            snapshot_every,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+
+    /// Rebuilds a platform by replaying a previously recorded log from the beginning.
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The log to replay, in order.
+    /// * `snapshot_every` - How many entries to record between snapshots.
+    pub fn replay(
+        events: Vec<Event>,
+        snapshot_every: usize,
+    ) -> Result<EventSourcedPlatform, EventError> {
+        let mut platform = EventSourcedPlatform::new(snapshot_every);
+        for event in events {
+            platform.append(event)?;
+        }
+        Ok(platform)
+    }
+
+    /// The recorded log.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// The current state. Use it for reads only: changes made through it bypass the log.
+    pub fn current(&self) -> &SharedPlatform {
+        &self.current
+    }
+
+    /// Checks that an entry can follow the log: its sequence number and date, for commands, that
+    /// the inverse reverses the changes, and for undo and redo entries, that they target the top
+    /// of the matching stack and carry that entry's changes.
+    fn validate(&self, event: &Event) -> Result<(), EventError> {
+        let invalid = |reason| {
+            Err(EventError::InvalidEntry {
+                seq: self.events.len(),
+                reason,
+            })
+        };
+        if event.seq != self.events.len() {
+            return invalid("sequence number does not match its position in the log");
+        }
+        if !event.on.is_valid() {
+            return invalid("date does not exist in the calendar");
+        }
+        if let Some(last) = self.events.last()
+            && event.on < last.on
+        {
+            return Err(EventError::OutOfOrder {
+                last: last.on,
+                given: event.on,
+            });
+        }
+        let (of, stack, reverses) = match event.kind {
+            EntryKind::Command => {
+                // Deleted courses are restored with the capacity they have before the entry.
+                let inverse = inverse_of(&event.changes, |c| match c {
+                    Change::DeleteStudent { name } => {
+                        Ok(Change::CreateStudent { name: name.clone() })
+                    }
+                    Change::DeleteCourse { name } => {
+                        self.current
+                            .course(name)
+                            .map(|course| Change::CreateCourse {
+                                name: name.clone(),
+                                capacity: course.capacity,
+                            })
+                    }
+                    _ => unreachable!(),
+                })?;
+                if event.inverse != inverse {
+                    return invalid("inverse does not reverse the entry's changes");
+                }
+                return Ok(());
+            }
+            EntryKind::Undo { of } => (of, &self.undo_stack, true),
+            EntryKind::Redo { of } => (of, &self.redo_stack, false),
+        };
+        if stack.last() != Some(&of) {
+            return invalid("undo or redo of an entry that is not next in line");
+        }
+        let target = &self.events[of];
+        let (changes, inverse) = if reverses {
+            (&target.inverse, &target.changes)
+        } else {
+            (&target.changes, &target.inverse)
+        };
+        if event.changes != *changes || event.inverse != *inverse {
+            return invalid("changes differ from the entry it undoes or redoes");
+        }
+        Ok(())
+    }
+
+    /// Validates, applies and records an entry, then updates the undo/redo stacks and snapshots.
+    fn append(&mut self, event: Event) -> Result<(), EventError> {
+        self.validate(&event)?;
+        for change in &event.changes {
+            if let Err(e) = change.apply(&self.current) {
+                // Earlier changes of this entry were already applied; rebuild the state from the
+                // log so a rejected entry leaves no trace.
+                self.current = self.state_after(self.events.len())?;
+                return Err(e.into());
+            }
+        }
+
+        match event.kind {
+            EntryKind::Command => {
+                self.undo_stack.push(event.seq);
+                self.redo_stack.clear();
+            }
+            EntryKind::Undo { of } => {
+                self.undo_stack.pop();
+                self.redo_stack.push(of);
+            }
+            EntryKind::Redo { of } => {
+                self.redo_stack.pop();
+                self.undo_stack.push(of);
+            }
+        }
+        self.events.push(event);
+        if self.events.len().is_multiple_of(self.snapshot_every) {
+            self.snapshots
+                .push((self.events.len(), self.current.fork()));
+        }
+        Ok(())
+    }
+
+    /// Records a command made of `changes`, which are reversed by `inverse`.
+    fn command(
+        &mut self,
+        on: Date,
+        changes: Vec<Change>,
+        inverse: Vec<Change>,
+    ) -> Result<(), EventError> {
+        let event = Event {
+            seq: self.events.len(),
+            on,
+            kind: EntryKind::Command,
+            changes,
+            inverse,
+        };
+        self.append(event)
+    }
+
+    /// Registers a new student.
+    pub fn add_student(&mut self, on: Date, name: &str) -> Result<(), EventError> {
+        self.command(
+            on,
+            vec![Change::CreateStudent { name: name.into() }],
+            vec![Change::DeleteStudent { name: name.into() }],
+        )
+    }
+
+    /// Removes a student. Their enrollments are recorded as explicit unenrollments first, so that
+    /// undoing the removal can restore them.
+    pub fn remove_student(&mut self, on: Date, name: &str) -> Result<(), EventError> {
+        let courses = self.current.courses_of(name)?;
+        let mut changes: Vec<Change> = courses
+            .iter()
+            .map(|c| Change::Unenroll {
+                student: name.into(),
+                course: c.clone(),
+            })
+            .collect();
+        changes.push(Change::DeleteStudent { name: name.into() });
+        let inverse = inverse_of(&changes, |c| match c {
+            Change::DeleteStudent { name } => Ok(Change::CreateStudent { name: name.clone() }),
+            _ => unreachable!(),
+        })?;
+        self.command(on, changes, inverse)
+    }
+
+    /// Registers a new course with the given capacity.
+    pub fn add_course(&mut self, on: Date, name: &str, capacity: usize) -> Result<(), EventError> {
+        self.command(
+            on,
+            vec![Change::CreateCourse {
+                name: name.into(),
+                capacity,
+            }],
+            vec![Change::DeleteCourse { name: name.into() }],
+        )
+    }
+
+    /// Removes a course. Its roster is recorded as explicit unenrollments first, so that undoing
+    /// the removal can restore it.
+    pub fn remove_course(&mut self, on: Date, name: &str) -> Result<(), EventError> {
+        let course = self.current.course(name)?;
+        let mut changes: Vec<Change> = course
+            .students
+            .iter()
+            .map(|s| Change::Unenroll {
+                student: s.clone(),
+                course: name.into(),
+            })
+            .collect();
+        changes.push(Change::DeleteCourse { name: name.into() });
+        let inverse = inverse_of(&changes, |c| match c {
+            Change::DeleteCourse { name } => Ok(Change::CreateCourse {
+                name: name.clone(),
+                capacity: course.capacity,
+            }),
+            _ => unreachable!(),
+        })?;
+        self.command(on, changes, inverse)
+    }
+
+    /// Enrolls a student in a course.
+    pub fn enroll(&mut self, on: Date, student: &str, course: &str) -> Result<(), EventError> {
+        let change = Change::Enroll {
+            student: student.into(),
+            course: course.into(),
+        };
+        let inverse = inverse_of(std::slice::from_ref(&change), |_| unreachable!())?;
+        self.command(on, vec![change], inverse)
+    }
+
+    /// Removes a student from a course.
+    pub fn unenroll(&mut self, on: Date, student: &str, course: &str) -> Result<(), EventError> {
+        let change = Change::Unenroll {
+            student: student.into(),
+            course: course.into(),
+        };
+        let inverse = inverse_of(std::slice::from_ref(&change), |_| unreachable!())?;
+        self.command(on, vec![change], inverse)
+    }
+
+    /// Reverses the most recent command (or redo) that has not been undone yet.
+    pub fn undo(&mut self, on: Date) -> Result<(), EventError> {
+        let &of = self.undo_stack.last().ok_or(EventError::NothingToUndo)?;
+        let target = &self.events[of];
+        let event = Event {
+            seq: self.events.len(),
+            on,
+            kind: EntryKind::Undo { of },
+            changes: target.inverse.clone(),
+            inverse: target.changes.clone(),
+        };
+        self.append(event)
+    }
+
+    /// Re-applies the most recently undone command.
+    pub fn redo(&mut self, on: Date) -> Result<(), EventError> {
+        let &of = self.redo_stack.last().ok_or(EventError::NothingToRedo)?;
+        let target = &self.events[of];
+        let event = Event {
+            seq: self.events.len(),
+            on,
+            kind: EntryKind::Redo { of },
+            changes: target.changes.clone(),
+            inverse: target.inverse.clone(),
+        };
+        self.append(event)
+    }
+
+    /// Rebuilds the state as of the end of `date`, i.e. after every entry dated on or before it.
+    /// Replay starts from the latest snapshot that does not go past that point.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date to rebuild the state for.
+    pub fn state_at(&self, date: Date) -> Result<SharedPlatform, EventError> {
+        self.state_after(self.events.partition_point(|e| e.on <= date))
+    }
+
+    /// Rebuilds the state after the first `upto` entries, starting from the latest snapshot that
+    /// does not go past them. Every recorded entry was applied once already, so replaying one can
+    /// only fail if the log was changed behind the platform's back.
+    fn state_after(&self, upto: usize) -> Result<SharedPlatform, EventError> {
+        let (start, state) = match self.snapshots.iter().rev().find(|(n, _)| *n <= upto) {
+            Some((start, snapshot)) => (*start, snapshot.fork()),
+            None => (0, SharedPlatform::new()),
+        };
+        for event in &self.events[start..upto] {
+            for change in &event.changes {
+                change.apply(&state)?;
+            }
+        }
+        Ok(state)
+    }
+
+    /// Returns who was enrolled in a course at the end of `date`.
+    ///
+    /// # Arguments
+    ///
+    /// * `course` - The course's name.
+    /// * `date` - The date to look at.
+    pub fn enrolled_on(&self, course: &str, date: Date) -> Result<Vec<String>, EventError> {
+        Ok(self.state_at(date)?.students_in(course)?)
+    }
+}
+
+/// Builds the changes that reverse `changes`: each change is inverted and the order is reversed.
+/// Enroll/Unenroll and the Create* changes invert mechanically; `delete` supplies the inverse of the
+/// Delete* changes, which need data (like a course's capacity) that the change itself lacks, and
+/// fails if that data cannot be found.
+fn inverse_of(
+    changes: &[Change],
+    delete: impl Fn(&Change) -> Result<Change, SharedPlatformError>,
+) -> Result<Vec<Change>, SharedPlatformError> {
+    changes
+        .iter()
+        .rev()
+        .map(|c| match c {
+            Change::CreateStudent { name } => Ok(Change::DeleteStudent { name: name.clone() }),
+            Change::CreateCourse { name, .. } => Ok(Change::DeleteCourse { name: name.clone() }),
+            Change::Enroll { student, course } => Ok(Change::Unenroll {
+                student: student.clone(),
+                course: course.clone(),
+            }),
+            Change::Unenroll { student, course } => Ok(Change::Enroll {
+                student: student.clone(),
+                course: course.clone(),
+            }),
+            Change::DeleteStudent { .. } | Change::DeleteCourse { .. } => delete(c),
+        })
+        .collect()
+}
+
+/// Demonstrates the event-sourced platform: records a term's worth of changes, undoes and redoes
+/// some of them, answers point-in-time questions, and checks that replaying the log from scratch
+/// reproduces the same state.
+pub fn event_sourcing_demo() {
+    println!("Event sourcing demo:");
+    let d = |s: &str| s.parse::<Date>().unwrap();
+
+    let mut p = EventSourcedPlatform::new(4);
+    p.add_course(d("2024-08-15"), "Intro to Rust", 2).unwrap();
+    p.add_student(d("2024-08-20"), "Kenry").unwrap();
+    p.add_student(d("2024-08-20"), "Kary").unwrap();
+    p.enroll(d("2024-09-01"), "Kenry", "Intro to Rust").unwrap();
+    p.enroll(d("2024-09-02"), "Kary", "Intro to Rust").unwrap();
+    p.unenroll(d("2024-10-15"), "Kary", "Intro to Rust")
+        .unwrap();
+    p.remove_student(d("2024-11-01"), "Kenry").unwrap();
+
+    if let Err(e) = p.enroll(d("2024-10-01"), "Kary", "Intro to Rust") {
+        println!("Error: {}", e);
+    }
+
+    // Undo the removal of Kenry: their enrollment comes back with them.
+    p.undo(d("2024-11-02")).unwrap();
+    assert_eq!(p.current().students_in("Intro to Rust").unwrap(), ["Kenry"]);
+    // Undo Kary's unenrollment, then change our mind again.
+    p.undo(d("2024-11-03")).unwrap();
+    p.redo(d("2024-11-04")).unwrap();
+    p.redo(d("2024-11-05")).unwrap();
+    assert!(p.current().student("Kenry").is_err());
+    assert_eq!(p.redo(d("2024-11-06")), Err(EventError::NothingToRedo));
+
+    for event in p.events() {
+        println!(
+            "#{} {} {:?}: {:?}",
+            event.seq, event.on, event.kind, event.changes
+        );
+    }
+
+    for date in [
+        "2024-08-31",
+        "2024-09-01",
+        "2024-10-01",
+        "2024-11-02",
+        "2024-11-05",
+    ] {
+        println!(
+            "Enrolled in Intro to Rust on {}: {:?}",
+            date,
+            p.enrolled_on("Intro to Rust", d(date)).unwrap()
+        );
+    }
+
+    let rebuilt = EventSourcedPlatform::replay(p.events().to_vec(), 4).unwrap();
+    assert_eq!(rebuilt.current().students(), p.current().students());
+    assert_eq!(rebuilt.current().courses(), p.current().courses());
+    println!(
+        "Replaying {} events reproduced the current state",
+        p.events().len()
+    );
+
+    // A tampered log is rejected instead of replaying into a wrong state.
+    let rejected_at = |edit: fn(&mut Vec<Event>)| {
+        let mut events = p.events().to_vec();
+        edit(&mut events);
+        match EventSourcedPlatform::replay(events, 4) {
+            Err(EventError::InvalidEntry { seq, .. }) => Some(seq),
+            _ => None,
+        }
+    };
+    assert_eq!(rejected_at(|e| e[3].seq = 7), Some(3));
+    // Entry 7 undoes entry 6; claim it undoes entry 5 instead.
+    assert_eq!(
+        rejected_at(|e| e[7].kind = EntryKind::Undo { of: 5 }),
+        Some(7)
+    );
+    assert_eq!(rejected_at(|e| e[8].changes.clear()), Some(8));
+    // A command whose stored inverse was edited would let a later undo apply anything.
+    assert_eq!(
+        rejected_at(|e| e[3].inverse = vec![Change::DeleteCourse {
+            name: "Intro to Rust".into()
+        }]),
+        Some(3)
+    );
+    assert_eq!(
+        rejected_at(|e| e[6].inverse.retain(|c| !matches!(c, Change::Enroll { .. }))),
+        Some(6)
+    );
+    assert_eq!(rejected_at(|e| e[0].on = Date::new(2024, 2, 30)), Some(0));
+
+    // An entry whose second change is rejected leaves no trace of its first.
+    let seq = p.events().len();
+    let broken = Event {
+        seq,
+        on: d("2024-12-01"),
+        kind: EntryKind::Command,
+        changes: vec![
+            Change::CreateStudent { name: "Kim".into() },
+            Change::Enroll {
+                student: "Kim".into(),
+                course: "Advanced Rust".into(),
+            },
+        ],
+        inverse: vec![
+            Change::Unenroll {
+                student: "Kim".into(),
+                course: "Advanced Rust".into(),
+            },
+            Change::DeleteStudent { name: "Kim".into() },
+        ],
+    };
+    assert!(matches!(p.append(broken), Err(EventError::Platform(_))));
+    assert!(p.current().student("Kim").is_err());
+    assert_eq!(p.events().len(), seq);
+
+    assert!("2024-02-29".parse::<Date>().is_ok());
+    assert!("2023-02-29".parse::<Date>().is_err());
+    assert!("2024-02-31".parse::<Date>().is_err());
+    assert!("2024-04-31".parse::<Date>().is_err());
+    assert_eq!(Date::checked(2024, 13, 1), None);
+    assert!(!Date::new(2023, 2, 29).is_valid());
+}
@@ -0,0 +1,227 @@
+//! This module holds the academic record types that an Enrollment carries: the term it belongs to,
+//! its current status, and the grade a student earned. Letter and numeric grades are both accepted
+//! and are normalized to the usual 4.0 grade-point scale when computing GPAs and distributions.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The part of the academic year a term falls in. Variants are declared in calendar order so the
+/// derived ordering sorts terms within the same year chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Season {
+    Spring,
+    Summer,
+    Fall,
+}
+
+/// An academic term such as "Fall 2024".
+///
+/// `year` is declared before `season` so the derived ordering sorts by year first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Term {
+    pub year: u16,
+    pub season: Season,
+}
+
+impl Term {
+    /// Creates a new Term.
+    ///
+    /// # Arguments
+    ///
+    /// * `season` - The season of the term.
+    /// * `year` - The calendar year of the term.
+    pub fn new(season: Season, year: u16) -> Term {
+        Term { year, season }
+    }
+}
+
+impl FromStr for Term {
+    type Err = String;
+
+    /// Parses a term written as "<Season> <year>", e.g. "Fall 2024".
+    fn from_str(s: &str) -> Result<Term, String> {
+        let mut parts = s.split_whitespace();
+        let season = match parts.next().map(|p| p.to_ascii_lowercase()).as_deref() {
+            Some("spring") => Season::Spring,
+            Some("summer") => Season::Summer,
+            Some("fall") => Season::Fall,
+            _ => return Err(format!("invalid term '{}': expected e.g. 'Fall 2024'", s)),
+        };
+        let year = parts
+            .next()
+            .and_then(|y| y.parse().ok())
+            .ok_or_else(|| format!("invalid term '{}': missing or invalid year", s))?;
+        if parts.next().is_some() {
+            return Err(format!("invalid term '{}': unexpected trailing text", s));
+        }
+        Ok(Term::new(season, year))
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} {}", self.season, self.year)
+    }
+}
+
+/// The lifecycle state of an enrollment record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnrollmentStatus {
+    /// The student is currently taking the course.
+    Active,
+    /// The student left the course before finishing it.
+    Dropped,
+    /// The student finished the course; a grade may be attached.
+    Completed,
+}
+
+/// A letter grade on the plus/minus scale. Variants are declared from best to worst so the
+/// derived ordering places an `A` before an `F`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LetterGrade {
+    A,
+    AMinus,
+    BPlus,
+    B,
+    BMinus,
+    CPlus,
+    C,
+    CMinus,
+    DPlus,
+    D,
+    F,
+}
+
+impl LetterGrade {
+    /// Returns the grade points for this letter on the 4.0 scale.
+    pub fn points(self) -> f64 {
+        match self {
+            LetterGrade::A => 4.0,
+            LetterGrade::AMinus => 3.7,
+            LetterGrade::BPlus => 3.3,
+            LetterGrade::B => 3.0,
+            LetterGrade::BMinus => 2.7,
+            LetterGrade::CPlus => 2.3,
+            LetterGrade::C => 2.0,
+            LetterGrade::CMinus => 1.7,
+            LetterGrade::DPlus => 1.3,
+            LetterGrade::D => 1.0,
+            LetterGrade::F => 0.0,
+        }
+    }
+
+    /// Converts a percentage score (0-100) to a letter using the common US cut-offs.
+    pub fn from_score(score: f64) -> LetterGrade {
+        match score {
+            s if s >= 93.0 => LetterGrade::A,
+            s if s >= 90.0 => LetterGrade::AMinus,
+            s if s >= 87.0 => LetterGrade::BPlus,
+            s if s >= 83.0 => LetterGrade::B,
+            s if s >= 80.0 => LetterGrade::BMinus,
+            s if s >= 77.0 => LetterGrade::CPlus,
+            s if s >= 73.0 => LetterGrade::C,
+            s if s >= 70.0 => LetterGrade::CMinus,
+            s if s >= 67.0 => LetterGrade::DPlus,
+            s if s >= 60.0 => LetterGrade::D,
+            _ => LetterGrade::F,
+        }
+    }
+}
+
+impl fmt::Display for LetterGrade {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            LetterGrade::A => "A",
+            LetterGrade::AMinus => "A-",
+            LetterGrade::BPlus => "B+",
+            LetterGrade::B => "B",
+            LetterGrade::BMinus => "B-",
+            LetterGrade::CPlus => "C+",
+            LetterGrade::C => "C",
+            LetterGrade::CMinus => "C-",
+            LetterGrade::DPlus => "D+",
+            LetterGrade::D => "D",
+            LetterGrade::F => "F",
+        };
+        f.pad(s)
+    }
+}
+
+impl FromStr for LetterGrade {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<LetterGrade, String> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "A" => Ok(LetterGrade::A),
+            "A-" => Ok(LetterGrade::AMinus),
+            "B+" => Ok(LetterGrade::BPlus),
+            "B" => Ok(LetterGrade::B),
+            "B-" => Ok(LetterGrade::BMinus),
+            "C+" => Ok(LetterGrade::CPlus),
+            "C" => Ok(LetterGrade::C),
+            "C-" => Ok(LetterGrade::CMinus),
+            "D+" => Ok(LetterGrade::DPlus),
+            "D" => Ok(LetterGrade::D),
+            "F" => Ok(LetterGrade::F),
+            _ => Err(format!("invalid letter grade '{}'", s)),
+        }
+    }
+}
+
+/// A grade recorded on an enrollment, either as a letter or as a percentage score.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Grade {
+    Letter(LetterGrade),
+    Numeric(f64),
+}
+
+impl Grade {
+    /// Returns the letter equivalent of this grade, converting numeric scores as needed.
+    pub fn letter(&self) -> LetterGrade {
+        match *self {
+            Grade::Letter(l) => l,
+            Grade::Numeric(score) => LetterGrade::from_score(score),
+        }
+    }
+
+    /// Returns the grade points for this grade on the 4.0 scale.
+    pub fn points(&self) -> f64 {
+        self.letter().points()
+    }
+}
+
+impl FromStr for Grade {
+    type Err = String;
+
+    /// Parses either a letter grade ("B+") or a percentage score ("88.5").
+    fn from_str(s: &str) -> Result<Grade, String> {
+        if let Ok(score) = s.trim().parse::<f64>() {
+            if !(0.0..=100.0).contains(&score) {
+                return Err(format!("score {} is outside 0-100", score));
+            }
+            return Ok(Grade::Numeric(score));
+        }
+        s.parse().map(Grade::Letter)
+    }
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Grade::Letter(l) => write!(f, "{}", l),
+            Grade::Numeric(score) => write!(f, "{} ({})", score, self.letter()),
+        }
+    }
+}
+
+/// Averages the grade points of the given grades, returning None when there are no grades.
+pub fn gpa<'g>(grades: impl IntoIterator<Item = &'g Grade>) -> Option<f64> {
+    let (total, count) = grades.into_iter().fold((0.0, 0), |(total, count), g| {
+        (total + g.points(), count + 1)
+    });
+    if count == 0 {
+        None
+    } else {
+        Some(total / count as f64)
+    }
+}
@@ -55,11 +55,15 @@
 
     Best practices:
       - Redesign data structures to avoid circular dependencies when possible.
-      - If circular references are required, consider using Weak pointers to break cycles and prevent
-        potential memory leaks.
+      - If circular references are required, use Weak pointers to break cycles and prevent
+        potential memory leaks (as done below, where students only hold Weak handles to courses).
 */
 #![allow(dead_code)]
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 // We use Rc (Reference Counted pointers) to allow multiple owners of the same data.
 // RefCell provides interior mutability, letting us borrow and modify data even when
@@ -67,11 +71,23 @@ use std::{cell::RefCell, rc::Rc};
 // Student enrolling in multiple Courses and a Course containing multiple Students.
 // Note that this approach defers certain checks to runtime, so it's important to manage
 // borrows carefully to avoid runtime errors.
+//
+// If both sides held strong Rc pointers to each other, every enrollment would create a
+// reference cycle: the course keeps the student alive and the student keeps the course
+// alive, so neither count ever reaches zero and nothing is freed. To break the cycle, the
+// course owns its students (strong Rc) while a student only observes its courses through
+// Weak pointers, which do not contribute to the strong count.
+//
+// Every student and course counts itself when it is created and when it is dropped, so the
+// demo can check that each node it built was freed.
+static NODES_CREATED: AtomicUsize = AtomicUsize::new(0);
+static NODES_DROPPED: AtomicUsize = AtomicUsize::new(0);
 
 struct Student {
     name: String,
-    // Each student holds a list of courses they are enrolled in.
-    courses: Vec<Rc<RefCell<Course>>>,
+    // Each student holds a list of courses they are enrolled in. These are Weak so that
+    // a student never keeps a course alive on its own.
+    courses: Vec<Weak<RefCell<Course>>>,
 }
 
 struct Course {
@@ -82,15 +98,27 @@ struct Course {
 
 impl Student {
     fn new(name: &str) -> Student {
+        NODES_CREATED.fetch_add(1, Ordering::Relaxed);
         Student {
             name: name.into(),
             courses: Vec::new(),
         }
     }
+
+    // Returns the names of the courses the student is enrolled in. Courses that have
+    // already been freed fail to upgrade and are skipped.
+    fn course_names(&self) -> Vec<String> {
+        self.courses
+            .iter()
+            .filter_map(|c| c.upgrade())
+            .map(|c| c.borrow().name.clone())
+            .collect()
+    }
 }
 
 impl Course {
     fn new(name: &str) -> Course {
+        NODES_CREATED.fetch_add(1, Ordering::Relaxed);
         Course {
             name: name.into(),
             students: Vec::new(),
@@ -98,24 +126,96 @@ impl Course {
     }
 
     // Adds a student to the course, establishing the many-to-many relationship.
-    // The student is added to the course's students list and, conversely, the course
-    // is added to the student's courses list.
+    // The student is added to the course's students list and, conversely, a weak
+    // handle to the course is added to the student's courses list.
     fn add_student(course: Rc<RefCell<Course>>, student: Rc<RefCell<Student>>) {
-        student.borrow_mut().courses.push(course.clone());
+        student.borrow_mut().courses.push(Rc::downgrade(&course));
         course.borrow_mut().students.push(student);
     }
+
+    // Removes a student from the course, cleaning up both sides of the relationship.
+    // Any stale weak handles left behind by freed courses are pruned at the same time.
+    fn remove_student(course: &Rc<RefCell<Course>>, student: &Rc<RefCell<Student>>) {
+        course
+            .borrow_mut()
+            .students
+            .retain(|s| !Rc::ptr_eq(s, student));
+        student
+            .borrow_mut()
+            .courses
+            .retain(|c| c.upgrade().is_some_and(|c| !Rc::ptr_eq(&c, course)));
+    }
+}
+
+impl Drop for Student {
+    fn drop(&mut self) {
+        NODES_DROPPED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Drop for Course {
+    fn drop(&mut self) {
+        NODES_DROPPED.fetch_add(1, Ordering::Relaxed);
+    }
 }
 
 mod database_normalization;
+mod event_sourcing;
+mod grades;
+mod rest_api;
+mod schedule;
+mod shared_platform;
 
 fn main() {
+    // `cargo run -- repl [dataset]` starts the interactive query prompt and
+    // `cargo run -- serve [addr]` starts the REST API instead of running the demos.
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("repl") => {
+            let default = concat!(env!("CARGO_MANIFEST_DIR"), "/data/platform.txt");
+            database_normalization::repl::run(args.get(2).map_or(default, String::as_str));
+            return;
+        }
+        Some("serve") => {
+            rest_api::serve(args.get(2).map_or("127.0.0.1:8080", String::as_str));
+            return;
+        }
+        _ => {}
+    }
+
     let kenry = Rc::new(RefCell::new(Student::new("Kenry")));
     let kary = Rc::new(RefCell::new(Student::new("Kary")));
     let course = Course::new("Rust Course");
     let magic_course = Rc::new(RefCell::new(course));
 
-    Course::add_student(magic_course.clone(), kenry);
-    Course::add_student(magic_course.clone(), kary);
+    Course::add_student(magic_course.clone(), kenry.clone());
+    Course::add_student(magic_course.clone(), kary.clone());
+    println!("Kenry is taking {:?}", kenry.borrow().course_names());
+
+    Course::remove_student(&magic_course, &kary);
+    println!("Kary is taking {:?}", kary.borrow().course_names());
+
+    // Weak handles let us observe whether the nodes were actually freed.
+    let weak_kenry = Rc::downgrade(&kenry);
+    let weak_kary = Rc::downgrade(&kary);
+    let weak_course = Rc::downgrade(&magic_course);
+
+    // Dropping the roots releases everything: the course is the only strong owner of
+    // Kenry, and no student keeps the course alive.
+    drop(kenry);
+    drop(kary);
+    drop(magic_course);
+    assert!(weak_course.upgrade().is_none());
+    assert!(weak_kenry.upgrade().is_none());
+    assert!(weak_kary.upgrade().is_none());
+    let created = NODES_CREATED.load(Ordering::Relaxed);
+    assert_eq!(created, 3);
+    assert_eq!(NODES_DROPPED.load(Ordering::Relaxed), created);
+    println!("All {} students and courses were freed", created);
 
+    schedule::schedule_demo();
     database_normalization::normalization_demo();
+    shared_platform::shared_platform_demo();
+    rest_api::rest_api_demo();
+    event_sourcing::event_sourcing_demo();
 }
@@ -0,0 +1,594 @@
+//! A small HTTP/JSON API over SharedPlatform, built directly on std::net so the whole request
+//! lifecycle (parse, route, respond) is visible. Each connection is handled on its own thread and
+//! carries a single request; the SharedPlatform takes care of synchronization between them.
+//! Reads time out and request lines, headers and bodies are size-limited, so a slow or idle
+//! client cannot hold a thread or grow memory without bound.
+//!
+//! Endpoints (names in paths are percent-encoded, e.g. `Intro%20to%20Rust`):
+//!
+//! ```text
+//! GET    /students                         list students
+//! POST   /students                         {"name": ".."} creates a student
+//! GET    /students/{name}                  one student
+//! PUT    /students/{name}                  {"name": ".."} renames a student
+//! DELETE /students/{name}                  removes a student and their enrollments
+//! GET    /students/{name}/courses          the student's courses
+//! GET    /courses                          list courses
+//! POST   /courses                          {"name": "..", "capacity": n} creates a course
+//! GET    /courses/{name}                   one course
+//! PUT    /courses/{name}                   {"capacity": n} changes the capacity
+//! DELETE /courses/{name}                   removes a course and its enrollments
+//! PUT    /courses/{name}/students/{name}   enrolls a student
+//! DELETE /courses/{name}/students/{name}   unenrolls a student
+//! ```
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::shared_platform::{CourseInfo, SharedPlatform, SharedPlatformError, StudentInfo};
+
+/// Requests with a larger body are rejected rather than read into memory.
+const MAX_BODY: usize = 1 << 20;
+
+/// Request and header lines longer than this are rejected, as are requests with more headers.
+const MAX_LINE: usize = 8 << 10;
+const MAX_HEADERS: usize = 100;
+
+/// A client that sends nothing for this long is disconnected, freeing its thread.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A parsed HTTP request. Only the parts the router needs are kept.
+struct Request {
+    method: String,
+    /// Percent-decoded path segments, e.g. ["courses", "Intro to Rust"].
+    segments: Vec<String>,
+    body: Vec<u8>,
+}
+
+/// An HTTP response with an optional JSON body.
+struct Response {
+    status: u16,
+    body: Option<Value>,
+}
+
+impl Response {
+    fn ok(body: Value) -> Response {
+        Response {
+            status: 200,
+            body: Some(body),
+        }
+    }
+
+    fn created(body: Value) -> Response {
+        Response {
+            status: 201,
+            body: Some(body),
+        }
+    }
+
+    fn no_content() -> Response {
+        Response {
+            status: 204,
+            body: None,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response {
+            status,
+            body: Some(json!({ "error": message })),
+        }
+    }
+}
+
+/// Maps platform errors to the closest HTTP status code.
+impl From<SharedPlatformError> for Response {
+    fn from(e: SharedPlatformError) -> Response {
+        let status = match e {
+            SharedPlatformError::UnknownStudent(_)
+            | SharedPlatformError::UnknownCourse(_)
+            | SharedPlatformError::NotEnrolled { .. } => 404,
+            SharedPlatformError::StudentExists(_)
+            | SharedPlatformError::CourseExists(_)
+            | SharedPlatformError::AlreadyEnrolled { .. }
+            | SharedPlatformError::CourseFull { .. }
+            | SharedPlatformError::CapacityBelowEnrollment { .. } => 409,
+        };
+        Response::error(status, &e.to_string())
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+fn student_json(s: &StudentInfo) -> Value {
+    json!({ "name": s.name, "courses": s.courses })
+}
+
+fn course_json(c: &CourseInfo) -> Value {
+    json!({ "name": c.name, "capacity": c.capacity, "students": c.students })
+}
+
+#[derive(Deserialize)]
+struct NameBody {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct NewCourseBody {
+    name: String,
+    capacity: usize,
+}
+
+#[derive(Deserialize)]
+struct CapacityBody {
+    capacity: usize,
+}
+
+/// Returns the name unless it is empty or only whitespace, which would make an unusable path.
+fn non_empty(name: &str) -> Result<&str, Response> {
+    if name.trim().is_empty() {
+        Err(Response::error(400, "name must not be empty"))
+    } else {
+        Ok(name)
+    }
+}
+
+/// Deserializes a JSON request body, producing a 400 response on failure.
+fn parse_body<'de, T: Deserialize<'de>>(body: &'de [u8]) -> Result<T, Response> {
+    serde_json::from_slice(body)
+        .map_err(|e| Response::error(400, &format!("invalid request body: {}", e)))
+}
+
+/// Converts the outcome of a handler into a response, so handlers can use `?` on both platform
+/// errors and body parsing errors.
+fn respond(result: Result<Response, Response>) -> Response {
+    result.unwrap_or_else(|e| e)
+}
+
+/// Dispatches a request to the matching endpoint.
+fn route(platform: &SharedPlatform, req: &Request) -> Response {
+    let segments: Vec<&str> = req.segments.iter().map(String::as_str).collect();
+    respond((|| match (req.method.as_str(), segments.as_slice()) {
+        ("GET", ["students"]) => Ok(Response::ok(
+            platform.students().iter().map(student_json).collect(),
+        )),
+        ("POST", ["students"]) => {
+            let body: NameBody = parse_body(&req.body)?;
+            platform.add_student(non_empty(&body.name)?)?;
+            Ok(Response::created(student_json(
+                &platform.student(&body.name)?,
+            )))
+        }
+        ("GET", ["students", name]) => Ok(Response::ok(student_json(&platform.student(name)?))),
+        ("PUT", ["students", name]) => {
+            let body: NameBody = parse_body(&req.body)?;
+            platform.rename_student(name, non_empty(&body.name)?)?;
+            Ok(Response::ok(student_json(&platform.student(&body.name)?)))
+        }
+        ("DELETE", ["students", name]) => {
+            platform.remove_student(name)?;
+            Ok(Response::no_content())
+        }
+        ("GET", ["students", name, "courses"]) => {
+            Ok(Response::ok(json!(platform.courses_of(name)?)))
+        }
+        ("GET", ["courses"]) => Ok(Response::ok(
+            platform.courses().iter().map(course_json).collect(),
+        )),
+        ("POST", ["courses"]) => {
+            let body: NewCourseBody = parse_body(&req.body)?;
+            platform.add_course(non_empty(&body.name)?, body.capacity)?;
+            Ok(Response::created(course_json(
+                &platform.course(&body.name)?,
+            )))
+        }
+        ("GET", ["courses", name]) => Ok(Response::ok(course_json(&platform.course(name)?))),
+        ("PUT", ["courses", name]) => {
+            let body: CapacityBody = parse_body(&req.body)?;
+            platform.set_capacity(name, body.capacity)?;
+            Ok(Response::ok(course_json(&platform.course(name)?)))
+        }
+        ("DELETE", ["courses", name]) => {
+            platform.remove_course(name)?;
+            Ok(Response::no_content())
+        }
+        ("PUT", ["courses", course, "students", student]) => {
+            platform.enroll(student, course)?;
+            Ok(Response::ok(course_json(&platform.course(course)?)))
+        }
+        ("DELETE", ["courses", course, "students", student]) => {
+            platform.unenroll(student, course)?;
+            Ok(Response::no_content())
+        }
+        (
+            _,
+            ["students"]
+            | ["students", _]
+            | ["students", _, "courses"]
+            | ["courses"]
+            | ["courses", _]
+            | ["courses", _, "students", _],
+        ) => Err(Response::error(405, "method not allowed")),
+        _ => Err(Response::error(404, "no such endpoint")),
+    })())
+}
+
+/// Decodes `%XX` escapes in a path segment. Both X must be hex digits; anything else, such as
+/// a sign that from_str_radix would accept, makes the segment malformed.
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return None;
+            }
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Encodes everything except unreserved characters as `%XX`, for building request paths.
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Reads one line of at most MAX_LINE bytes into `line`, returning false if it was longer.
+fn read_limited_line(reader: &mut impl BufRead, line: &mut String) -> io::Result<bool> {
+    let n = reader.by_ref().take(MAX_LINE as u64).read_line(line)?;
+    Ok(n < MAX_LINE || line.ends_with('\n'))
+}
+
+/// Reads one HTTP/1.1 request. Malformed requests are reported as a ready-made error response.
+fn read_request(reader: &mut impl BufRead) -> io::Result<Result<Request, Response>> {
+    let mut line = String::new();
+    if !read_limited_line(reader, &mut line)? {
+        return Ok(Err(Response::error(431, "request line too long")));
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(Err(Response::error(400, "malformed request line")));
+    };
+    let method = method.to_string();
+    let path = target.split('?').next().unwrap_or("");
+    let segments: Option<Vec<String>> = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(percent_decode)
+        .collect();
+    let Some(segments) = segments else {
+        return Ok(Err(Response::error(400, "malformed path")));
+    };
+
+    let mut content_length = 0;
+    for count in 0.. {
+        let mut header = String::new();
+        if !read_limited_line(reader, &mut header)? {
+            return Ok(Err(Response::error(431, "header line too long")));
+        }
+        if header.trim().is_empty() {
+            break;
+        }
+        if count == MAX_HEADERS {
+            return Ok(Err(Response::error(431, "too many headers")));
+        }
+        if let Some((key, value)) = header.split_once(':')
+            && key.trim().eq_ignore_ascii_case("content-length")
+        {
+            match value.trim().parse() {
+                Ok(n) => content_length = n,
+                Err(_) => return Ok(Err(Response::error(400, "invalid Content-Length"))),
+            }
+        }
+    }
+    if content_length > MAX_BODY {
+        return Ok(Err(Response::error(413, "request body too large")));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Ok(Request {
+        method,
+        segments,
+        body,
+    }))
+}
+
+fn write_response(stream: &mut impl Write, resp: &Response) -> io::Result<()> {
+    let body = resp
+        .body
+        .as_ref()
+        .map(|b| b.to_string())
+        .unwrap_or_default();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        resp.status,
+        reason(resp.status),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn handle_connection(platform: &SharedPlatform, stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let resp = match read_request(&mut reader)? {
+        Ok(req) => route(platform, &req),
+        Err(resp) => resp,
+    };
+    let mut stream = stream;
+    write_response(&mut stream, &resp)
+}
+
+/// A running server. Dropping the handle leaves the server running; call `shutdown` to stop it.
+pub struct ServerHandle {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl ServerHandle {
+    /// The address the server is listening on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Stops accepting connections and waits for the accept loop to exit.
+    pub fn shutdown(self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake the accept loop, which is blocked waiting for a connection.
+        let _ = TcpStream::connect(self.addr);
+        self.thread.join().unwrap();
+    }
+}
+
+/// Binds to `addr` and serves the platform on a background thread. Use port 0 to let the OS pick
+/// a free port, then read it back from `ServerHandle::addr`.
+///
+/// # Arguments
+///
+/// * `addr` - The address to listen on, e.g. "127.0.0.1:8080".
+/// * `platform` - The platform the API reads and modifies.
+pub fn start(addr: &str, platform: SharedPlatform) -> io::Result<ServerHandle> {
+    let listener = TcpListener::bind(addr)?;
+    let addr = listener.local_addr()?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = {
+        let stop = stop.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(stream) = stream else { continue };
+                let platform = platform.clone();
+                thread::spawn(move || {
+                    if let Err(e) = handle_connection(&platform, stream) {
+                        eprintln!("connection error: {}", e);
+                    }
+                });
+            }
+        })
+    };
+    Ok(ServerHandle { addr, stop, thread })
+}
+
+/// Serves an empty platform on `addr` until the process is killed.
+pub fn serve(addr: &str) {
+    match start(addr, SharedPlatform::new()) {
+        Ok(handle) => {
+            println!("Listening on http://{}", handle.addr());
+            handle.thread.join().unwrap();
+        }
+        Err(e) => eprintln!("Cannot listen on {}: {}", addr, e),
+    }
+}
+
+/// Sends one request to the server and returns the status code and parsed JSON body (Null when
+/// the response has no body).
+fn request(
+    addr: SocketAddr,
+    method: &str,
+    path: &[&str],
+    body: Option<Value>,
+) -> io::Result<(u16, Value)> {
+    let path: String = path
+        .iter()
+        .map(|s| format!("/{}", percent_encode(s)))
+        .collect();
+    let body = body.map(|b| b.to_string()).unwrap_or_default();
+    let mut stream = TcpStream::connect(addr)?;
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        addr,
+        body.len(),
+        body
+    )?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "bad status line"))?;
+    let body = if body.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_str(body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+    };
+    Ok((status, body))
+}
+
+/// Demonstrates the API by starting a server on a loopback port and exercising every endpoint
+/// through real HTTP requests, checking the status codes and bodies that come back.
+pub fn rest_api_demo() {
+    println!("REST API demo:");
+    let handle = start("127.0.0.1:0", SharedPlatform::new()).unwrap();
+    let addr = handle.addr();
+    let call = |method: &str, path: &[&str], body: Option<Value>| {
+        let (status, body) = request(addr, method, path, body).unwrap();
+        println!("{} /{} -> {} {}", method, path.join("/"), status, body);
+        (status, body)
+    };
+
+    assert_eq!(
+        call("POST", &["students"], Some(json!({"name": "Kenry"}))).0,
+        201
+    );
+    assert_eq!(
+        call("POST", &["students"], Some(json!({"name": "Kary"}))).0,
+        201
+    );
+    assert_eq!(
+        call("POST", &["students"], Some(json!({"name": "Kary"}))).0,
+        409
+    );
+    assert_eq!(
+        call("POST", &["students"], Some(json!({"nom": "Kary"}))).0,
+        400
+    );
+    let new_course = json!({"name": "Intro to Rust", "capacity": 1});
+    assert_eq!(call("POST", &["courses"], Some(new_course)).0, 201);
+
+    assert_eq!(
+        call(
+            "PUT",
+            &["courses", "Intro to Rust", "students", "Kenry"],
+            None
+        )
+        .0,
+        200
+    );
+    assert_eq!(
+        call(
+            "PUT",
+            &["courses", "Intro to Rust", "students", "Kary"],
+            None
+        )
+        .0,
+        409
+    );
+    assert_eq!(
+        call(
+            "PUT",
+            &["courses", "Intro to Rust"],
+            Some(json!({"capacity": 2}))
+        )
+        .0,
+        200
+    );
+    assert_eq!(
+        call(
+            "PUT",
+            &["courses", "Intro to Rust", "students", "Kary"],
+            None
+        )
+        .0,
+        200
+    );
+
+    let (status, body) = call("GET", &["students", "Kenry", "courses"], None);
+    assert_eq!((status, body), (200, json!(["Intro to Rust"])));
+    assert_eq!(
+        call(
+            "PUT",
+            &["students", "Kenry"],
+            Some(json!({"name": "Kenji"}))
+        )
+        .0,
+        200
+    );
+    let (status, body) = call("GET", &["courses", "Intro to Rust"], None);
+    assert_eq!(status, 200);
+    assert_eq!(body["students"], json!(["Kary", "Kenji"]));
+
+    assert_eq!(
+        call(
+            "DELETE",
+            &["courses", "Intro to Rust", "students", "Kary"],
+            None
+        )
+        .0,
+        204
+    );
+    assert_eq!(call("DELETE", &["students", "Kenji"], None).0, 204);
+    assert_eq!(call("GET", &["students", "Kenji"], None).0, 404);
+    let (status, body) = call("GET", &["courses"], None);
+    assert_eq!(status, 200);
+    assert_eq!(body[0]["students"], json!([]));
+    assert_eq!(call("DELETE", &["courses", "Intro to Rust"], None).0, 204);
+    assert_eq!(call("PATCH", &["courses"], None).0, 405);
+    assert_eq!(call("GET", &["teachers"], None).0, 404);
+    assert_eq!(
+        call("POST", &["students"], Some(json!({"name": " "}))).0,
+        400
+    );
+    let empty_course = json!({"name": "", "capacity": 1});
+    assert_eq!(call("POST", &["courses"], Some(empty_course)).0, 400);
+
+    // Oversized request lines and headers are rejected before they are read into memory.
+    let status = |raw: String| match read_request(&mut raw.as_bytes()) {
+        Ok(Err(resp)) => resp.status,
+        _ => 200,
+    };
+    let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "x".repeat(MAX_LINE));
+    assert_eq!(status(long_line), 431);
+    let many_headers = format!(
+        "GET / HTTP/1.1\r\n{}\r\n",
+        "X-A: b\r\n".repeat(MAX_HEADERS + 1)
+    );
+    assert_eq!(status(many_headers), 431);
+    assert_eq!(status("GET / HTTP/1.1\r\nX-A: b\r\n\r\n".to_string()), 200);
+    assert_eq!(
+        status("GET /courses/A%+1 HTTP/1.1\r\n\r\n".to_string()),
+        400
+    );
+    assert_eq!(
+        percent_decode("Intro%20to%2fRust").unwrap(),
+        "Intro to/Rust"
+    );
+    assert_eq!(percent_decode("%-1"), None);
+    assert_eq!(percent_decode("%2"), None);
+
+    handle.shutdown();
+}
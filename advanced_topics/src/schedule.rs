@@ -0,0 +1,195 @@
+//! This module holds the weekly schedule types attached to a Course: the day, time span and room of
+//! each class meeting. Schedules repeat every week of a term, so two meetings clash whenever they
+//! fall on the same weekday and their time spans overlap.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A day of the week, declared in calendar order so the derived ordering sorts Monday first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Weekday {
+    /// All days in calendar order.
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ];
+
+    /// The full English name of the day.
+    pub fn name(self) -> &'static str {
+        match self {
+            Weekday::Mon => "Monday",
+            Weekday::Tue => "Tuesday",
+            Weekday::Wed => "Wednesday",
+            Weekday::Thu => "Thursday",
+            Weekday::Fri => "Friday",
+            Weekday::Sat => "Saturday",
+            Weekday::Sun => "Sunday",
+        }
+    }
+}
+
+impl FromStr for Weekday {
+    type Err = String;
+
+    /// Parses a day's three-letter abbreviation or full name, case-insensitively ("mon",
+    /// "Monday").
+    fn from_str(s: &str) -> Result<Weekday, String> {
+        let lower = s.trim().to_ascii_lowercase();
+        Weekday::ALL
+            .into_iter()
+            .find(|d| {
+                let name = d.name().to_ascii_lowercase();
+                lower == name || lower == name[..3]
+            })
+            .ok_or_else(|| format!("invalid weekday '{}'", s))
+    }
+}
+
+impl fmt::Display for Weekday {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(&format!("{:?}", self))
+    }
+}
+
+/// A time of day with minute precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    minutes: u16,
+}
+
+impl Time {
+    /// Creates a Time from an hour (0-23) and minute (0-59).
+    pub fn new(hour: u16, minute: u16) -> Time {
+        assert!(hour < 24 && minute < 60, "invalid time {}:{}", hour, minute);
+        Time {
+            minutes: hour * 60 + minute,
+        }
+    }
+
+    /// Minutes since midnight.
+    pub fn minutes(self) -> u16 {
+        self.minutes
+    }
+}
+
+impl FromStr for Time {
+    type Err = String;
+
+    /// Parses a 24-hour time such as "09:30".
+    fn from_str(s: &str) -> Result<Time, String> {
+        let invalid = || format!("invalid time '{}': expected HH:MM", s);
+        let (h, m) = s.trim().split_once(':').ok_or_else(invalid)?;
+        let hour: u16 = h.parse().map_err(|_| invalid())?;
+        let minute: u16 = m.parse().map_err(|_| invalid())?;
+        if hour >= 24 || minute >= 60 {
+            return Err(invalid());
+        }
+        Ok(Time::new(hour, minute))
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.minutes / 60, self.minutes % 60)
+    }
+}
+
+/// One weekly class meeting: a time span on a given day, in a given room.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Meeting {
+    pub day: Weekday,
+    pub start: Time,
+    pub end: Time,
+    pub room: String,
+}
+
+impl Meeting {
+    /// Creates a new Meeting. `start` must be before `end`.
+    ///
+    /// # Arguments
+    ///
+    /// * `day` - The day of the week the class meets.
+    /// * `start` - When the class starts.
+    /// * `end` - When the class ends.
+    /// * `room` - Where the class meets.
+    pub fn new(day: Weekday, start: Time, end: Time, room: &str) -> Meeting {
+        assert!(start < end, "a meeting must start before it ends");
+        Meeting {
+            day,
+            start,
+            end,
+            room: room.into(),
+        }
+    }
+
+    /// Returns whether the two meetings take place at the same time. Back-to-back meetings (one
+    /// ending exactly when the other starts) do not overlap.
+    pub fn overlaps(&self, other: &Meeting) -> bool {
+        self.day == other.day && self.start < other.end && other.start < self.end
+    }
+}
+
+impl FromStr for Meeting {
+    type Err = String;
+
+    /// Parses "<day>, <start>, <end>, <room>", e.g. "Mon, 09:00, 10:30, Room 101".
+    fn from_str(s: &str) -> Result<Meeting, String> {
+        let fields: Vec<&str> = s.split(',').map(str::trim).collect();
+        let [day, start, end, room] = fields[..] else {
+            return Err(format!(
+                "invalid meeting '{}': expected day, start, end, room",
+                s
+            ));
+        };
+        let (start, end): (Time, Time) = (start.parse()?, end.parse()?);
+        if start >= end {
+            return Err(format!(
+                "invalid meeting '{}': must start before it ends",
+                s
+            ));
+        }
+        Ok(Meeting::new(day.parse()?, start, end, room))
+    }
+}
+
+impl fmt::Display for Meeting {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {}-{} in {}",
+            self.day, self.start, self.end, self.room
+        )
+    }
+}
+
+/// Demonstrates parsing schedule entries and detecting clashes between meetings.
+pub fn schedule_demo() {
+    println!("Schedule demo:");
+    assert_eq!("mon".parse(), Ok(Weekday::Mon));
+    assert_eq!(" Wednesday".parse(), Ok(Weekday::Wed));
+    assert!("Monkey".parse::<Weekday>().is_err());
+    assert!("Moé".parse::<Weekday>().is_err());
+
+    let lecture: Meeting = "Mon, 09:00, 10:30, Room 101".parse().unwrap();
+    let lab: Meeting = "monday, 10:00, 12:00, Lab 2".parse().unwrap();
+    let seminar: Meeting = "Mon, 10:30, 11:30, Room 101".parse().unwrap();
+    println!("{} clashes with {}", lecture, lab);
+    assert!(lecture.overlaps(&lab));
+    assert!(!lecture.overlaps(&seminar));
+    assert!("Mon, 11:00, 10:00, Room 101".parse::<Meeting>().is_err());
+}
@@ -0,0 +1,464 @@
+//! The models in main.rs and database_normalization.rs rely on Rc<RefCell<...>> and `&'a` borrows,
+//! neither of which can cross thread boundaries: Rc's reference count is not atomic, and borrowed
+//! Students and Courses would have to outlive every thread that sees them.
+//!
+//! SharedPlatform instead owns its data and identifies students and courses by name. All state sits
+//! behind a single Mutex, shared through an Arc, so every enroll/unenroll observes and updates the
+//! course roster, its capacity check and the student's course list as one atomic step. Cloning a
+//! SharedPlatform is cheap and yields another handle to the same state.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+/// A course with a maximum number of students and its current roster.
+#[derive(Clone)]
+struct CourseEntry {
+    capacity: usize,
+    students: BTreeSet<String>,
+}
+
+/// Everything guarded by the platform's Mutex. Enrollments are indexed in both directions so
+/// per-student and per-course lookups are cheap; the two indexes are only ever changed together.
+#[derive(Clone, Default)]
+struct State {
+    /// Student name -> names of the courses they are enrolled in.
+    students: BTreeMap<String, BTreeSet<String>>,
+    /// Course name -> capacity and roster.
+    courses: BTreeMap<String, CourseEntry>,
+}
+
+/// Errors returned by SharedPlatform operations.
+#[derive(Debug, PartialEq)]
+pub enum SharedPlatformError {
+    UnknownStudent(String),
+    UnknownCourse(String),
+    StudentExists(String),
+    CourseExists(String),
+    AlreadyEnrolled {
+        student: String,
+        course: String,
+    },
+    NotEnrolled {
+        student: String,
+        course: String,
+    },
+    CourseFull {
+        course: String,
+        capacity: usize,
+    },
+    /// A course's capacity cannot be lowered below its current number of students.
+    CapacityBelowEnrollment {
+        course: String,
+        enrolled: usize,
+    },
+}
+
+impl fmt::Display for SharedPlatformError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SharedPlatformError::UnknownStudent(s) => write!(f, "unknown student '{}'", s),
+            SharedPlatformError::UnknownCourse(c) => write!(f, "unknown course '{}'", c),
+            SharedPlatformError::StudentExists(s) => write!(f, "student '{}' already exists", s),
+            SharedPlatformError::CourseExists(c) => write!(f, "course '{}' already exists", c),
+            SharedPlatformError::AlreadyEnrolled { student, course } => {
+                write!(f, "{} is already enrolled in {}", student, course)
+            }
+            SharedPlatformError::NotEnrolled { student, course } => {
+                write!(f, "{} is not enrolled in {}", student, course)
+            }
+            SharedPlatformError::CourseFull { course, capacity } => {
+                write!(f, "{} is full ({} students)", course, capacity)
+            }
+            SharedPlatformError::CapacityBelowEnrollment { course, enrolled } => {
+                write!(f, "{} already has {} students enrolled", course, enrolled)
+            }
+        }
+    }
+}
+
+/// A point-in-time copy of a student's record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StudentInfo {
+    pub name: String,
+    pub courses: Vec<String>,
+}
+
+/// A point-in-time copy of a course's record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CourseInfo {
+    pub name: String,
+    pub capacity: usize,
+    pub students: Vec<String>,
+}
+
+/// A thread-safe enrollment platform. Clones share the same underlying state.
+#[derive(Clone, Default)]
+pub struct SharedPlatform {
+    state: Arc<Mutex<State>>,
+}
+
+impl SharedPlatform {
+    /// Creates a new, empty SharedPlatform.
+    pub fn new() -> SharedPlatform {
+        SharedPlatform::default()
+    }
+
+    /// Locks the shared state. A panic in another thread while holding the lock cannot leave the
+    /// two indexes half-updated (each operation validates before mutating), so a poisoned lock is
+    /// safe to recover.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Registers a new student.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The student's unique name.
+    pub fn add_student(&self, name: &str) -> Result<(), SharedPlatformError> {
+        let mut state = self.lock();
+        if state.students.contains_key(name) {
+            return Err(SharedPlatformError::StudentExists(name.into()));
+        }
+        state.students.insert(name.into(), BTreeSet::new());
+        Ok(())
+    }
+
+    /// Registers a new course that admits at most `capacity` students.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The course's unique name.
+    /// * `capacity` - The maximum number of enrolled students.
+    pub fn add_course(&self, name: &str, capacity: usize) -> Result<(), SharedPlatformError> {
+        let mut state = self.lock();
+        if state.courses.contains_key(name) {
+            return Err(SharedPlatformError::CourseExists(name.into()));
+        }
+        state.courses.insert(
+            name.into(),
+            CourseEntry {
+                capacity,
+                students: BTreeSet::new(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Renames a student, carrying their enrollments over to the new name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The student's current name.
+    /// * `new_name` - The name to rename the student to.
+    pub fn rename_student(&self, name: &str, new_name: &str) -> Result<(), SharedPlatformError> {
+        let mut state = self.lock();
+        if !state.students.contains_key(name) {
+            return Err(SharedPlatformError::UnknownStudent(name.into()));
+        }
+        if name == new_name {
+            return Ok(());
+        }
+        if state.students.contains_key(new_name) {
+            return Err(SharedPlatformError::StudentExists(new_name.into()));
+        }
+        let courses = state.students.remove(name).unwrap();
+        for course in &courses {
+            let roster = &mut state.courses.get_mut(course).unwrap().students;
+            roster.remove(name);
+            roster.insert(new_name.into());
+        }
+        state.students.insert(new_name.into(), courses);
+        Ok(())
+    }
+
+    /// Removes a student and all of their enrollments.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The student's name.
+    pub fn remove_student(&self, name: &str) -> Result<(), SharedPlatformError> {
+        let mut state = self.lock();
+        let courses = state
+            .students
+            .remove(name)
+            .ok_or_else(|| SharedPlatformError::UnknownStudent(name.into()))?;
+        for course in courses {
+            state
+                .courses
+                .get_mut(&course)
+                .unwrap()
+                .students
+                .remove(name);
+        }
+        Ok(())
+    }
+
+    /// Changes the capacity of a course. The new capacity must fit everyone already enrolled.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The course's name.
+    /// * `capacity` - The new maximum number of enrolled students.
+    pub fn set_capacity(&self, name: &str, capacity: usize) -> Result<(), SharedPlatformError> {
+        let mut state = self.lock();
+        let entry = state
+            .courses
+            .get_mut(name)
+            .ok_or_else(|| SharedPlatformError::UnknownCourse(name.into()))?;
+        if entry.students.len() > capacity {
+            return Err(SharedPlatformError::CapacityBelowEnrollment {
+                course: name.into(),
+                enrolled: entry.students.len(),
+            });
+        }
+        entry.capacity = capacity;
+        Ok(())
+    }
+
+    /// Removes a course and all of its enrollments.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The course's name.
+    pub fn remove_course(&self, name: &str) -> Result<(), SharedPlatformError> {
+        let mut state = self.lock();
+        let entry = state
+            .courses
+            .remove(name)
+            .ok_or_else(|| SharedPlatformError::UnknownCourse(name.into()))?;
+        for student in entry.students {
+            state.students.get_mut(&student).unwrap().remove(name);
+        }
+        Ok(())
+    }
+
+    /// Enrolls a student in a course, failing if the course is already at capacity.
+    ///
+    /// # Arguments
+    ///
+    /// * `student` - The student's name.
+    /// * `course` - The course's name.
+    pub fn enroll(&self, student: &str, course: &str) -> Result<(), SharedPlatformError> {
+        let mut state = self.lock();
+        let State { students, courses } = &mut *state;
+        let enrolled = students
+            .get_mut(student)
+            .ok_or_else(|| SharedPlatformError::UnknownStudent(student.into()))?;
+        let entry = courses
+            .get_mut(course)
+            .ok_or_else(|| SharedPlatformError::UnknownCourse(course.into()))?;
+        if entry.students.contains(student) {
+            return Err(SharedPlatformError::AlreadyEnrolled {
+                student: student.into(),
+                course: course.into(),
+            });
+        }
+        if entry.students.len() >= entry.capacity {
+            return Err(SharedPlatformError::CourseFull {
+                course: course.into(),
+                capacity: entry.capacity,
+            });
+        }
+        entry.students.insert(student.into());
+        enrolled.insert(course.into());
+        Ok(())
+    }
+
+    /// Removes a student from a course, freeing a seat.
+    ///
+    /// # Arguments
+    ///
+    /// * `student` - The student's name.
+    /// * `course` - The course's name.
+    pub fn unenroll(&self, student: &str, course: &str) -> Result<(), SharedPlatformError> {
+        let mut state = self.lock();
+        let State { students, courses } = &mut *state;
+        let enrolled = students
+            .get_mut(student)
+            .ok_or_else(|| SharedPlatformError::UnknownStudent(student.into()))?;
+        let entry = courses
+            .get_mut(course)
+            .ok_or_else(|| SharedPlatformError::UnknownCourse(course.into()))?;
+        if !entry.students.remove(student) {
+            return Err(SharedPlatformError::NotEnrolled {
+                student: student.into(),
+                course: course.into(),
+            });
+        }
+        enrolled.remove(course);
+        Ok(())
+    }
+
+    /// Returns a copy of every student's record, ordered by name.
+    pub fn students(&self) -> Vec<StudentInfo> {
+        self.lock()
+            .students
+            .iter()
+            .map(|(name, courses)| StudentInfo {
+                name: name.clone(),
+                courses: courses.iter().cloned().collect(),
+            })
+            .collect()
+    }
+
+    /// Returns a copy of a student's record.
+    pub fn student(&self, name: &str) -> Result<StudentInfo, SharedPlatformError> {
+        Ok(StudentInfo {
+            name: name.into(),
+            courses: self.courses_of(name)?,
+        })
+    }
+
+    /// Returns a copy of every course's record, ordered by name.
+    pub fn courses(&self) -> Vec<CourseInfo> {
+        self.lock()
+            .courses
+            .iter()
+            .map(|(name, entry)| CourseInfo {
+                name: name.clone(),
+                capacity: entry.capacity,
+                students: entry.students.iter().cloned().collect(),
+            })
+            .collect()
+    }
+
+    /// Returns a copy of a course's record.
+    pub fn course(&self, name: &str) -> Result<CourseInfo, SharedPlatformError> {
+        self.lock()
+            .courses
+            .get(name)
+            .map(|entry| CourseInfo {
+                name: name.into(),
+                capacity: entry.capacity,
+                students: entry.students.iter().cloned().collect(),
+            })
+            .ok_or_else(|| SharedPlatformError::UnknownCourse(name.into()))
+    }
+
+    /// Returns the names of the courses a student is enrolled in.
+    pub fn courses_of(&self, student: &str) -> Result<Vec<String>, SharedPlatformError> {
+        self.lock()
+            .students
+            .get(student)
+            .map(|courses| courses.iter().cloned().collect())
+            .ok_or_else(|| SharedPlatformError::UnknownStudent(student.into()))
+    }
+
+    /// Returns the names of the students enrolled in a course.
+    pub fn students_in(&self, course: &str) -> Result<Vec<String>, SharedPlatformError> {
+        self.lock()
+            .courses
+            .get(course)
+            .map(|entry| entry.students.iter().cloned().collect())
+            .ok_or_else(|| SharedPlatformError::UnknownCourse(course.into()))
+    }
+
+    /// Returns an independent copy of the current state. Unlike `clone`, which hands out another
+    /// handle to the same data, later changes to either platform are not seen by the other.
+    pub fn fork(&self) -> SharedPlatform {
+        SharedPlatform {
+            state: Arc::new(Mutex::new(self.lock().clone())),
+        }
+    }
+
+    /// Verifies that no course exceeds its capacity and that the student and course indexes agree
+    /// with each other, describing the first violation found.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        let state = self.lock();
+        for (name, entry) in &state.courses {
+            if entry.students.len() > entry.capacity {
+                return Err(format!(
+                    "{} has {} students but a capacity of {}",
+                    name,
+                    entry.students.len(),
+                    entry.capacity
+                ));
+            }
+            for student in &entry.students {
+                if !state
+                    .students
+                    .get(student)
+                    .is_some_and(|c| c.contains(name))
+                {
+                    return Err(format!("{} lists {} but not vice versa", name, student));
+                }
+            }
+        }
+        for (name, courses) in &state.students {
+            for course in courses {
+                if !state
+                    .courses
+                    .get(course)
+                    .is_some_and(|e| e.students.contains(name))
+                {
+                    return Err(format!("{} lists {} but not vice versa", name, course));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Demonstrates SharedPlatform under contention: many threads race to enroll in and drop out of a
+/// handful of small courses, then the final state is checked against the platform's invariants.
+pub fn shared_platform_demo() {
+    println!("Shared platform demo:");
+    const THREADS: usize = 16;
+    const STUDENTS_PER_THREAD: usize = 25;
+    let courses = [("Intro to Rust", 30), ("Algorithms", 10), ("Compilers", 5)];
+
+    let platform = SharedPlatform::new();
+    for (name, capacity) in courses {
+        platform.add_course(name, capacity).unwrap();
+    }
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let platform = platform.clone();
+            thread::spawn(move || {
+                // Net number of seats this thread holds in each course at the end.
+                let mut held = [0usize; 3];
+                let mut successes = 0;
+                for i in 0..STUDENTS_PER_THREAD {
+                    let student = format!("Student {}-{}", t, i);
+                    platform.add_student(&student).unwrap();
+                    for (c, (course, _)) in courses.iter().enumerate() {
+                        if platform.enroll(&student, course).is_ok() {
+                            held[c] += 1;
+                            successes += 1;
+                            // Every third successful enrollment is dropped again, freeing the
+                            // seat for another thread.
+                            if successes % 3 == 0 {
+                                platform.unenroll(&student, course).unwrap();
+                                held[c] -= 1;
+                            }
+                        }
+                    }
+                }
+                held
+            })
+        })
+        .collect();
+
+    let mut held = [0usize; 3];
+    for handle in handles {
+        for (total, n) in held.iter_mut().zip(handle.join().unwrap()) {
+            *total += n;
+        }
+    }
+
+    platform.check_invariants().unwrap();
+    for (c, (course, capacity)) in courses.iter().enumerate() {
+        let enrolled = platform.students_in(course).unwrap().len();
+        // The seats the threads believe they hold must match the roster exactly.
+        assert_eq!(enrolled, held[c]);
+        println!("{}: {}/{} seats taken", course, enrolled, capacity);
+    }
+    println!(
+        "Student 0-0 is taking {:?}",
+        platform.courses_of("Student 0-0").unwrap()
+    );
+}
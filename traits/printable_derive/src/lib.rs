@@ -0,0 +1,78 @@
+/*
+    `#[derive(Printable)]` for the traits crate's pretty-printer.
+
+    Derive macros must live in a crate of their own (a proc-macro crate), which runs at compile time
+    and turns the struct's definition into an impl block. For
+
+        #[derive(Printable)]
+        struct Point { x: i32, y: i32 }
+
+    it generates
+
+        impl crate::pretty::Printable for Point {
+            fn to_doc(&self) -> crate::pretty::Doc {
+                crate::pretty::Doc::record("Point", vec![
+                    ("x", crate::pretty::Printable::to_doc(&self.x)),
+                    ("y", crate::pretty::Printable::to_doc(&self.y)),
+                ])
+            }
+        }
+
+    The generated code names the trait through `crate::pretty`, so the macro only works inside the
+    traits crate, which is the only crate using it. Tuple structs print like tuples and unit
+    structs as their name. Every generic type parameter gets a Printable bound.
+*/
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, GenericParam, Index, parse_macro_input, parse_quote};
+
+#[proc_macro_derive(Printable)]
+pub fn derive_printable(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let label = name.to_string();
+
+    let body = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let idents: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                let labels = idents.iter().map(|i| i.as_ref().unwrap().to_string());
+                quote! {
+                    crate::pretty::Doc::record(#label, vec![
+                        #( (#labels, crate::pretty::Printable::to_doc(&self.#idents)) ),*
+                    ])
+                }
+            }
+            Fields::Unnamed(fields) => {
+                let indices = (0..fields.unnamed.len()).map(Index::from);
+                quote! {
+                    crate::pretty::Doc::tuple_struct(#label, vec![
+                        #( crate::pretty::Printable::to_doc(&self.#indices) ),*
+                    ])
+                }
+            }
+            Fields::Unit => quote! { crate::pretty::Doc::text(#label) },
+        },
+        Data::Enum(_) | Data::Union(_) => {
+            return syn::Error::new_spanned(name, "Printable can only be derived for structs")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    for param in &mut input.generics.params {
+        if let GenericParam::Type(param) = param {
+            param.bounds.push(parse_quote!(crate::pretty::Printable));
+        }
+    }
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics crate::pretty::Printable for #name #type_generics #where_clause {
+            fn to_doc(&self) -> crate::pretty::Doc {
+                #body
+            }
+        }
+    }
+    .into()
+}
@@ -0,0 +1,275 @@
+/*
+    Constructing trait objects at runtime by name.
+
+    Generic code like `Human::create(name)` needs the concrete type at compile time. When the type
+    is only known at runtime, say from a line "cat Meeku" in a config file, we need a lookup from the
+    kind's name to a function that builds the right type. The AnimalRegistry is that lookup: it maps
+    each kind string to a factory, a plain function pointer returning Box<dyn Animal>.
+
+    Registering a type needs no hand-written factory. The generic function `boxed::<A>` is
+    instantiated once per type, and each instantiation coerces to the same function pointer type,
+    so factories for different types can live in one map.
+
+    register_type also remembers the kind under the type's TypeId, so the registry can map an
+    existing animal back to the kind that builds it. Saving animals relies on this: the tag written
+    is the one loading will look up, whatever the animal reports as its species.
+*/
+
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::traits::{Animal, Cat, Dog, Human, Parrot};
+
+/// A function building a boxed animal from its name.
+pub(crate) type AnimalFactory = fn(Cow<'static, str>) -> Box<dyn Animal>;
+
+/// The errors the registry can return.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RegistryError {
+    /// A factory was already registered under this kind.
+    DuplicateKind(String),
+    /// No factory is registered under this kind.
+    UnknownKind(String),
+    /// A config line named a kind with no registered factory.
+    UnknownKindAt { line: usize, kind: String },
+    /// A config line was not of the form "<kind> <name>".
+    MalformedLine { line: usize, text: String },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryError::DuplicateKind(kind) => {
+                write!(f, "the kind '{}' is already registered", kind)
+            }
+            RegistryError::UnknownKind(kind) => write!(f, "unknown animal kind '{}'", kind),
+            RegistryError::UnknownKindAt { line, kind } => {
+                write!(f, "line {}: unknown animal kind '{}'", line, kind)
+            }
+            RegistryError::MalformedLine { line, text } => write!(
+                f,
+                "line {}: expected '<kind> <name>', found '{}'",
+                line, text
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Builds an A through its Animal::create factory and boxes it as a trait object.
+fn boxed<A: Animal + 'static>(name: Cow<'static, str>) -> Box<dyn Animal> {
+    Box::new(A::create(name))
+}
+
+/// Maps kind names to factories. Kinds are matched case-insensitively.
+pub(crate) struct AnimalRegistry {
+    factories: HashMap<String, AnimalFactory>,
+    /// The first kind each type was registered under with register_type.
+    kinds_by_type: HashMap<TypeId, String>,
+}
+
+impl AnimalRegistry {
+    /// Creates an empty registry.
+    pub(crate) fn new() -> AnimalRegistry {
+        AnimalRegistry {
+            factories: HashMap::new(),
+            kinds_by_type: HashMap::new(),
+        }
+    }
+
+    /// Creates a registry with the crate's built-in animals: "human", "cat", "dog" and "parrot".
+    pub(crate) fn with_builtin() -> AnimalRegistry {
+        let mut registry = AnimalRegistry::new();
+        registry.register_type::<Human>("human").unwrap();
+        registry.register_type::<Cat>("cat").unwrap();
+        registry.register_type::<Dog>("dog").unwrap();
+        registry.register_type::<Parrot>("parrot").unwrap();
+        registry
+    }
+
+    /// Registers a factory under a kind name.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The name the factory is looked up by.
+    /// * `factory` - The function that builds animals of this kind.
+    pub(crate) fn register(
+        &mut self,
+        kind: &str,
+        factory: AnimalFactory,
+    ) -> Result<(), RegistryError> {
+        let kind = kind.to_lowercase();
+        if self.factories.contains_key(&kind) {
+            return Err(RegistryError::DuplicateKind(kind));
+        }
+        self.factories.insert(kind, factory);
+        Ok(())
+    }
+
+    /// Registers A's own Animal::create as the factory for a kind. The first kind registered for A
+    /// is the one kind_of returns for it.
+    pub(crate) fn register_type<A: Animal + 'static>(
+        &mut self,
+        kind: &str,
+    ) -> Result<(), RegistryError> {
+        self.register(kind, boxed::<A>)?;
+        self.kinds_by_type
+            .entry(TypeId::of::<A>())
+            .or_insert_with(|| kind.to_lowercase());
+        Ok(())
+    }
+
+    /// Returns the kind that builds animals of the same type as `animal`, if its type was
+    /// registered with register_type. Custom factories do not say which type they build, so
+    /// their kinds are not found here.
+    pub(crate) fn kind_of(&self, animal: &dyn Animal) -> Option<&str> {
+        let any: &dyn Any = animal;
+        self.kinds_by_type.get(&any.type_id()).map(String::as_str)
+    }
+
+    /// Returns the registered kinds in alphabetical order.
+    pub(crate) fn kinds(&self) -> Vec<&str> {
+        let mut kinds: Vec<&str> = self.factories.keys().map(String::as_str).collect();
+        kinds.sort();
+        kinds
+    }
+
+    /// Builds an animal of the given kind.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The registered kind name, such as "cat".
+    /// * `name` - The new animal's name.
+    pub(crate) fn create(
+        &self,
+        kind: &str,
+        name: impl Into<Cow<'static, str>>,
+    ) -> Result<Box<dyn Animal>, RegistryError> {
+        let factory = self
+            .factories
+            .get(&kind.to_lowercase())
+            .ok_or_else(|| RegistryError::UnknownKind(kind.to_string()))?;
+        Ok(factory(name.into()))
+    }
+
+    /// Builds one animal per line of a config with lines "<kind> <name>". Blank lines and lines
+    /// starting with '#' are skipped; the first bad line stops the whole load. The names are
+    /// copied, so the config text can be dropped afterwards.
+    pub(crate) fn load(&self, config: &str) -> Result<Vec<Box<dyn Animal>>, RegistryError> {
+        let mut animals = Vec::new();
+        for (index, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((kind, name)) = line.split_once(char::is_whitespace) else {
+                return Err(RegistryError::MalformedLine {
+                    line: index + 1,
+                    text: line.to_string(),
+                });
+            };
+            let animal = self.create(kind, name.trim().to_string()).map_err(|_| {
+                RegistryError::UnknownKindAt {
+                    line: index + 1,
+                    kind: kind.to_string(),
+                }
+            })?;
+            animals.push(animal);
+        }
+        Ok(animals)
+    }
+}
+
+/// A kind defined outside the traits module, to show that registering it needs only an impl.
+struct Robot {
+    name: Cow<'static, str>,
+}
+
+impl Animal for Robot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn species(&self) -> &'static str {
+        "robot"
+    }
+
+    fn create(name: impl Into<Cow<'static, str>>) -> Robot {
+        Robot { name: name.into() }
+    }
+}
+
+/// Demonstrates building trait objects from kind names.
+pub fn animal_registry_demo() {
+    println!("Animal registry:");
+    let mut registry = AnimalRegistry::with_builtin();
+    println!("Registered kinds: {:?}", registry.kinds());
+
+    let animals = registry.load(include_str!("../data/animals.txt")).unwrap();
+    for animal in &animals {
+        animal.talk();
+    }
+    let names: Vec<&str> = animals.iter().map(|a| a.name()).collect();
+    assert_eq!(names, ["Kenry", "Meeku", "Kary", "Tama", "Pochi", "Polly"]);
+
+    // Configs assembled at runtime work too, since names are copied into owned Strings.
+    let config: String = [("dog", "Hachi"), ("parrot", "Kiwi")]
+        .iter()
+        .map(|(kind, name)| format!("{} {}\n", kind, name))
+        .collect();
+    let pets = registry.load(&config).unwrap();
+    drop(config);
+    let descriptions: Vec<String> = pets.iter().map(|p| p.describe()).collect();
+    assert_eq!(
+        descriptions,
+        ["Hachi is a good dog", "Kiwi is a parrot that says \"Kiwi\""]
+    );
+
+    // New kinds can be added at runtime, with a generic factory or a custom one.
+    registry.register_type::<Robot>("robot").unwrap();
+    registry
+        .register("kitten", |name| Box::new(Cat::create(name)))
+        .unwrap();
+    assert_eq!(
+        registry.kinds(),
+        ["cat", "dog", "human", "kitten", "parrot", "robot"]
+    );
+    let robot = registry.create("Robot", "R2").unwrap();
+    robot.talk();
+    assert_eq!(robot.describe(), "R2 is a robot");
+    registry.create("kitten", "Mochi").unwrap().talk();
+
+    // An animal maps back to the kind its type was first registered under.
+    assert_eq!(registry.kind_of(robot.as_ref()), Some("robot"));
+    let mochi = registry.create("kitten", "Mochi").unwrap();
+    assert_eq!(registry.kind_of(mochi.as_ref()), Some("cat"));
+    assert_eq!(AnimalRegistry::new().kind_of(mochi.as_ref()), None);
+
+    assert_eq!(
+        registry.register_type::<Human>("HUMAN").unwrap_err(),
+        RegistryError::DuplicateKind("human".to_string())
+    );
+    let err = registry.load("human Kenji\ndragon Smaug").err().unwrap();
+    println!("Loading a dragon: {}", err);
+    assert_eq!(
+        err,
+        RegistryError::UnknownKindAt {
+            line: 2,
+            kind: "dragon".to_string()
+        }
+    );
+    assert_eq!(
+        registry.create("dragon", "Smaug").err().unwrap(),
+        RegistryError::UnknownKind("dragon".to_string())
+    );
+    assert_eq!(
+        registry.load("\n# comment\ncat").err().unwrap(),
+        RegistryError::MalformedLine {
+            line: 3,
+            text: "cat".to_string()
+        }
+    );
+}
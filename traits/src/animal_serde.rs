@@ -0,0 +1,291 @@
+/*
+    Saving and loading collections of trait objects.
+
+    Serde's derive macros need to know the concrete type at compile time, so they cannot produce a
+    Box<dyn Animal> directly. Instead, each animal is converted to an AnimalRecord, a plain struct
+    holding its type tag, its name and any extra attributes, and serde handles the records:
+
+        {"type": "parrot", "name": "Polly", "phrase": "Polly wants a cracker"}
+
+    The tag is the kind the animal's type is registered under in an AnimalRegistry, found through
+    AnimalRegistry::kind_of. It is not the animal's species: a type may keep the default species
+    "animal", or be registered as "kitten" while describing itself as a cat, and only the registered
+    kind is guaranteed to load again. Loading looks the tag up in the same registry to find the
+    factory for the right concrete type, then restores the attributes through
+    Animal::set_attribute. Tags the registry does not know are reported as errors rather than
+    skipped, so a typo in a fixture cannot silently drop an animal.
+
+    The records are format-agnostic: the same Vec<AnimalRecord> is written as JSON with serde_json
+    or as YAML with serde_yaml_ng.
+*/
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::animal_registry::AnimalRegistry;
+use crate::traits::{Animal, Cat, Dog, Human, Parrot};
+
+/// The serialized form of one animal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct AnimalRecord {
+    /// The type tag, which selects the factory when loading.
+    #[serde(rename = "type")]
+    pub(crate) kind: String,
+    pub(crate) name: String,
+    /// Any other fields, as returned by Animal::attributes.
+    #[serde(flatten)]
+    pub(crate) attributes: BTreeMap<String, String>,
+}
+
+impl AnimalRecord {
+    /// Captures an animal's name and attributes under the given type tag.
+    pub(crate) fn from_animal(kind: &str, animal: &dyn Animal) -> AnimalRecord {
+        AnimalRecord {
+            kind: kind.to_string(),
+            name: animal.name().to_string(),
+            attributes: animal
+                .attributes()
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        }
+    }
+}
+
+/// The text formats a collection can be saved in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Format {
+    Json,
+    Yaml,
+}
+
+/// The errors saving or loading can return.
+#[derive(Debug)]
+pub(crate) enum PersistError {
+    /// The JSON could not be written or parsed.
+    Json(serde_json::Error),
+    /// The YAML could not be written or parsed.
+    Yaml(serde_yaml_ng::Error),
+    /// The record at `index` has a type tag the registry does not know.
+    UnknownType { index: usize, tag: String },
+    /// The animal at `index` is of a type the registry has no kind for, so it cannot be loaded
+    /// back.
+    UnregisteredType { index: usize, species: String },
+    /// The record at `index` has an attribute its type rejected.
+    InvalidAttribute { index: usize, message: String },
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PersistError::Json(e) => write!(f, "invalid JSON: {}", e),
+            PersistError::Yaml(e) => write!(f, "invalid YAML: {}", e),
+            PersistError::UnknownType { index, tag } => {
+                write!(f, "animal #{} has unknown type '{}'", index, tag)
+            }
+            PersistError::UnregisteredType { index, species } => {
+                write!(f, "animal #{} ({}) has no registered kind", index, species)
+            }
+            PersistError::InvalidAttribute { index, message } => {
+                write!(f, "animal #{}: {}", index, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PersistError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PersistError::Json(e) => Some(e),
+            PersistError::Yaml(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for PersistError {
+    fn from(e: serde_json::Error) -> PersistError {
+        PersistError::Json(e)
+    }
+}
+
+impl From<serde_yaml_ng::Error> for PersistError {
+    fn from(e: serde_yaml_ng::Error) -> PersistError {
+        PersistError::Yaml(e)
+    }
+}
+
+/// Writes a collection of animals as a list of tagged records.
+///
+/// # Arguments
+///
+/// * `registry` - Supplies each animal's type tag: the kind its type is registered under.
+/// * `animals` - The animals to save, of any mix of types.
+/// * `format` - Whether to write JSON or YAML.
+pub(crate) fn save(
+    registry: &AnimalRegistry,
+    animals: &[Box<dyn Animal>],
+    format: Format,
+) -> Result<String, PersistError> {
+    let records = animals
+        .iter()
+        .enumerate()
+        .map(|(index, animal)| {
+            let kind = registry.kind_of(animal.as_ref()).ok_or_else(|| {
+                PersistError::UnregisteredType {
+                    index,
+                    species: animal.species().to_string(),
+                }
+            })?;
+            Ok(AnimalRecord::from_animal(kind, animal.as_ref()))
+        })
+        .collect::<Result<Vec<AnimalRecord>, PersistError>>()?;
+    Ok(match format {
+        Format::Json => serde_json::to_string_pretty(&records)?,
+        Format::Yaml => serde_yaml_ng::to_string(&records)?,
+    })
+}
+
+/// Reads a list of tagged records and rebuilds each as the trait object its tag names.
+///
+/// # Arguments
+///
+/// * `registry` - Maps each type tag to the factory for its concrete type.
+/// * `text` - The saved collection.
+/// * `format` - Whether `text` is JSON or YAML.
+pub(crate) fn load(
+    registry: &AnimalRegistry,
+    text: &str,
+    format: Format,
+) -> Result<Vec<Box<dyn Animal>>, PersistError> {
+    let records: Vec<AnimalRecord> = match format {
+        Format::Json => serde_json::from_str(text)?,
+        Format::Yaml => serde_yaml_ng::from_str(text)?,
+    };
+    records
+        .into_iter()
+        .enumerate()
+        .map(|(index, record)| {
+            let mut animal = registry.create(&record.kind, record.name).map_err(|_| {
+                PersistError::UnknownType {
+                    index,
+                    tag: record.kind.clone(),
+                }
+            })?;
+            for (key, value) in &record.attributes {
+                animal
+                    .set_attribute(key, value)
+                    .map_err(|message| PersistError::InvalidAttribute { index, message })?;
+            }
+            Ok(animal)
+        })
+        .collect()
+}
+
+/// An animal that keeps the default species, "animal", to show that tags come from the registry.
+struct Stray {
+    name: Cow<'static, str>,
+}
+
+impl Animal for Stray {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn create(name: impl Into<Cow<'static, str>>) -> Stray {
+        Stray { name: name.into() }
+    }
+}
+
+/// Demonstrates saving and loading a mixed collection of animals.
+pub fn animal_serde_demo() {
+    println!("Serializing trait objects:");
+    let registry = AnimalRegistry::with_builtin();
+
+    // The fixtures hold the same animals in both formats.
+    let from_json = load(
+        &registry,
+        include_str!("../data/animals.json"),
+        Format::Json,
+    )
+    .unwrap();
+    let from_yaml = load(
+        &registry,
+        include_str!("../data/animals.yaml"),
+        Format::Yaml,
+    )
+    .unwrap();
+    let describe = |animals: &[Box<dyn Animal>]| -> Vec<String> {
+        animals.iter().map(|a| a.describe()).collect()
+    };
+    for description in describe(&from_json) {
+        println!("{}", description);
+    }
+    assert_eq!(describe(&from_json), describe(&from_yaml));
+
+    let animals: Vec<Box<dyn Animal>> = vec![
+        Box::new(Human::create("Kenry")),
+        Box::new(Cat::create("Meeku")),
+        Box::new(Dog::create("Pochi")),
+        Box::new(Parrot::with_phrase("Polly", "Polly wants a cracker")),
+    ];
+    let json = save(&registry, &animals, Format::Json).unwrap();
+    println!("As JSON:\n{}", json);
+    let yaml = save(&registry, &animals, Format::Yaml).unwrap();
+    println!("As YAML:\n{}", yaml);
+    assert!(json.contains(r#""type": "parrot""#));
+    assert!(yaml.contains("phrase: Polly wants a cracker"));
+
+    // Round trips through either format give back the same animals, as the right types.
+    for (text, format) in [(&json, Format::Json), (&yaml, Format::Yaml)] {
+        let loaded = load(&registry, text, format).unwrap();
+        assert_eq!(describe(&loaded), describe(&animals));
+        assert_eq!(save(&registry, &loaded, format).unwrap(), *text);
+    }
+    assert_eq!(describe(&from_json), describe(&animals));
+
+    // Unknown tags, unknown attributes and broken syntax are all reported.
+    let unknown = r#"[{"type": "human", "name": "Kenji"}, {"type": "dragon", "name": "Smaug"}]"#;
+    let err = load(&registry, unknown, Format::Json).err().unwrap();
+    println!("Loading a dragon: {}", err);
+    assert!(matches!(err, PersistError::UnknownType { index: 1, ref tag } if tag == "dragon"));
+
+    let bad_attribute = "- type: cat\n  name: Tama\n  phrase: meow\n";
+    let err = load(&registry, bad_attribute, Format::Yaml).err().unwrap();
+    println!("Loading a talking cat: {}", err);
+    assert!(matches!(
+        err,
+        PersistError::InvalidAttribute { index: 0, .. }
+    ));
+
+    let err = load(&registry, r#"[{"name": "Kary"}]"#, Format::Json)
+        .err()
+        .unwrap();
+    println!("Loading an untagged animal: {}", err);
+    assert!(matches!(err, PersistError::Json(_)));
+
+    // The tag is the registered kind, not the species, so kinds named differently from their
+    // species still round-trip.
+    let mut renamed = AnimalRegistry::new();
+    renamed.register_type::<Cat>("kitten").unwrap();
+    renamed.register_type::<Stray>("stray").unwrap();
+    let pets: Vec<Box<dyn Animal>> = vec![
+        Box::new(Cat::create("Tama")),
+        Box::new(Stray::create("Rex")),
+    ];
+    let json = save(&renamed, &pets, Format::Json).unwrap();
+    assert!(json.contains(r#""type": "kitten""#) && json.contains(r#""type": "stray""#));
+    let loaded = load(&renamed, &json, Format::Json).unwrap();
+    assert_eq!(describe(&loaded), ["Tama is a cat", "Rex is an animal"]);
+
+    // Saving an animal the registry cannot build again is an error, not an unloadable file.
+    let err = save(&registry, &pets, Format::Yaml).err().unwrap();
+    println!("Saving a stray: {}", err);
+    assert!(matches!(
+        err,
+        PersistError::UnregisteredType { index: 1, .. }
+    ));
+}
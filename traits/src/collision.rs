@@ -0,0 +1,660 @@
+/*
+    Collision detection between shapes, and a spatial index for finding collisions quickly.
+
+    intersects(a, b) works on any two &dyn Shape. It first compares bounding boxes, which rules out
+    most pairs cheaply, then compares the shapes' outlines exactly:
+
+    - circle–circle: the centers are no further apart than the sum of the radii.
+    - circle–polygon: the center is inside the polygon, or some edge comes within the radius of it.
+    - polygon–polygon: for two convex polygons (squares, rectangles, triangles, regular polygons)
+      the separating axis theorem (SAT) applies: two convex shapes are disjoint exactly when some
+      line separates them, and it is enough to try the lines perpendicular to each edge. Projecting
+      both polygons onto each edge normal, the shapes intersect unless one projection leaves a
+      gap. Zero-length edges, from repeated vertices, have no direction and are skipped. SAT does
+      not hold for non-convex polygons, so those fall back to checking whether any edges cross or
+      one polygon holds a vertex of the other.
+    - ellipse–polygon: scaling the ellipse's own axes turns it into the unit circle, and the same
+      affine map turns the polygon into another polygon, so the circle–polygon test applies.
+    - ellipse–circle and ellipse–ellipse have no such reduction. One ellipse is replaced by a
+      polygon of ELLIPSE_SEGMENTS vertices on its boundary, which is within 0.01% of its size.
+
+    As everywhere in the shape library, shapes that only touch count as intersecting.
+
+    Testing every pair of n shapes takes n²/2 tests. The Grid index divides the plane into square
+    cells and lists each shape under every cell its bounding box overlaps. Only shapes sharing a
+    cell can collide, so with cells about the size of a typical shape, each shape is compared
+    against its few neighbors instead of everything. A shape much larger than the cells would be
+    listed under thousands of them, so one covering more than MAX_CELLS_PER_SHAPE cells is kept in
+    a separate list and compared against every other shape instead. Likewise, a query region
+    covering more cells than there are shapes is checked against every shape.
+
+    The demo checks that the grid and brute force always find the same collisions; the benchmark
+    at the end, run by `cargo run --release -- bench`, times them against each other.
+*/
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::time::Instant;
+
+use crate::rng::XorShift;
+use crate::shapes::{
+    BoundingBox, Circle, Ellipse, Outline, Point, Polygon, Rectangle, Shape, Square, TOLERANCE,
+    Transform, Triangle, edges, polygon_contains, to_local,
+};
+
+/// How many vertices approximate an ellipse when no exact test applies.
+const ELLIPSE_SEGMENTS: usize = 256;
+
+/// The most grid cells a shape is listed under; larger shapes are tested against every shape.
+const MAX_CELLS_PER_SHAPE: f64 = 64.0;
+
+/// Returns whether two shapes overlap or touch.
+pub(crate) fn intersects(a: &dyn Shape, b: &dyn Shape) -> bool {
+    a.bounding_box().intersects(&b.bounding_box()) && outlines_intersect(&a.outline(), &b.outline())
+}
+
+fn outlines_intersect(a: &Outline, b: &Outline) -> bool {
+    match (a, b) {
+        (
+            Outline::Circle {
+                center: c1,
+                radius: r1,
+            },
+            Outline::Circle {
+                center: c2,
+                radius: r2,
+            },
+        ) => c1.distance(*c2) <= r1 + r2 + TOLERANCE,
+        (Outline::Circle { center, radius }, Outline::Polygon(vertices))
+        | (Outline::Polygon(vertices), Outline::Circle { center, radius }) => {
+            circle_polygon(*center, *radius, vertices)
+        }
+        (Outline::Polygon(a), Outline::Polygon(b)) => match is_convex(a) && is_convex(b) {
+            true => separating_axis(a, b),
+            false => polygons_intersect(a, b),
+        },
+        (
+            Outline::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                angle,
+            },
+            Outline::Polygon(vertices),
+        )
+        | (
+            Outline::Polygon(vertices),
+            Outline::Ellipse {
+                center,
+                radius_x,
+                radius_y,
+                angle,
+            },
+        ) => {
+            // In the ellipse's own frame, with its axes scaled to 1, it is the unit circle.
+            let mapped: Vec<Point> = vertices
+                .iter()
+                .map(|&p| {
+                    let local = to_local(p, *center, *angle);
+                    Point::new(local.x / radius_x, local.y / radius_y)
+                })
+                .collect();
+            circle_polygon(Point::ORIGIN, 1.0, &mapped)
+        }
+        (ellipse @ Outline::Ellipse { .. }, other) | (other, ellipse @ Outline::Ellipse { .. }) => {
+            outlines_intersect(&approximate(ellipse), other)
+        }
+    }
+}
+
+/// Replaces an ellipse outline with a polygon through ELLIPSE_SEGMENTS points on it.
+fn approximate(ellipse: &Outline) -> Outline {
+    let Outline::Ellipse {
+        center,
+        radius_x,
+        radius_y,
+        angle,
+    } = *ellipse
+    else {
+        return ellipse.clone();
+    };
+    Outline::Polygon(
+        (0..ELLIPSE_SEGMENTS)
+            .map(|k| {
+                let t = 2.0 * PI * k as f64 / ELLIPSE_SEGMENTS as f64;
+                center + Point::new(radius_x * t.cos(), radius_y * t.sin()).rotated(angle)
+            })
+            .collect(),
+    )
+}
+
+/// Returns the distance from `point` to the nearest point of the segment from `a` to `b`.
+fn segment_distance(point: Point, a: Point, b: Point) -> f64 {
+    let edge = b - a;
+    if edge.dot(edge) == 0.0 {
+        return point.distance(a);
+    }
+    let t = ((point - a).dot(edge) / edge.dot(edge)).clamp(0.0, 1.0);
+    point.distance(a + edge * t)
+}
+
+fn circle_polygon(center: Point, radius: f64, vertices: &[Point]) -> bool {
+    polygon_contains(vertices, center)
+        || edges(vertices).any(|(a, b)| segment_distance(center, a, b) <= radius + TOLERANCE)
+}
+
+/// Returns whether every turn along the boundary goes the same way.
+fn is_convex(vertices: &[Point]) -> bool {
+    let turns: Vec<f64> = edges(vertices)
+        .zip(edges(vertices).skip(1))
+        .map(|((a, b), (_, c))| (b - a).cross(c - b))
+        .collect();
+    turns.iter().all(|&t| t >= -TOLERANCE) || turns.iter().all(|&t| t <= TOLERANCE)
+}
+
+/// Returns the interval covered by projecting the points onto `axis`.
+fn project(vertices: &[Point], axis: Point) -> (f64, f64) {
+    vertices
+        .iter()
+        .map(|p| p.dot(axis))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), d| {
+            (lo.min(d), hi.max(d))
+        })
+}
+
+/// The separating axis test for two convex polygons. Zero-length edges are skipped, since their
+/// normal is undefined.
+fn separating_axis(a: &[Point], b: &[Point]) -> bool {
+    edges(a).chain(edges(b)).all(|(p, q)| {
+        let edge = q - p;
+        if edge.length() == 0.0 {
+            return true;
+        }
+        let axis = Point::new(-edge.y, edge.x) * (1.0 / edge.length());
+        let (a_lo, a_hi) = project(a, axis);
+        let (b_lo, b_hi) = project(b, axis);
+        a_lo <= b_hi + TOLERANCE && b_lo <= a_hi + TOLERANCE
+    })
+}
+
+/// Returns whether two segments cross or touch.
+fn segments_intersect(a1: Point, a2: Point, b1: Point, b2: Point) -> bool {
+    let side = |p: Point, q: Point, r: Point| (q - p).cross(r - p);
+    let crossing =
+        side(a1, a2, b1) * side(a1, a2, b2) < 0.0 && side(b1, b2, a1) * side(b1, b2, a2) < 0.0;
+    crossing
+        || segment_distance(a1, b1, b2) <= TOLERANCE
+        || segment_distance(a2, b1, b2) <= TOLERANCE
+        || segment_distance(b1, a1, a2) <= TOLERANCE
+        || segment_distance(b2, a1, a2) <= TOLERANCE
+}
+
+/// The general test for simple polygons: two polygons intersect exactly when their boundaries
+/// cross, or one lies entirely inside the other.
+fn polygons_intersect(a: &[Point], b: &[Point]) -> bool {
+    edges(a).any(|(a1, a2)| edges(b).any(|(b1, b2)| segments_intersect(a1, a2, b1, b2)))
+        || polygon_contains(a, b[0])
+        || polygon_contains(b, a[0])
+}
+
+/// Returns the shape covering exactly a bounding box, for testing shapes against a region.
+fn region_shape(region: &BoundingBox) -> Rectangle {
+    Rectangle::new(
+        (region.min + region.max) * 0.5,
+        region.width(),
+        region.height(),
+    )
+}
+
+/// Returns the indices of the shapes overlapping `region`, by testing every shape.
+pub(crate) fn brute_force_query(shapes: &[&dyn Shape], region: &BoundingBox) -> Vec<usize> {
+    let area = region_shape(region);
+    (0..shapes.len())
+        .filter(|&i| intersects(shapes[i], &area))
+        .collect()
+}
+
+/// Returns every colliding pair (i, j) with i < j, by testing every pair. The bounding boxes are
+/// computed once up front, as the grid does, so the comparison is only between the two searches.
+pub(crate) fn brute_force_pairs(shapes: &[&dyn Shape]) -> Vec<(usize, usize)> {
+    let boxes: Vec<BoundingBox> = shapes.iter().map(|s| s.bounding_box()).collect();
+    let mut pairs = Vec::new();
+    for i in 0..shapes.len() {
+        for j in i + 1..shapes.len() {
+            if boxes[i].intersects(&boxes[j]) && intersects(shapes[i], shapes[j]) {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+/// A uniform grid over the plane, listing which shapes each cell may contain.
+pub(crate) struct Grid<'a> {
+    shapes: Vec<&'a dyn Shape>,
+    boxes: Vec<BoundingBox>,
+    cell_size: f64,
+    /// The indices of the shapes whose bounding box overlaps each non-empty cell.
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    /// The indices of the shapes covering more than MAX_CELLS_PER_SHAPE cells, in increasing
+    /// order. They are listed under no cell.
+    large: Vec<usize>,
+}
+
+impl<'a> Grid<'a> {
+    /// Indexes the shapes.
+    ///
+    /// # Arguments
+    ///
+    /// * `shapes` - The shapes to index; queries return positions in this slice.
+    /// * `cell_size` - The side of each cell. About the size of a typical shape works best: much
+    ///   smaller cells list each shape many times, much larger ones put many shapes in each cell.
+    ///   However small the cells, no shape is listed under more than MAX_CELLS_PER_SHAPE of them,
+    ///   so memory stays proportional to the number of shapes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell_size` is not positive.
+    pub(crate) fn new(shapes: &[&'a dyn Shape], cell_size: f64) -> Grid<'a> {
+        assert!(cell_size > 0.0, "the cell size must be positive");
+        let mut grid = Grid {
+            shapes: shapes.to_vec(),
+            boxes: shapes.iter().map(|s| s.bounding_box()).collect(),
+            cell_size,
+            cells: HashMap::new(),
+            large: Vec::new(),
+        };
+        for (i, b) in grid.boxes.iter().enumerate() {
+            // Boxes within TOLERANCE of each other count as touching, so each box is listed under
+            // the cells within TOLERANCE of it too.
+            let b = inflated(b);
+            let count = grid.cell_count(&b);
+            if count > MAX_CELLS_PER_SHAPE || count.is_nan() {
+                grid.large.push(i);
+                continue;
+            }
+            for cell in grid.cells_covering(&b) {
+                grid.cells.entry(cell).or_default().push(i);
+            }
+        }
+        grid
+    }
+
+    /// Returns how many cells a box overlaps. It is a float, as the count of a huge box overflows
+    /// any integer, and NaN for a box with NaN coordinates.
+    fn cell_count(&self, b: &BoundingBox) -> f64 {
+        let span =
+            |lo: f64, hi: f64| (hi / self.cell_size).floor() - (lo / self.cell_size).floor() + 1.0;
+        span(b.min.x, b.max.x) * span(b.min.y, b.max.y)
+    }
+
+    fn cell_of(&self, point: Point) -> (i64, i64) {
+        (
+            (point.x / self.cell_size).floor() as i64,
+            (point.y / self.cell_size).floor() as i64,
+        )
+    }
+
+    /// Returns every cell a box overlaps, row by row.
+    fn cells_covering(&self, b: &BoundingBox) -> impl Iterator<Item = (i64, i64)> + use<> {
+        let (x0, y0) = self.cell_of(b.min);
+        let (x1, y1) = self.cell_of(b.max);
+        (y0..=y1).flat_map(move |y| (x0..=x1).map(move |x| (x, y)))
+    }
+
+    /// Returns the indices of the shapes overlapping `region`, in increasing order. A region
+    /// covering more cells than there are shapes is checked against every shape instead, since
+    /// walking its cells would take longer.
+    pub(crate) fn query(&self, region: &BoundingBox) -> Vec<usize> {
+        let area = region_shape(region);
+        let count = self.cell_count(region);
+        let mut found: Vec<usize> = if count <= self.shapes.len() as f64 {
+            self.cells_covering(region)
+                .filter_map(|cell| self.cells.get(&cell))
+                .flatten()
+                .chain(&self.large)
+                .copied()
+                .collect()
+        } else {
+            (0..self.shapes.len()).collect()
+        };
+        found.sort_unstable();
+        found.dedup();
+        found.retain(|&i| intersects(self.shapes[i], &area));
+        found
+    }
+
+    /// Returns every colliding pair (i, j) with i < j, in increasing order. Each cell lists its
+    /// shapes in increasing order, so i < j holds within a cell.
+    pub(crate) fn colliding_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for (&cell, members) in &self.cells {
+            for (k, &i) in members.iter().enumerate() {
+                for &j in &members[k + 1..] {
+                    let (a, b) = (&self.boxes[i], &self.boxes[j]);
+                    // Two boxes can share several cells; the pair is only tested in the cell
+                    // holding the lower-left corner of their overlap, so it is reported once. The
+                    // corner is taken from the inflated boxes the cells were assigned from.
+                    let corner = Point::new(
+                        a.min.x.max(b.min.x) - TOLERANCE,
+                        a.min.y.max(b.min.y) - TOLERANCE,
+                    );
+                    if a.intersects(b)
+                        && self.cell_of(corner) == cell
+                        && intersects(self.shapes[i], self.shapes[j])
+                    {
+                        pairs.push((i, j));
+                    }
+                }
+            }
+        }
+        // The large shapes are in no cell, so each is tested against every other shape.
+        for (k, &i) in self.large.iter().enumerate() {
+            for j in (0..self.shapes.len()).filter(|j| !self.large[..=k].contains(j)) {
+                if self.boxes[i].intersects(&self.boxes[j])
+                    && intersects(self.shapes[i], self.shapes[j])
+                {
+                    pairs.push((i.min(j), i.max(j)));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+/// Returns the box grown by TOLERANCE on every side.
+fn inflated(b: &BoundingBox) -> BoundingBox {
+    let margin = Point::new(TOLERANCE, TOLERANCE);
+    BoundingBox {
+        min: b.min - margin,
+        max: b.max + margin,
+    }
+}
+
+/// Scatters `n` shapes of every type, of sizes 0.5 to 2.5, over a square that grows with `n` so
+/// the number of neighbors per shape stays about the same.
+fn random_shapes(n: usize, rng: &mut XorShift) -> Vec<Box<dyn Shape>> {
+    let side = (n as f64).sqrt() * 3.0;
+    (0..n)
+        .map(|_| -> Box<dyn Shape> {
+            let center = Point::new(rng.next_f64() * side, rng.next_f64() * side);
+            let size = 0.5 + rng.next_f64() * 2.0;
+            let angle = rng.next_f64() * 2.0 * PI;
+            match rng.range(0, 5) {
+                0 => Box::new(Circle::new(center, size / 2.0)),
+                1 => Box::new(Square::new(center, size).rotate(angle, center)),
+                2 => Box::new(Rectangle::new(center, size, size / 3.0).rotate(angle, center)),
+                3 => Box::new(Ellipse::new(center, size / 2.0, size / 4.0).rotate(angle, center)),
+                4 => Box::new(
+                    Triangle::new(
+                        center,
+                        center + Point::new(size, 0.0),
+                        center + Point::new(0.0, size),
+                    )
+                    .rotate(angle, center),
+                ),
+                _ => Box::new(Polygon::regular(
+                    center,
+                    size / 2.0,
+                    3 + rng.range(0, 5) as usize,
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Returns a random region of the same scale as the shapes.
+fn random_region(n: usize, rng: &mut XorShift) -> BoundingBox {
+    let side = (n as f64).sqrt() * 3.0;
+    let min = Point::new(rng.next_f64() * side, rng.next_f64() * side);
+    BoundingBox {
+        min,
+        max: min + Point::new(1.0 + rng.next_f64() * 5.0, 1.0 + rng.next_f64() * 5.0),
+    }
+}
+
+/// Times finding all collisions, and answering region queries, with and without the grid, and
+/// checks that both find the same shapes.
+///
+/// # Arguments
+///
+/// * `max_exponent` - The largest scene has 10^max_exponent shapes. Brute force is quadratic, so
+///   10^5 already takes seconds.
+pub(crate) fn run(max_exponent: u32) {
+    const QUERIES: usize = 100;
+    println!(
+        "{:>7} {:>9} {:>14} {:>14} {:>14} {:>14}",
+        "shapes", "pairs", "brute pairs", "grid pairs", "brute queries", "grid queries"
+    );
+    let mut rng = XorShift::new(0x9e37_79b9_7f4a_7c15);
+    for exponent in 2..=max_exponent {
+        let n = 10usize.pow(exponent);
+        let boxed = random_shapes(n, &mut rng);
+        let shapes: Vec<&dyn Shape> = boxed.iter().map(|s| s.as_ref()).collect();
+        let regions: Vec<BoundingBox> = (0..QUERIES).map(|_| random_region(n, &mut rng)).collect();
+
+        let start = Instant::now();
+        let expected = brute_force_pairs(&shapes);
+        let brute_pairs = start.elapsed();
+        let start = Instant::now();
+        let grid = Grid::new(&shapes, 2.5);
+        assert_eq!(grid.colliding_pairs(), expected);
+        let grid_pairs = start.elapsed();
+
+        let start = Instant::now();
+        let expected: Vec<Vec<usize>> = regions
+            .iter()
+            .map(|r| brute_force_query(&shapes, r))
+            .collect();
+        let brute_queries = start.elapsed();
+        let start = Instant::now();
+        for (region, expected) in regions.iter().zip(&expected) {
+            assert_eq!(&grid.query(region), expected);
+        }
+        let grid_queries = start.elapsed();
+
+        println!(
+            "{:>7} {:>9} {:>11.3} ms {:>11.3} ms {:>11.3} ms {:>11.3} ms",
+            n,
+            grid.colliding_pairs().len(),
+            brute_pairs.as_secs_f64() * 1e3,
+            grid_pairs.as_secs_f64() * 1e3,
+            brute_queries.as_secs_f64() * 1e3,
+            grid_queries.as_secs_f64() * 1e3
+        );
+    }
+}
+
+/// Returns whether some point of a fine lattice lies in both shapes: a slow but independent
+/// check that intersects does not miss overlaps.
+fn shared_point(a: &dyn Shape, b: &dyn Shape) -> bool {
+    const STEPS: usize = 40;
+    let (ba, bb) = (a.bounding_box(), b.bounding_box());
+    if !ba.intersects(&bb) {
+        return false;
+    }
+    let min = Point::new(ba.min.x.max(bb.min.x), ba.min.y.max(bb.min.y));
+    let max = Point::new(ba.max.x.min(bb.max.x), ba.max.y.min(bb.max.y));
+    (0..=STEPS).any(|i| {
+        (0..=STEPS).any(|j| {
+            let p = Point::new(
+                min.x + (max.x - min.x) * i as f64 / STEPS as f64,
+                min.y + (max.y - min.y) * j as f64 / STEPS as f64,
+            );
+            a.contains(p) && b.contains(p)
+        })
+    })
+}
+
+/// Demonstrates the intersection tests on every pair of shape types, and the grid index.
+pub fn collision_demo() {
+    println!("Collision detection:");
+    let circle = Circle::new(Point::ORIGIN, 1.0);
+    let square = Square::new(Point::new(1.5, 0.0), 1.0);
+    let diamond = Square::new(Point::new(2.0, 2.0), 2.0).rotate(PI / 4.0, Point::new(2.0, 2.0));
+    let ellipse = Ellipse::new(Point::new(-3.0, 0.0), 1.5, 0.5);
+    let triangle = Triangle::new(
+        Point::new(0.0, 1.0),
+        Point::new(1.0, 3.0),
+        Point::new(-1.0, 3.0),
+    );
+    let l_shape = Polygon::new(vec![
+        Point::new(4.0, -2.0),
+        Point::new(6.0, -2.0),
+        Point::new(6.0, -1.0),
+        Point::new(5.0, -1.0),
+        Point::new(5.0, 0.0),
+        Point::new(4.0, 0.0),
+    ]);
+    let named: [(&str, &dyn Shape); 6] = [
+        ("circle", &circle),
+        ("square", &square),
+        ("diamond", &diamond),
+        ("ellipse", &ellipse),
+        ("triangle", &triangle),
+        ("L shape", &l_shape),
+    ];
+    for (i, (a_name, a)) in named.iter().enumerate() {
+        for (b_name, b) in &named[i + 1..] {
+            if intersects(*a, *b) {
+                println!("{} intersects {}", a_name, b_name);
+            }
+        }
+    }
+
+    // Touching counts: the square's left edge touches the circle, and the triangle's tip rests on
+    // top of it. The ellipse ends 0.5 before the circle.
+    assert!(intersects(&circle, &square) && intersects(&square, &circle));
+    assert!(intersects(&circle, &triangle));
+    assert!(!intersects(&circle, &ellipse));
+    assert!(intersects(
+        &circle,
+        &ellipse.translate(Point::new(0.5, 0.0))
+    ));
+    // The diamond's lowest corner is at (2, 2 - √2); the square's top right corner at (2, 0.5).
+    assert!(!intersects(&square, &diamond));
+    assert!(intersects(
+        &square.translate(Point::new(0.5, 0.1)),
+        &diamond
+    ));
+    // A circle in the L shape's notch touches neither arm, though it is inside its bounding box.
+    let notch = Circle::new(Point::new(5.6, -0.4), 0.35);
+    assert!(notch.bounding_box().intersects(&l_shape.bounding_box()));
+    assert!(!intersects(&notch, &l_shape));
+    assert!(intersects(&notch.scale(2.0, notch.center), &l_shape));
+    // A small square in the notch: the L shape is not convex, so SAT would wrongly report a hit.
+    let block = Square::new(Point::new(5.5, -0.5), 0.8);
+    assert!(!intersects(&block, &l_shape));
+    assert!(separating_axis(&block.corners(), &l_shape.vertices));
+    // Shapes inside others intersect even though their boundaries do not cross.
+    assert!(intersects(
+        &Circle::new(Point::new(4.5, -1.5), 0.1),
+        &l_shape
+    ));
+    assert!(intersects(
+        &Triangle::new(
+            Point::new(4.2, -1.8),
+            Point::new(4.4, -1.8),
+            Point::new(4.2, -1.6)
+        ),
+        &l_shape
+    ));
+    assert!(intersects(&ellipse, &Circle::new(ellipse.center, 0.1)));
+    assert!(intersects(
+        &Ellipse::new(Point::ORIGIN, 3.0, 2.0),
+        &triangle
+    ));
+
+    // A repeated vertex makes a zero-length edge, which SAT must not take as a separating axis.
+    let repeated = Polygon::new(vec![
+        Point::new(0.0, 0.0),
+        Point::new(2.0, 0.0),
+        Point::new(2.0, 0.0),
+        Point::new(2.0, 2.0),
+        Point::new(0.0, 2.0),
+    ]);
+    let inside = Square::new(Point::new(1.0, 1.0), 1.0);
+    assert!(intersects(&repeated, &inside));
+    assert!(intersects(
+        &repeated,
+        &Circle::new(Point::new(2.5, 0.0), 0.5)
+    ));
+
+    // SAT and the general polygon test agree on convex polygons.
+    let mut rng = XorShift::new(0x2545_f491_4f6c_dd1d);
+    let boxed = random_shapes(300, &mut rng);
+    let shapes: Vec<&dyn Shape> = boxed.iter().map(|s| s.as_ref()).collect();
+    for a in &shapes {
+        for b in &shapes {
+            if let (Outline::Polygon(pa), Outline::Polygon(pb)) = (a.outline(), b.outline())
+                && a.bounding_box().intersects(&b.bounding_box())
+            {
+                assert_eq!(separating_axis(&pa, &pb), polygons_intersect(&pa, &pb));
+            }
+        }
+    }
+
+    // Every pair with a common point is found, and the grid agrees with brute force.
+    let pairs = brute_force_pairs(&shapes);
+    for (i, a) in shapes.iter().enumerate() {
+        for (j, b) in shapes.iter().enumerate().skip(i + 1) {
+            if shared_point(*a, *b) {
+                assert!(
+                    pairs.contains(&(i, j)),
+                    "missed the collision of {} and {}",
+                    i,
+                    j
+                );
+            }
+        }
+    }
+    for cell_size in [0.5, 2.5, 10.0, 100.0] {
+        let grid = Grid::new(&shapes, cell_size);
+        assert_eq!(grid.colliding_pairs(), pairs);
+        for _ in 0..50 {
+            let region = random_region(shapes.len(), &mut rng);
+            assert_eq!(grid.query(&region), brute_force_query(&shapes, &region));
+        }
+    }
+    // Boxes within TOLERANCE of each other touch, even when a cell boundary lies between them.
+    let left = Rectangle::new(Point::new(1.25, 0.5), 1.0 - 2e-10, 1.0);
+    let right = Rectangle::new(Point::new(2.25, 0.5), 1.0, 1.0);
+    assert_eq!(left.bounding_box().max.x, 1.75 - 1e-10);
+    let touching: [&dyn Shape; 2] = [&left, &right];
+    assert_eq!(brute_force_pairs(&touching), [(0, 1)]);
+    assert_eq!(Grid::new(&touching, 1.75).colliding_pairs(), [(0, 1)]);
+    // A shape covering millions of cells is kept out of them and still found.
+    let mut with_large = shapes.clone();
+    let huge = Square::new(Point::new(25.0, 25.0), 1e4);
+    with_large.push(&huge);
+    let grid = Grid::new(&with_large, 0.5);
+    assert_eq!(grid.large, [shapes.len()]);
+    assert!(grid.cells.values().map(Vec::len).sum::<usize>() < 64 * with_large.len());
+    assert_eq!(grid.colliding_pairs(), brute_force_pairs(&with_large));
+    let region = random_region(shapes.len(), &mut rng);
+    assert_eq!(grid.query(&region), brute_force_query(&with_large, &region));
+    // So is a region covering billions of cells, without visiting them.
+    let everywhere = BoundingBox {
+        min: Point::new(-5e4, -5e4),
+        max: Point::new(5e4, 5e4),
+    };
+    assert_eq!(
+        grid.query(&everywhere),
+        (0..with_large.len()).collect::<Vec<_>>()
+    );
+    let unbounded = BoundingBox {
+        min: Point::new(f64::NEG_INFINITY, 0.0),
+        max: Point::new(f64::INFINITY, 1.0),
+    };
+    assert_eq!(
+        grid.query(&unbounded),
+        brute_force_query(&with_large, &unbounded)
+    );
+
+    println!(
+        "{} random shapes collide in {} pairs",
+        shapes.len(),
+        pairs.len()
+    );
+}
@@ -0,0 +1,268 @@
+/*
+    Parsing and formatting Complex numbers.
+
+    FromStr lets a string be turned into a value with `s.parse()`, and Display controls how a value
+    is printed with `{}`. Together they give Complex a text form that round-trips: anything printed
+    with `{}` can be parsed back.
+
+    Accepted input forms (whitespace between the parts is ignored):
+    - "3+4i", "3 - 4i", "1e3-2j": a real part followed by a signed imaginary part
+    - "-2.5i", "i", "-j": a purely imaginary number
+    - "7", "-0.5": a purely real number
+    - "(1,2)", "(1, -2)": a tuple of the real and imaginary parts
+    Both `i` and `j` (the engineering convention) are accepted as the imaginary unit.
+*/
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::num::Num;
+use crate::operator_overloading::Complex;
+
+/// The reasons a string can fail to parse as a Complex number.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ParseComplexError {
+    /// The input was empty or only whitespace.
+    Empty,
+    /// The input opened a parenthesis without closing it, or vice versa.
+    UnbalancedParens(String),
+    /// A parenthesised tuple did not have exactly two comma-separated parts.
+    WrongTupleArity(String),
+    /// The input had two terms but the second was missing its `i` or `j` suffix.
+    MissingImaginaryUnit(String),
+    /// The input had more terms than a real and an imaginary part.
+    TooManyTerms(String),
+    /// One of the parts was not a valid number of the component type.
+    InvalidNumber { part: String, reason: String },
+}
+
+impl fmt::Display for ParseComplexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseComplexError::Empty => {
+                write!(f, "cannot parse a complex number from an empty string")
+            }
+            ParseComplexError::UnbalancedParens(s) => {
+                write!(f, "unbalanced parentheses in '{}'", s)
+            }
+            ParseComplexError::WrongTupleArity(s) => {
+                write!(
+                    f,
+                    "expected a tuple of two parts like '(1, 2)', found '{}'",
+                    s
+                )
+            }
+            ParseComplexError::MissingImaginaryUnit(s) => write!(
+                f,
+                "the second term of '{}' must end with the imaginary unit 'i' or 'j'",
+                s
+            ),
+            ParseComplexError::TooManyTerms(s) => write!(
+                f,
+                "'{}' has too many terms: expected a real part and an imaginary part",
+                s
+            ),
+            ParseComplexError::InvalidNumber { part, reason } => {
+                write!(f, "invalid number '{}': {}", part, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseComplexError {}
+
+/// Parses a single component, turning the component type's error into a descriptive one.
+fn parse_part<T>(part: &str) -> Result<T, ParseComplexError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    part.parse()
+        .map_err(|e: T::Err| ParseComplexError::InvalidNumber {
+            part: part.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// Parses the coefficient of an imaginary term with its unit already removed. A bare sign stands
+/// for a coefficient of one, so "i" is 1i and "-j" is -1i.
+fn parse_imaginary<T>(coefficient: &str) -> Result<T, ParseComplexError>
+where
+    T: FromStr + Num,
+    T::Err: fmt::Display,
+{
+    match coefficient {
+        "" | "+" => Ok(T::one()),
+        "-" => parse_part("-1"),
+        _ => parse_part(coefficient),
+    }
+}
+
+/// Strips the imaginary unit from a term, returning None if the term is real.
+fn imaginary(term: &str) -> Option<&str> {
+    term.strip_suffix(['i', 'j'])
+}
+
+/// Returns the byte index where the second term starts: a `+` or `-` that is not the leading sign
+/// and not the sign of an exponent (as in "1e-3").
+fn split_index(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    (1..bytes.len())
+        .find(|&i| matches!(bytes[i], b'+' | b'-') && !matches!(bytes[i - 1], b'e' | b'E'))
+}
+
+impl<T> FromStr for Complex<T>
+where
+    T: FromStr + Num,
+    T::Err: fmt::Display,
+{
+    type Err = ParseComplexError;
+
+    /// Parses any of the forms listed in the module documentation. The implicit zero and one of
+    /// forms like "3" and "i" come from the Num trait.
+    fn from_str(s: &str) -> Result<Complex<T>, ParseComplexError> {
+        let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if compact.is_empty() {
+            return Err(ParseComplexError::Empty);
+        }
+
+        match (compact.starts_with('('), compact.ends_with(')')) {
+            (true, true) => {
+                let inner = &compact[1..compact.len() - 1];
+                let parts: Vec<&str> = inner.split(',').collect();
+                let [re, im] = parts[..] else {
+                    return Err(ParseComplexError::WrongTupleArity(s.to_string()));
+                };
+                return Ok(Complex::new(parse_part(re)?, parse_part(im)?));
+            }
+            (false, false) if !compact.contains(['(', ')']) => {}
+            _ => return Err(ParseComplexError::UnbalancedParens(s.to_string())),
+        }
+
+        match split_index(&compact) {
+            None => match imaginary(&compact) {
+                Some(coefficient) => Ok(Complex::new(T::zero(), parse_imaginary(coefficient)?)),
+                None => Ok(Complex::new(parse_part(&compact)?, T::zero())),
+            },
+            Some(at) => {
+                let (re, im) = compact.split_at(at);
+                if split_index(im).is_some() {
+                    return Err(ParseComplexError::TooManyTerms(s.to_string()));
+                }
+                let coefficient = imaginary(im)
+                    .ok_or_else(|| ParseComplexError::MissingImaginaryUnit(s.to_string()))?;
+                Ok(Complex::new(parse_part(re)?, parse_imaginary(coefficient)?))
+            }
+        }
+    }
+}
+
+/// Formats a Complex number as "re+imi", for example "3-4i".
+///
+/// The formatter flags apply to both parts: `{:.3}` prints each part with three decimals
+/// ("1.000+2.000i") and `{:+}` also signs the real part ("+1+2i"). A width pads the whole number
+/// according to the fill and alignment, so `{:>12.1}` right-aligns "1.0+2.0i" in twelve columns.
+impl<T> fmt::Display for Complex<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let re = match (f.precision(), f.sign_plus()) {
+            (Some(p), true) => format!("{:+.*}", p, self.re),
+            (Some(p), false) => format!("{:.*}", p, self.re),
+            (None, true) => format!("{:+}", self.re),
+            (None, false) => format!("{}", self.re),
+        };
+        let mut im = match f.precision() {
+            Some(p) => format!("{:+.*}", p, self.im),
+            None => format!("{:+}", self.im),
+        };
+        // NaN is printed without a sign even with the `+` flag.
+        if !im.starts_with(['+', '-']) {
+            im.insert(0, '+');
+        }
+        let text = format!("{}{}i", re, im);
+
+        let padding = f.width().unwrap_or(0).saturating_sub(text.chars().count());
+        let (before, after) = match f.align() {
+            Some(fmt::Alignment::Left) => (0, padding),
+            Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+            Some(fmt::Alignment::Right) | None => (padding, 0),
+        };
+        let fill = f.fill().to_string();
+        write!(f, "{}{}{}", fill.repeat(before), text, fill.repeat(after))
+    }
+}
+
+/// Demonstrates parsing and formatting Complex numbers.
+pub fn complex_format_demo() {
+    println!("Complex parsing and formatting:");
+
+    for input in [
+        "3+4i",
+        "-2.5i",
+        "1e3-2j",
+        "(1,2)",
+        "3 - 4i",
+        "i",
+        "-j",
+        "7",
+        "1e-3+2E+2i",
+    ] {
+        let z: Complex<f64> = input.parse().unwrap();
+        println!("{:>12} parses to {}", input, z);
+    }
+    assert_eq!("3+4i".parse(), Ok(Complex::new(3.0, 4.0)));
+    assert_eq!("-2.5i".parse(), Ok(Complex::new(0.0, -2.5)));
+    assert_eq!("1e3-2j".parse(), Ok(Complex::new(1000.0, -2.0)));
+    assert_eq!("(1,2)".parse(), Ok(Complex::new(1.0, 2.0)));
+    assert_eq!("1e-3+2E+2i".parse(), Ok(Complex::new(0.001, 200.0)));
+    assert_eq!("-j".parse(), Ok(Complex::new(0.0, -1.0)));
+    assert_eq!("7".parse(), Ok(Complex::new(7, 0)));
+    assert_eq!("( 4 , -5 )".parse(), Ok(Complex::new(4, -5)));
+
+    for input in ["", "3+4", "1+2i+3i", "(1,2", "(1,2,3)", "x+2i", "2.5+1i"] {
+        let err = input.parse::<Complex<i32>>().unwrap_err();
+        println!("{:?} fails: {}", input, err);
+    }
+    assert_eq!("".parse::<Complex<f64>>(), Err(ParseComplexError::Empty));
+    assert!(matches!(
+        "3+4".parse::<Complex<f64>>(),
+        Err(ParseComplexError::MissingImaginaryUnit(_))
+    ));
+    assert!(matches!(
+        "1+2i+3i".parse::<Complex<f64>>(),
+        Err(ParseComplexError::TooManyTerms(_))
+    ));
+    assert!(matches!(
+        "(1,2".parse::<Complex<f64>>(),
+        Err(ParseComplexError::UnbalancedParens(_))
+    ));
+    assert!(matches!(
+        "(1,2,3)".parse::<Complex<f64>>(),
+        Err(ParseComplexError::WrongTupleArity(_))
+    ));
+    assert!(matches!(
+        "2.5+1i".parse::<Complex<i32>>(),
+        Err(ParseComplexError::InvalidNumber { part, .. }) if part == "2.5"
+    ));
+
+    let z = Complex::new(1.0, 2.0);
+    assert_eq!(format!("{}", z), "1+2i");
+    assert_eq!(format!("{:.3}", z), "1.000+2.000i");
+    assert_eq!(format!("{:+.1}", z), "+1.0+2.0i");
+    assert_eq!(format!("{:.2}", Complex::new(-0.5, -1.25)), "-0.50-1.25i");
+    assert_eq!(format!("{:>10}", Complex::new(3, -4)), "      3-4i");
+    assert_eq!(format!("{:*^10}", Complex::new(3, -4)), "***3-4i***");
+    assert_eq!(format!("{}", Complex::new(1.0, f64::NAN)), "1+NaNi");
+    println!("{{:.3}} of {:?} is {:.3}", z, z);
+
+    // Printing with `{}` and parsing back gives the same number.
+    for z in [
+        Complex::new(-1.5, 0.25),
+        Complex::new(1e300, -1e-300),
+        Complex::new(0.0, -0.0),
+    ] {
+        assert_eq!(z.to_string().parse(), Ok(z));
+    }
+}
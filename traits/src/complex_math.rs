@@ -0,0 +1,413 @@
+/*
+    Complex analysis on top of the Complex type from operator_overloading.
+
+    Inherent methods can be added to a type from any module of the crate that defines it, so the
+    floating-point functions for Complex<T> live here instead of growing the operator module. They
+    are generic over the Float trait from the num module, so they work for both f32 and f64 parts.
+
+    Branch cuts:
+    Functions like ln and sqrt are multi-valued on the complex plane, so each returns its principal
+    value and is discontinuous along a "branch cut":
+    - arg, ln, sqrt and powf cut along the negative real axis. arg returns values in [-π, π]; the
+      sign of a zero imaginary part picks the side of the cut, so ln(-1 + 0i) = iπ while
+      ln(-1 - 0i) = -iπ, matching IEEE 754 and C99.
+    - asin and acos cut along the real axis outside [-1, 1]; atan cuts along the imaginary axis
+      outside [-i, i].
+
+    The inverse sine follows Kahan's formulation in terms of sqrt(1 - z) and sqrt(1 + z) rather
+    than -i ln(iz + sqrt(1 - z²)): the square roots see the sign of a zero imaginary part, so
+    asin(2 + 0i) and asin(2 - 0i) land on opposite sides of the cut as in C99, and nothing is
+    squared, so large arguments do not overflow.
+
+    NaN and infinity:
+    Following C99 Annex G, a complex number is infinite if either part is infinite, even when the
+    other part is NaN, and NaN inputs otherwise propagate to NaN outputs. Special cases where naive
+    formulas would produce a spurious NaN (like 0 * ∞ inside exp, sinh or cosh) are handled
+    explicitly.
+*/
+
+use std::f64::consts::PI;
+
+use crate::num::{Float, One, Zero};
+use crate::operator_overloading::Complex;
+
+/// The constant 2 in any float type.
+fn two<T: Float>() -> T {
+    T::one() + T::one()
+}
+
+impl<T: Float> Complex<T> {
+    /// Returns true if either part is NaN and neither part is infinite.
+    pub(crate) fn is_nan(&self) -> bool {
+        (self.re.is_nan() || self.im.is_nan()) && !self.is_infinite()
+    }
+
+    /// Returns true if either part is infinite, even when the other part is NaN.
+    pub(crate) fn is_infinite(&self) -> bool {
+        self.re.is_infinite() || self.im.is_infinite()
+    }
+
+    /// Returns true if both parts are finite.
+    pub(crate) fn is_finite(&self) -> bool {
+        self.re.is_finite() && self.im.is_finite()
+    }
+
+    /// The squared magnitude re² + im². Cheaper than abs when only comparing sizes.
+    pub(crate) fn norm_sqr(&self) -> T {
+        self.re * self.re + self.im * self.im
+    }
+
+    /// The magnitude |z|. Uses hypot, which avoids overflow for large parts and returns infinity
+    /// when either part is infinite, even if the other is NaN.
+    pub(crate) fn abs(&self) -> T {
+        self.re.hypot(self.im)
+    }
+
+    /// The argument (angle) of z in [-π, π], measured from the positive real axis.
+    pub(crate) fn arg(&self) -> T {
+        self.im.atan2(self.re)
+    }
+
+    /// Converts to polar form, returning (|z|, arg z).
+    pub(crate) fn to_polar(&self) -> (T, T) {
+        (self.abs(), self.arg())
+    }
+
+    /// Builds a complex number from a magnitude and an angle: r(cos θ + i sin θ).
+    pub(crate) fn from_polar(r: T, theta: T) -> Complex<T> {
+        // An angle of exactly zero keeps the imaginary part zero even for an infinite magnitude,
+        // where r * sin(0) would be ∞ * 0 = NaN.
+        if theta.is_zero() {
+            return Complex::new(r, theta);
+        }
+        Complex::new(r * theta.cos(), r * theta.sin())
+    }
+
+    /// The reciprocal 1/z.
+    pub(crate) fn recip(&self) -> Complex<T> {
+        Complex::<T>::one() / self.clone()
+    }
+
+    /// e raised to the power z: e^re (cos im + i sin im).
+    pub(crate) fn exp(&self) -> Complex<T> {
+        if self.re.is_infinite() && !self.im.is_finite() {
+            // exp(-∞ + i·y) tends to zero whatever y is; exp(+∞ + i·y) is infinite with an
+            // undefined direction.
+            return if self.re < T::zero() {
+                Complex::zero()
+            } else {
+                Complex::new(self.re, T::nan())
+            };
+        }
+        // from_polar keeps a real argument real, so exp(x + 0i) = e^x + 0i exactly.
+        Complex::from_polar(self.re.exp(), self.im)
+    }
+
+    /// The principal natural logarithm ln|z| + i arg z. The branch cut lies along the negative
+    /// real axis, and ln(0) = -∞.
+    pub(crate) fn ln(&self) -> Complex<T> {
+        Complex::new(self.abs().ln(), self.arg())
+    }
+
+    /// The principal square root, with a non-negative real part. The branch cut lies along the
+    /// negative real axis; the sign of the imaginary part of the result follows that of z.
+    pub(crate) fn sqrt(&self) -> Complex<T> {
+        if self.im.is_infinite() {
+            return Complex::new(T::infinity(), self.im);
+        }
+        if self.re.is_nan() || self.im.is_nan() {
+            return Complex::new(T::nan(), T::nan());
+        }
+        if self.re.is_zero() && self.im.is_zero() {
+            return Complex::new(T::zero(), self.im);
+        }
+        if self.re.is_infinite() {
+            return if self.re > T::zero() {
+                Complex::new(self.re, T::zero().copysign(self.im))
+            } else {
+                Complex::new(T::zero(), T::infinity().copysign(self.im))
+            };
+        }
+        // t = sqrt((|re| + |z|) / 2) avoids the cancellation in (|z| - re) when re < 0.
+        let t = ((self.re.abs() + self.abs()) / two::<T>()).sqrt();
+        if self.re >= T::zero() {
+            Complex::new(t, self.im / (two::<T>() * t))
+        } else {
+            Complex::new(self.im.abs() / (two::<T>() * t), t.copysign(self.im))
+        }
+    }
+
+    /// Raises z to a real power through polar form: |z|^n (cos nθ + i sin nθ). z^0 is 1 for every
+    /// z, including 0 and NaN, matching f64::powf.
+    pub(crate) fn powf(&self, n: T) -> Complex<T> {
+        if n.is_zero() {
+            return Complex::one();
+        }
+        let (r, theta) = self.to_polar();
+        Complex::from_polar(r.powf(n), theta * n)
+    }
+
+    /// Raises z to a complex power, e^(w ln z). 0^w is 0 when w has a positive real part.
+    pub(crate) fn powc(&self, w: &Complex<T>) -> Complex<T> {
+        if w.re.is_zero() && w.im.is_zero() {
+            return Complex::one();
+        }
+        if self.re.is_zero() && self.im.is_zero() && w.re > T::zero() {
+            return Complex::zero();
+        }
+        (w * &self.ln()).exp()
+    }
+
+    /// sin z = -i sinh(iz), which inherits sinh's handling of zero and infinite parts.
+    pub(crate) fn sin(&self) -> Complex<T> {
+        let s = Complex::new(-self.im, self.re).sinh();
+        Complex::new(s.im, -s.re)
+    }
+
+    /// cos z = cosh(iz).
+    pub(crate) fn cos(&self) -> Complex<T> {
+        Complex::new(-self.im, self.re).cosh()
+    }
+
+    /// tan z = -i tanh(iz), which inherits tanh's overflow handling.
+    pub(crate) fn tan(&self) -> Complex<T> {
+        let iz = Complex::new(-self.im, self.re);
+        let t = iz.tanh();
+        Complex::new(t.im, -t.re)
+    }
+
+    /// sinh(a + bi) = sinh a cos b + i cosh a sin b. Where a part is zero or infinite, a product
+    /// would be 0 · ∞ = NaN, so those cases return the C99 limits directly.
+    pub(crate) fn sinh(&self) -> Complex<T> {
+        let (a, b) = (self.re, self.im);
+        if b.is_zero() {
+            return Complex::new(a.sinh(), b);
+        }
+        if a.is_zero() {
+            return Complex::new(a, b.sin());
+        }
+        if a.is_infinite() && !b.is_finite() {
+            return Complex::new(a, T::nan());
+        }
+        Complex::new(a.sinh() * b.cos(), a.cosh() * b.sin())
+    }
+
+    /// cosh(a + bi) = cosh a cos b + i sinh a sin b, with the same special cases as sinh.
+    pub(crate) fn cosh(&self) -> Complex<T> {
+        let (a, b) = (self.re, self.im);
+        if b.is_zero() {
+            // sinh a · b, without sinh(±∞) · 0.
+            return Complex::new(a.cosh(), T::one().copysign(a) * b);
+        }
+        if a.is_zero() {
+            let im = if b.is_finite() { a * b.sin() } else { a };
+            return Complex::new(b.cos(), im);
+        }
+        if a.is_infinite() && !b.is_finite() {
+            return Complex::new(T::infinity(), T::nan());
+        }
+        Complex::new(a.cosh() * b.cos(), a.sinh() * b.sin())
+    }
+
+    /// tanh(a + bi) = (sinh 2a + i sin 2b) / (cosh 2a + cos 2b). For large |a| both sinh and cosh
+    /// overflow, so the limit ±1 + 0i is returned directly.
+    pub(crate) fn tanh(&self) -> Complex<T> {
+        if self.re.abs() > T::from_f64(20.0) {
+            return Complex::new(
+                T::one().copysign(self.re),
+                T::zero().copysign((two::<T>() * self.im).sin()),
+            );
+        }
+        let (a, b) = (two::<T>() * self.re, two::<T>() * self.im);
+        let d = a.cosh() + b.cos();
+        Complex::new(a.sinh() / d, b.sin() / d)
+    }
+
+    /// The principal inverse sine. With ξ = sqrt(1 - z) and η = sqrt(1 + z), Kahan's formula is
+    /// asin z = atan(re z / re(ξη)) + i asinh(im(conj(ξ) η)).
+    pub(crate) fn asin(&self) -> Complex<T> {
+        let (x, y) = (self.re, self.im);
+        // Beyond 1/ε, 1 ± z rounds to ±z, and ξη, of size |z|, may overflow. Taking the roots of
+        // ±z/4 instead scales ξη down by 4; atan only needs the ratio x / re(ξη), and for such
+        // large arguments asinh(v) = ln 2|v| to within rounding.
+        let limit = T::one() / T::epsilon();
+        let large = x.abs() > limit || y.abs() > limit;
+        let four = two::<T>() * two::<T>();
+        let (xi, eta) = if large {
+            let (x, y) = (x / four, y / four);
+            (Complex::new(-x, -y).sqrt(), Complex::new(x, y).sqrt())
+        } else {
+            (
+                Complex::new(T::one() - x, -y).sqrt(),
+                Complex::new(T::one() + x, y).sqrt(),
+            )
+        };
+        // Written out rather than through Complex's Mul, so the signs of zero parts survive.
+        let re_product = xi.re * eta.re - xi.im * eta.im;
+        let im_conj_product = xi.re * eta.im - xi.im * eta.re;
+        if large {
+            // The true im(conj(ξ) η) is 4v, and asinh(4v) ≈ ln 8|v|.
+            let v = im_conj_product;
+            let im = (v.abs().ln() + (two::<T>() * four).ln()).copysign(v);
+            return Complex::new((x / four).atan2(re_product), im);
+        }
+        Complex::new(x.atan2(re_product), im_conj_product.asinh())
+    }
+
+    /// The principal inverse cosine, π/2 - asin z.
+    pub(crate) fn acos(&self) -> Complex<T> {
+        let a = self.asin();
+        Complex::new(T::pi() / two::<T>() - a.re, -a.im)
+    }
+
+    /// The principal inverse tangent, (i/2) (ln(1 - iz) - ln(1 + iz)). Undefined (infinite) at ±i.
+    pub(crate) fn atan(&self) -> Complex<T> {
+        let one = Complex::<T>::one();
+        let iz = Complex::new(-self.im, self.re);
+        let d = (&one - &iz).ln() - (one + iz).ln();
+        Complex::new(-d.im / two::<T>(), d.re / two::<T>())
+    }
+}
+
+/// Returns whether two complex numbers are within `eps` of each other.
+fn approx_eq(a: &Complex<f64>, b: &Complex<f64>, eps: f64) -> bool {
+    (a - b).abs() <= eps
+}
+
+/// Demonstrates the complex functions and checks them against well-known identities.
+pub fn complex_math_demo() {
+    println!("Complex math:");
+    const EPS: f64 = 1e-12;
+    let z = Complex::new(3.0, 4.0);
+
+    println!(
+        "z = {:?}, |z| = {}, arg z = {}, conj z = {:?}",
+        z,
+        z.abs(),
+        z.arg(),
+        z.conj()
+    );
+    assert_eq!(z.abs(), 5.0);
+    assert_eq!(&z * &z.conj(), Complex::new(z.norm_sqr(), 0.0));
+
+    // Euler's identity: e^(iπ) + 1 = 0.
+    let euler = (Complex::i() * PI).exp() + 1.0;
+    println!("e^(iπ) + 1 = {:?}", euler);
+    assert!(approx_eq(&euler, &Complex::new(0.0, 0.0), EPS));
+
+    let (r, theta) = z.to_polar();
+    assert!(approx_eq(&Complex::from_polar(r, theta), &z, EPS));
+    assert!(approx_eq(&z.ln().exp(), &z, EPS));
+    assert!(approx_eq(&(z.sqrt() * z.sqrt()), &z, EPS));
+    assert!(approx_eq(&z.powf(0.5), &z.sqrt(), EPS));
+    assert!(approx_eq(&z.powf(3.0), &(&z * &z * &z), 1e-10));
+    assert!(approx_eq(
+        &z.powc(&Complex::new(2.0, 0.0)),
+        &(&z * &z),
+        1e-10
+    ));
+    assert!(approx_eq(&(&z * &z.recip()), &Complex::new(1.0, 0.0), EPS));
+
+    // sin² z + cos² z = 1, and the inverse functions undo the forward ones near the origin.
+    let w = Complex::new(0.5, -0.25);
+    let one = w.sin() * w.sin() + w.cos() * w.cos();
+    assert!(approx_eq(&one, &Complex::new(1.0, 0.0), EPS));
+    assert!(approx_eq(&w.sin().asin(), &w, EPS));
+    assert!(approx_eq(&w.cos().acos(), &w, EPS));
+    assert!(approx_eq(&w.tan().atan(), &w, EPS));
+    assert!(approx_eq(&w.tan(), &(w.sin() / w.cos()), EPS));
+    assert!(approx_eq(&w.tanh(), &(w.sinh() / w.cosh()), EPS));
+    println!(
+        "sin(w) = {:?}, atan(tan(w)) = {:?}",
+        w.sin(),
+        w.tan().atan()
+    );
+
+    // The functions are generic over Float, so f32 parts work as well.
+    let z32: Complex<f32> = Complex::new(3.0, 4.0);
+    assert_eq!(z32.abs(), 5.0);
+    assert!((z32.ln().exp() - z32).abs() < 1e-5);
+
+    // The sign of zero selects the side of the branch cut on the negative real axis.
+    println!("sqrt(-4 + 0i) = {:?}", Complex::new(-4.0, 0.0).sqrt());
+    println!("sqrt(-4 - 0i) = {:?}", Complex::new(-4.0, -0.0).sqrt());
+    assert_eq!(Complex::new(-4.0, 0.0).sqrt(), Complex::new(0.0, 2.0));
+    assert_eq!(Complex::new(-4.0, -0.0).sqrt(), Complex::new(0.0, -2.0));
+    assert_eq!(Complex::new(-1.0, 0.0).ln(), Complex::new(0.0, PI));
+    assert_eq!(Complex::new(-1.0, -0.0).ln(), Complex::new(0.0, -PI));
+
+    // NaN and infinity handling.
+    let inf_nan = Complex::new(f64::INFINITY, f64::NAN);
+    assert!(inf_nan.is_infinite() && !inf_nan.is_nan());
+    assert_eq!(inf_nan.abs(), f64::INFINITY);
+    assert!(Complex::new(f64::NAN, 1.0).exp().is_nan());
+    assert_eq!(
+        Complex::new(f64::NEG_INFINITY, f64::NAN).exp(),
+        Complex::new(0.0, 0.0)
+    );
+    assert_eq!(
+        Complex::new(f64::INFINITY, 0.0).exp(),
+        Complex::new(f64::INFINITY, 0.0)
+    );
+    assert_eq!(Complex::new(0.0, 0.0).ln().re, f64::NEG_INFINITY);
+    assert_eq!(
+        Complex::new(f64::NAN, f64::NAN).powf(0.0),
+        Complex::new(1.0, 0.0)
+    );
+    assert_eq!(
+        Complex::new(-1.0, f64::INFINITY).sqrt(),
+        Complex::new(f64::INFINITY, f64::INFINITY)
+    );
+    assert_eq!(Complex::new(1000.0, 1.0).tanh(), Complex::new(1.0, 0.0));
+    assert_eq!(
+        Complex::new(0.0, f64::INFINITY).sin(),
+        Complex::new(0.0, f64::INFINITY)
+    );
+    assert_eq!(
+        Complex::new(f64::INFINITY, 0.0).sinh(),
+        Complex::new(f64::INFINITY, 0.0)
+    );
+    assert_eq!(
+        Complex::new(f64::INFINITY, 0.0).cosh(),
+        Complex::new(f64::INFINITY, 0.0)
+    );
+    assert_eq!(
+        Complex::new(0.0, f64::INFINITY).cos(),
+        Complex::new(f64::INFINITY, -0.0)
+    );
+    assert!(
+        Complex::new(f64::INFINITY, f64::INFINITY)
+            .sinh()
+            .is_infinite()
+    );
+
+    // asin respects the sign of zero on its branch cuts and stays finite for large arguments.
+    let asin_2 = Complex::new(2.0, 0.0).asin();
+    println!("asin(2 + 0i) = {:?}", asin_2);
+    assert!(approx_eq(
+        &asin_2,
+        &Complex::new(PI / 2.0, 1.3169578969248166),
+        EPS
+    ));
+    assert!(approx_eq(
+        &Complex::new(2.0, -0.0).asin(),
+        &Complex::new(PI / 2.0, -1.3169578969248166),
+        EPS
+    ));
+    let asin_big = Complex::new(1e200, 0.0).asin();
+    println!("asin(1e200) = {:?}", asin_big);
+    assert!(asin_big.is_finite());
+    assert!(approx_eq(
+        &asin_big,
+        &Complex::new(PI / 2.0, (2e200f64).ln()),
+        EPS
+    ));
+    let z_big = Complex::new(-3e300, 4e300);
+    assert!(approx_eq(&z_big.asin().sin(), &z_big, 1e288));
+    assert_eq!(Complex::new(0.5, 0.0).asin().im, 0.0);
+    assert!(Complex::new(1.0, 1.0).is_finite());
+    println!(
+        "exp(-∞ + NaN·i) = {:?}",
+        Complex::new(f64::NEG_INFINITY, f64::NAN).exp()
+    );
+}
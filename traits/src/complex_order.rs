@@ -0,0 +1,188 @@
+/*
+    Ordering Complex numbers.
+
+    Unlike real numbers, complex numbers cannot be ordered in a way that is compatible with their
+    arithmetic, so Complex does not implement Ord or PartialOrd. Code that needs an order (to sort,
+    to find a maximum, to deduplicate) has to say which one it means. This module offers three
+    comparators with the signature sort_by expects:
+    - by_magnitude: by distance from the origin, |z|
+    - by_argument: by angle from the positive real axis, arg z in [-π, π]
+    - lexicographic: by real part, then by imaginary part
+
+    f64 itself only implements PartialOrd because NaN is unordered, which is why `v.sort()` does not
+    compile for floats. The comparators use f64::total_cmp, the IEEE 754 totalOrder predicate, which
+    orders every float including NaN: -NaN < -∞ < ... < -0.0 < +0.0 < ... < +∞ < +NaN. The TotalF64
+    wrapper packages that order as Eq, Ord and Hash impls so floats can be used as map keys and in
+    sets.
+*/
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+use crate::operator_overloading::Complex;
+
+/// An f64 ordered by the IEEE 754 total order. Unlike plain f64, NaN equals itself (bit for bit)
+/// and -0.0 sorts before +0.0, so the wrapper can implement Eq, Ord and Hash consistently.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TotalF64(pub(crate) f64);
+
+impl PartialEq for TotalF64 {
+    fn eq(&self, other: &TotalF64) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TotalF64 {}
+
+impl PartialOrd for TotalF64 {
+    fn partial_cmp(&self, other: &TotalF64) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalF64 {
+    fn cmp(&self, other: &TotalF64) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Hash for TotalF64 {
+    /// Two values are equal under total_cmp exactly when their bits are equal, so hashing the bits
+    /// agrees with Eq.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl Complex<f64> {
+    /// Wraps both parts in TotalF64, giving a value that is Eq and Hash and can be stored in a
+    /// HashSet or used as a HashMap key.
+    pub(crate) fn to_total(&self) -> Complex<TotalF64> {
+        Complex::new(TotalF64(self.re), TotalF64(self.im))
+    }
+}
+
+/// Orders by magnitude |z|. Numbers on the same circle around the origin compare equal, so a
+/// stable sort keeps them in their original order; chain `.then_with` for a tie-breaker.
+pub(crate) fn by_magnitude(a: &Complex<f64>, b: &Complex<f64>) -> Ordering {
+    a.abs().total_cmp(&b.abs())
+}
+
+/// Orders by argument arg z, going counter-clockwise from the negative real axis just below the
+/// branch cut (-π) to just above it (π). Numbers on the same ray compare equal.
+pub(crate) fn by_argument(a: &Complex<f64>, b: &Complex<f64>) -> Ordering {
+    a.arg().total_cmp(&b.arg())
+}
+
+/// Orders by real part, then by imaginary part. This is the order the struct used to derive; it
+/// carries no mathematical meaning but is total, so it suits sorting before deduplication.
+pub(crate) fn lexicographic(a: &Complex<f64>, b: &Complex<f64>) -> Ordering {
+    a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im))
+}
+
+/// Demonstrates sorting and deduplicating Complex numbers with explicit orderings.
+pub fn complex_order_demo() {
+    println!("Ordering complex numbers:");
+
+    let numbers = vec![
+        Complex::new(3.0, 4.0),
+        Complex::new(-1.0, 0.0),
+        Complex::new(0.0, -2.0),
+        Complex::new(1.0, 1.0),
+        Complex::new(-5.0, 0.0),
+        Complex::new(0.0, 2.0),
+    ];
+
+    let mut sorted = numbers.clone();
+    sorted.sort_by(by_magnitude);
+    println!(
+        "By magnitude: {:?}",
+        sorted.iter().map(|z| z.to_string()).collect::<Vec<_>>()
+    );
+    let magnitudes: Vec<f64> = sorted.iter().map(|z| z.abs()).collect();
+    assert!(magnitudes.windows(2).all(|w| w[0] <= w[1]));
+    // 0-2i and 0+2i tie on magnitude, and the stable sort keeps their original order.
+    assert_eq!(sorted[2], Complex::new(0.0, -2.0));
+    assert_eq!(sorted[3], Complex::new(0.0, 2.0));
+
+    sorted.sort_by(by_argument);
+    println!(
+        "By argument: {:?}",
+        sorted.iter().map(|z| z.to_string()).collect::<Vec<_>>()
+    );
+    assert_eq!(sorted.first(), Some(&Complex::new(0.0, -2.0)));
+    // -1+0i and -5+0i lie on the same ray at angle π.
+    assert_eq!(
+        sorted[4..],
+        [Complex::new(-1.0, 0.0), Complex::new(-5.0, 0.0)]
+    );
+
+    sorted.sort_by(lexicographic);
+    println!(
+        "Lexicographic: {:?}",
+        sorted.iter().map(|z| z.to_string()).collect::<Vec<_>>()
+    );
+    assert_eq!(sorted.first(), Some(&Complex::new(-5.0, 0.0)));
+    assert_eq!(
+        sorted[2..4],
+        [Complex::new(0.0, -2.0), Complex::new(0.0, 2.0)]
+    );
+
+    // 3+4i and -5+0i both have magnitude 5; max_by returns the last of equal maxima.
+    let largest = numbers.iter().max_by(|a, b| by_magnitude(a, b));
+    assert_eq!(largest, Some(&Complex::new(-5.0, 0.0)));
+
+    // Tie-breaking: by magnitude first, then counter-clockwise by angle.
+    let mut ring = vec![
+        Complex::new(0.0, 1.0),
+        Complex::new(-1.0, 0.0),
+        Complex::new(1.0, 0.0),
+    ];
+    ring.sort_by(|a, b| by_magnitude(a, b).then_with(|| by_argument(a, b)));
+    assert_eq!(
+        ring,
+        [
+            Complex::new(1.0, 0.0),
+            Complex::new(0.0, 1.0),
+            Complex::new(-1.0, 0.0)
+        ]
+    );
+
+    // NaN never equals itself, so plain dedup keeps every NaN. Comparing through TotalF64 treats
+    // identical NaNs as duplicates, and sorting lexicographically brings duplicates together.
+    let nan = Complex::new(f64::NAN, 0.0);
+    let mut with_duplicates = vec![
+        Complex::new(1.0, 2.0),
+        nan.clone(),
+        Complex::new(0.0, 0.0),
+        Complex::new(1.0, 2.0),
+        nan.clone(),
+        Complex::new(0.0, 0.0),
+    ];
+    with_duplicates.sort_by(lexicographic);
+    with_duplicates.dedup_by_key(|z| z.to_total());
+    println!(
+        "Deduplicated: {:?}",
+        with_duplicates
+            .iter()
+            .map(|z| z.to_string())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(with_duplicates.len(), 3);
+    assert!(with_duplicates[2].re.is_nan());
+
+    // The wrapper also makes complex numbers hashable.
+    let unique: HashSet<Complex<TotalF64>> = numbers
+        .iter()
+        .chain(&numbers)
+        .chain([&nan, &nan])
+        .map(|z| z.to_total())
+        .collect();
+    assert_eq!(unique.len(), numbers.len() + 1);
+
+    // Signed zeros are distinct under the total order, even though 0.0 == -0.0 as plain floats.
+    assert!(TotalF64(-0.0) < TotalF64(0.0));
+    assert_eq!(TotalF64(f64::NAN), TotalF64(f64::NAN));
+    assert!(TotalF64(f64::INFINITY) < TotalF64(f64::NAN));
+}
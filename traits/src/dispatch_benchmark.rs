@@ -0,0 +1,512 @@
+/*
+    Benchmarking the three ways to call a trait method on a mixed collection.
+
+    static_and_dyn_dispatch, why_dyn_dispatch and vectors_of_diff_objects describe the trade-offs;
+    this module measures them on the same Shape, Animal and Printable types:
+
+    - enum: the values are wrapped in an enum and each call is a `match`. Every variant is known
+      at compile time, so the compiler can inline each arm.
+    - &dyn: the collection holds trait objects, and each call goes through the vtable. The call
+      target is only known at runtime, so it cannot be inlined.
+    - generic: each concrete type lives in its own Vec and is processed by a generic function,
+      monomorphised once per type. This is the fastest, but gives up the mixed collection. For
+      every family, the "generic, no inline" variant forces a real call per item, isolating how
+      much of the generic version's advantage comes from inlining.
+
+    The memory per item matters as well, and the "bytes" column reports it: an enum is as large
+    as its largest variant plus a tag, a &dyn is a fat pointer (data pointer plus vtable pointer)
+    plus the value it points to, and the homogeneous Vecs store each value at its own size, so
+    their average depends on the mix of types. Heap data, like a String's characters, is not
+    counted.
+
+    Binary size is the cost side of monomorphisation: every type a generic function is used with
+    gets its own copy of the machine code. Each strategy's loop over the shapes is kept in a
+    function of its own, and the harness looks those functions up in the running executable's
+    symbol table (with `nm`), reporting how many copies exist and their total size. The generic
+    loop appears once per shape type, the enum and &dyn loops once. A stripped executable, or a
+    system without nm, has nothing to report.
+
+    Run the full suite with `cargo run --release -- bench [max_exponent]`, which measures
+    collections of 10^3 up to 10^max_exponent items (default 7). Debug builds disable inlining
+    and are only useful for checking that the harness works.
+*/
+
+use std::borrow::Cow;
+use std::hint::black_box;
+use std::mem::size_of;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::rng::XorShift;
+use crate::shapes::{Circle, Point, Shape, Square};
+use crate::static_and_dyn_dispatch::Printable;
+use crate::traits::{Animal, Cat, Human};
+use crate::vectors_of_diff_objects::Creature;
+
+/// How many times each measurement is repeated; the fastest run is reported.
+const REPETITIONS: usize = 3;
+
+/// Which of two types each item of a mixed collection has, and a size parameter for it.
+fn mix(n: usize) -> Vec<(bool, u32)> {
+    // A fixed seed, so every strategy sees the same mix of types in the same unpredictable order
+    // (a regular pattern would let the branch predictor hide the dispatch cost).
+    let mut rng = XorShift::new(0x2545_f491_4f6c_dd1d);
+    (0..n)
+        .map(|_| (rng.range(0, 1) == 0, rng.range(1, 100)))
+        .collect()
+}
+
+/// The result of timing one strategy on one collection size.
+struct Measurement {
+    strategy: &'static str,
+    items: usize,
+    /// The average number of bytes stored per item, excluding heap data.
+    bytes_per_item: f64,
+    best: Duration,
+    result: f64,
+}
+
+impl Measurement {
+    fn ns_per_item(&self) -> f64 {
+        self.best.as_secs_f64() * 1e9 / self.items as f64
+    }
+
+    fn million_items_per_second(&self) -> f64 {
+        self.items as f64 / self.best.as_secs_f64() / 1e6
+    }
+}
+
+/// Runs `work` REPETITIONS times and keeps the fastest time. black_box stops the compiler from
+/// optimizing the work away or hoisting it out of the loop.
+fn measure<F: FnMut() -> f64>(
+    strategy: &'static str,
+    items: usize,
+    bytes_per_item: f64,
+    mut work: F,
+) -> Measurement {
+    let mut best = Duration::MAX;
+    let mut result = 0.0;
+    for _ in 0..REPETITIONS {
+        let start = Instant::now();
+        result = black_box(work());
+        best = best.min(start.elapsed());
+    }
+    Measurement {
+        strategy,
+        items,
+        bytes_per_item,
+        best,
+        result,
+    }
+}
+
+/// The average size of the items of two homogeneous collections holding `items` values in total.
+fn mean_size<A, B>(a: &[A], b: &[B], items: usize) -> f64 {
+    (size_of_val(a) + size_of_val(b)) as f64 / items as f64
+}
+
+/// The size of a collection of trait objects per item: the fat pointer plus what it points to.
+fn dyn_size<T: ?Sized, A, B>(a: &[A], b: &[B], items: usize) -> f64 {
+    size_of::<&T>() as f64 + mean_size(a, b, items)
+}
+
+/// Shapes wrapped in an enum for match-based dispatch.
+enum ShapeKind {
+    Circle(Circle),
+    Square(Square),
+}
+
+impl ShapeKind {
+    fn area(&self) -> f64 {
+        match self {
+            ShapeKind::Circle(c) => c.area(),
+            ShapeKind::Square(s) => s.area(),
+        }
+    }
+}
+
+// The three shape loops are never inlined, so each keeps a symbol whose size shape_code_sizes can
+// read. That costs one call per collection, not per item.
+
+/// Sums the areas of shapes wrapped in the enum.
+#[inline(never)]
+fn enum_total_area(shapes: &[ShapeKind]) -> f64 {
+    shapes.iter().map(ShapeKind::area).sum()
+}
+
+/// Sums the areas of shapes behind trait objects.
+#[inline(never)]
+fn dyn_total_area(shapes: &[&dyn Shape]) -> f64 {
+    shapes.iter().map(|s| s.area()).sum()
+}
+
+/// Sums the areas of one concrete shape type. Monomorphised once for Circle and once for Square.
+#[inline(never)]
+fn total_area<S: Shape>(shapes: &[S]) -> f64 {
+    shapes.iter().map(|s| s.area()).sum()
+}
+
+/// Calls area through a function the optimizer may not inline.
+#[inline(never)]
+fn area_not_inlined<S: Shape>(shape: &S) -> f64 {
+    shape.area()
+}
+
+fn total_area_not_inlined<S: Shape>(shapes: &[S]) -> f64 {
+    shapes.iter().map(area_not_inlined).sum()
+}
+
+fn bench_shapes(n: usize) -> Vec<Measurement> {
+    let mix = mix(n);
+    let enums: Vec<ShapeKind> = mix
+        .iter()
+        .map(|&(circle, size)| match circle {
+            true => ShapeKind::Circle(Circle::new(Point::ORIGIN, size as f64)),
+            false => ShapeKind::Square(Square::new(Point::ORIGIN, size as f64)),
+        })
+        .collect();
+    let circles: Vec<Circle> = mix
+        .iter()
+        .filter(|(circle, _)| *circle)
+        .map(|&(_, size)| Circle::new(Point::ORIGIN, size as f64))
+        .collect();
+    let squares: Vec<Square> = mix
+        .iter()
+        .filter(|(circle, _)| !*circle)
+        .map(|&(_, size)| Square::new(Point::ORIGIN, size as f64))
+        .collect();
+    // The trait objects point into the homogeneous Vecs, interleaved in the mixed order.
+    let (mut c, mut s) = (circles.iter(), squares.iter());
+    let objects: Vec<&dyn Shape> = mix
+        .iter()
+        .map(|&(circle, _)| -> &dyn Shape {
+            match circle {
+                true => c.next().unwrap(),
+                false => s.next().unwrap(),
+            }
+        })
+        .collect();
+
+    let generic_size = mean_size(&circles, &squares, n);
+    vec![
+        measure("enum", n, size_of::<ShapeKind>() as f64, || {
+            enum_total_area(&enums)
+        }),
+        measure(
+            "&dyn",
+            n,
+            dyn_size::<dyn Shape, _, _>(&circles, &squares, n),
+            || dyn_total_area(&objects),
+        ),
+        measure("generic", n, generic_size, || {
+            total_area(&circles) + total_area(&squares)
+        }),
+        measure("generic, no inline", n, generic_size, || {
+            total_area_not_inlined(&circles) + total_area_not_inlined(&squares)
+        }),
+    ]
+}
+
+/// The work done per animal: the length of its name and species.
+fn animal_work<A: Animal + ?Sized>(animal: &A) -> f64 {
+    (animal.name().len() + animal.species().len()) as f64
+}
+
+fn total_animal_work<A: Animal>(animals: &[A]) -> f64 {
+    animals.iter().map(animal_work).sum()
+}
+
+#[inline(never)]
+fn animal_work_not_inlined<A: Animal>(animal: &A) -> f64 {
+    animal_work(animal)
+}
+
+fn total_animal_work_not_inlined<A: Animal>(animals: &[A]) -> f64 {
+    animals.iter().map(animal_work_not_inlined).sum()
+}
+
+fn bench_animals(n: usize) -> Vec<Measurement> {
+    const NAMES: [&str; 4] = ["Kenry", "Kary", "Meeku", "Tama"];
+    let mix = mix(n);
+    let name = |size: u32| Cow::Borrowed(NAMES[size as usize % NAMES.len()]);
+    let enums: Vec<Creature> = mix
+        .iter()
+        .map(|&(human, size)| match human {
+            true => Creature::Human(Human::create(name(size))),
+            false => Creature::Cat(Cat::create(name(size))),
+        })
+        .collect();
+    let humans: Vec<Human> = mix
+        .iter()
+        .filter(|(human, _)| *human)
+        .map(|&(_, size)| Human::create(name(size)))
+        .collect();
+    let cats: Vec<Cat> = mix
+        .iter()
+        .filter(|(human, _)| !*human)
+        .map(|&(_, size)| Cat::create(name(size)))
+        .collect();
+    let (mut h, mut c) = (humans.iter(), cats.iter());
+    let objects: Vec<&dyn Animal> = mix
+        .iter()
+        .map(|&(human, _)| -> &dyn Animal {
+            match human {
+                true => h.next().unwrap(),
+                false => c.next().unwrap(),
+            }
+        })
+        .collect();
+
+    let generic_size = mean_size(&humans, &cats, n);
+    vec![
+        measure("enum", n, size_of::<Creature>() as f64, || {
+            enums
+                .iter()
+                .map(|creature| match creature {
+                    Creature::Human(h) => animal_work(h),
+                    Creature::Cat(c) => animal_work(c),
+                })
+                .sum()
+        }),
+        measure(
+            "&dyn",
+            n,
+            dyn_size::<dyn Animal, _, _>(&humans, &cats, n),
+            || objects.iter().map(|a| animal_work(*a)).sum(),
+        ),
+        measure("generic", n, generic_size, || {
+            total_animal_work(&humans) + total_animal_work(&cats)
+        }),
+        measure("generic, no inline", n, generic_size, || {
+            total_animal_work_not_inlined(&humans) + total_animal_work_not_inlined(&cats)
+        }),
+    ]
+}
+
+/// Printable values wrapped in an enum.
+enum PrintableKind {
+    Int(i32),
+    Text(String),
+}
+
+/// The work done per value: the length of its formatted text. Formatting allocates, so this
+/// benchmark shows how little the dispatch strategy matters next to expensive methods.
+fn printable_work<P: Printable + ?Sized>(value: &P) -> f64 {
+    value.format().len() as f64
+}
+
+fn total_printable_work<P: Printable>(values: &[P]) -> f64 {
+    values.iter().map(printable_work).sum()
+}
+
+#[inline(never)]
+fn printable_work_not_inlined<P: Printable>(value: &P) -> f64 {
+    printable_work(value)
+}
+
+fn total_printable_work_not_inlined<P: Printable>(values: &[P]) -> f64 {
+    values.iter().map(printable_work_not_inlined).sum()
+}
+
+fn bench_printables(n: usize) -> Vec<Measurement> {
+    let mix = mix(n);
+    let enums: Vec<PrintableKind> = mix
+        .iter()
+        .map(|&(int, size)| match int {
+            true => PrintableKind::Int(size as i32),
+            false => PrintableKind::Text(size.to_string()),
+        })
+        .collect();
+    let ints: Vec<i32> = mix
+        .iter()
+        .filter(|(int, _)| *int)
+        .map(|&(_, size)| size as i32)
+        .collect();
+    let texts: Vec<String> = mix
+        .iter()
+        .filter(|(int, _)| !*int)
+        .map(|&(_, size)| size.to_string())
+        .collect();
+    let (mut i, mut t) = (ints.iter(), texts.iter());
+    let objects: Vec<&dyn Printable> = mix
+        .iter()
+        .map(|&(int, _)| -> &dyn Printable {
+            match int {
+                true => i.next().unwrap(),
+                false => t.next().unwrap(),
+            }
+        })
+        .collect();
+
+    let generic_size = mean_size(&ints, &texts, n);
+    vec![
+        measure("enum", n, size_of::<PrintableKind>() as f64, || {
+            enums
+                .iter()
+                .map(|value| match value {
+                    PrintableKind::Int(i) => printable_work(i),
+                    PrintableKind::Text(t) => printable_work(t),
+                })
+                .sum()
+        }),
+        measure(
+            "&dyn",
+            n,
+            dyn_size::<dyn Printable, _, _>(&ints, &texts, n),
+            || objects.iter().map(|p| printable_work(*p)).sum(),
+        ),
+        measure("generic", n, generic_size, || {
+            total_printable_work(&ints) + total_printable_work(&texts)
+        }),
+        measure("generic, no inline", n, generic_size, || {
+            total_printable_work_not_inlined(&ints) + total_printable_work_not_inlined(&texts)
+        }),
+    ]
+}
+
+/// Prints one table row per measurement and checks that all strategies computed the same result
+/// (up to floating-point summation order).
+fn report(family: &str, measurements: &[Measurement]) {
+    let reference = measurements[0].result;
+    for m in measurements {
+        assert!(
+            (m.result - reference).abs() <= 1e-9 * reference.abs(),
+            "{} {} computed {} instead of {}",
+            family,
+            m.strategy,
+            m.result,
+            reference
+        );
+        println!(
+            "{:<10} {:>9} {:<19} {:>7.1} B {:>10.2} ns/item {:>10.1} Mitems/s",
+            family,
+            m.items,
+            m.strategy,
+            m.bytes_per_item,
+            m.ns_per_item(),
+            m.million_items_per_second()
+        );
+    }
+}
+
+/// The function holding each strategy's loop over the shapes, by its demangled symbol name.
+const SHAPE_LOOPS: [(&str, &str); 3] = [
+    ("enum", concat!(module_path!(), "::enum_total_area")),
+    ("&dyn", concat!(module_path!(), "::dyn_total_area")),
+    ("generic", concat!(module_path!(), "::total_area")),
+];
+
+/// The machine code of one strategy's shape loop in the executable.
+struct CodeSize {
+    strategy: &'static str,
+    /// How many copies of the function exist: one per type for a generic function.
+    copies: usize,
+    bytes: u64,
+}
+
+/// Reads the sizes of the shape loops from the running executable's symbol table with `nm`.
+fn shape_code_sizes() -> Result<Vec<CodeSize>, String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let output = Command::new("nm")
+        .args(["--demangle", "--print-size", "--defined-only"])
+        .arg(&exe)
+        .output()
+        .map_err(|e| format!("cannot run nm: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "nm failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let symbols = String::from_utf8_lossy(&output.stdout);
+    // Each line reads "<address> <size> <kind> <name>", with the numbers in hex.
+    let sizes_of = |name: &str| -> Vec<u64> {
+        symbols
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, ' ');
+                let size = fields.nth(1)?;
+                let symbol = fields.nth(1)?;
+                (symbol == name)
+                    .then(|| u64::from_str_radix(size, 16).ok())
+                    .flatten()
+            })
+            .collect()
+    };
+    Ok(SHAPE_LOOPS
+        .iter()
+        .map(|&(strategy, name)| {
+            let sizes = sizes_of(name);
+            CodeSize {
+                strategy,
+                copies: sizes.len(),
+                bytes: sizes.iter().sum(),
+            }
+        })
+        .collect())
+}
+
+/// Prints the size of each strategy's shape loop, and returns the sizes if they could be read.
+fn report_code_sizes() -> Option<Vec<CodeSize>> {
+    let sizes = match shape_code_sizes() {
+        Ok(sizes) if sizes.iter().any(|s| s.copies > 0) => sizes,
+        Ok(_) => {
+            println!("Machine code size: no symbols found; the executable may be stripped");
+            return None;
+        }
+        Err(e) => {
+            println!("Machine code size: {}", e);
+            return None;
+        }
+    };
+    println!("Machine code of the Shape loops:");
+    for size in &sizes {
+        println!(
+            "{:<10} {:>2} {:<8} {:>6} B",
+            size.strategy,
+            size.copies,
+            if size.copies == 1 { "copy" } else { "copies" },
+            size.bytes
+        );
+    }
+    Some(sizes)
+}
+
+/// Runs every benchmark for collections of 10^3 up to 10^max_exponent items.
+///
+/// # Arguments
+///
+/// * `max_exponent` - The largest collection has 10^max_exponent items.
+pub(crate) fn run(max_exponent: u32) {
+    if cfg!(debug_assertions) {
+        println!(
+            "Warning: this is a debug build; use `cargo run --release -- bench` for real numbers."
+        );
+    }
+    println!(
+        "{:<10} {:>9} {:<19} {:>9} {:>18} {:>19}",
+        "family", "items", "strategy", "bytes", "time", "throughput"
+    );
+    for exponent in 3..=max_exponent {
+        let n = 10usize.pow(exponent);
+        report("Shape", &bench_shapes(n));
+        report("Animal", &bench_animals(n));
+        report("Printable", &bench_printables(n));
+    }
+    report_code_sizes();
+}
+
+/// Runs the benchmarks on small collections, as a quick check that the harness works.
+pub fn dispatch_benchmark_demo() {
+    println!("Dispatch benchmark (small sizes; see `cargo run --release -- bench`):");
+    run(3);
+    // The generic loop is compiled once for Circle and once for Square.
+    if let Some(sizes) = shape_code_sizes()
+        .ok()
+        .filter(|s| s.iter().any(|s| s.copies > 0))
+    {
+        assert_eq!(sizes[0].copies, 1);
+        assert_eq!(sizes[1].copies, 1);
+        assert_eq!(sizes[2].copies, 2);
+    }
+}
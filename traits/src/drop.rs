@@ -1,9 +1,17 @@
+use std::any::Any;
+
 /// The Drop trait in Rust provides a way to customize the cleanup process when an object goes out of scope.
 /// It works similarly to a destructor in other languages, ensuring that resources are freed correctly.
 /// Note that while Rust automatically calls drop when a value is no longer needed, you can also force early cleanup
 /// using the standard library's drop function. However, you cannot call the drop method directly on an instance.
-struct Creature {
+///
+/// A creature can also own guards: values held only to be dropped along with it, so their own
+/// Drop impls run when the creature dies. Other modules use them to observe creatures without
+/// Creature knowing about them, such as lifecycle.rs's Tracked tokens and game.rs's departures.
+pub(crate) struct Creature {
     name: String,
+    /// Dropped in order, right after the creature's own Drop impl runs.
+    _guards: Vec<Box<dyn Any>>,
 }
 
 impl Creature {
@@ -16,9 +24,30 @@ impl Creature {
     /// # Returns
     ///
     /// A Creature instance with the given name.
-    fn new(name: &str) -> Creature {
+    pub(crate) fn new(name: &str) -> Creature {
+        Creature::with_guards(name, Vec::new())
+    }
+
+    /// Creates a new Creature that owns `guard` and drops it when the creature is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - A string slice that holds the name of the creature.
+    /// * `guard` - The value to drop along with the creature.
+    pub(crate) fn with_guard(name: &str, guard: impl Any) -> Creature {
+        Creature::with_guards(name, vec![Box::new(guard)])
+    }
+
+    fn with_guards(name: &str, guards: Vec<Box<dyn Any>>) -> Creature {
         println!("{} enters the game", name);
-        Creature { name: name.into() }
+        Creature {
+            name: name.into(),
+            _guards: guards,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
     }
 }
 
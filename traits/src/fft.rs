@@ -0,0 +1,203 @@
+/*
+    The Fast Fourier Transform.
+
+    The discrete Fourier transform (DFT) turns n samples x into n frequency components:
+
+        X[k] = Σ x[j] · e^(-2πi·jk/n)    for k in 0..n
+
+    Computed directly that takes O(n²) operations. The radix-2 Cooley-Tukey FFT splits the sum
+    into its even and odd samples, which are two DFTs of half the size, and recurses down to
+    single samples, for O(n log n) in total. The split needs n to be a power of two.
+
+    This implementation is iterative: it first permutes the samples into bit-reversed index order
+    (the order the recursion would visit them in) and then combines pairs of sub-transforms in
+    place, doubling their size each pass. The twiddle factors e^(-2πi·k/size) are computed directly
+    from the angle rather than by repeated multiplication, which would accumulate rounding error.
+*/
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::num::Zero;
+use crate::operator_overloading::Complex;
+
+/// The errors the FFT can return.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FftError {
+    /// The radix-2 FFT only works on inputs whose length is a power of two.
+    NotPowerOfTwo(usize),
+}
+
+impl fmt::Display for FftError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FftError::NotPowerOfTwo(len) => write!(
+                f,
+                "the FFT needs a power-of-two number of samples, got {}",
+                len
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FftError {}
+
+/// Transforms `data` in place. `sign` is -1 for the forward transform and 1 for the inverse; the
+/// inverse's 1/n scaling is left to the caller.
+fn transform(data: &mut [Complex<f64>], sign: f64) -> Result<(), FftError> {
+    let n = data.len();
+    if !n.is_power_of_two() {
+        return Err(FftError::NotPowerOfTwo(n));
+    }
+
+    // Move each sample to the position given by reversing the bits of its index.
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i
+            .reverse_bits()
+            .checked_shr(usize::BITS - bits)
+            .unwrap_or(0);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    // Combine pairs of transforms of length half into transforms of length size.
+    let mut size = 2;
+    while size <= n {
+        let half = size / 2;
+        let twiddles: Vec<Complex<f64>> = (0..half)
+            .map(|k| Complex::from_polar(1.0, sign * 2.0 * PI * k as f64 / size as f64))
+            .collect();
+        for block in data.chunks_exact_mut(size) {
+            let (even, odd) = block.split_at_mut(half);
+            for k in 0..half {
+                let t = &twiddles[k] * &odd[k];
+                odd[k] = &even[k] - &t;
+                even[k] += t;
+            }
+        }
+        size *= 2;
+    }
+    Ok(())
+}
+
+/// Computes the discrete Fourier transform of `input` with the radix-2 FFT.
+///
+/// # Arguments
+///
+/// * `input` - The samples. Their number must be a power of two.
+pub(crate) fn fft(input: &[Complex<f64>]) -> Result<Vec<Complex<f64>>, FftError> {
+    let mut output = input.to_vec();
+    transform(&mut output, -1.0)?;
+    Ok(output)
+}
+
+/// Computes the inverse discrete Fourier transform, so that ifft(fft(x)) returns x up to
+/// rounding.
+///
+/// # Arguments
+///
+/// * `input` - The frequency components. Their number must be a power of two.
+pub(crate) fn ifft(input: &[Complex<f64>]) -> Result<Vec<Complex<f64>>, FftError> {
+    let mut output = input.to_vec();
+    transform(&mut output, 1.0)?;
+    let n = output.len() as f64;
+    for z in &mut output {
+        *z /= n;
+    }
+    Ok(output)
+}
+
+/// Computes the discrete Fourier transform straight from its definition in O(n²). Works for any
+/// length; used as the reference the FFT is checked against.
+pub(crate) fn dft(input: &[Complex<f64>]) -> Vec<Complex<f64>> {
+    let n = input.len();
+    (0..n)
+        .map(|k| {
+            input
+                .iter()
+                .enumerate()
+                .map(|(j, x)| {
+                    // Reduce jk modulo n first so the angle stays small and accurate.
+                    let angle = -2.0 * PI * ((j * k) % n) as f64 / n as f64;
+                    x * &Complex::from_polar(1.0, angle)
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// The largest distance between corresponding elements of two equally long slices.
+fn max_error(a: &[Complex<f64>], b: &[Complex<f64>]) -> f64 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y).abs())
+        .fold(0.0, f64::max)
+}
+
+/// Demonstrates the FFT and checks it against the naive DFT and known transforms.
+pub fn fft_demo() {
+    println!("Fast Fourier Transform:");
+
+    // An impulse contains every frequency equally.
+    let mut impulse = vec![Complex::zero(); 8];
+    impulse[0] = Complex::new(1.0, 0.0);
+    let spectrum = fft(&impulse).unwrap();
+    assert!(spectrum.iter().all(|z| *z == Complex::new(1.0, 0.0)));
+
+    // A pure tone of frequency 3 concentrates in bin 3.
+    let n = 16;
+    let tone: Vec<Complex<f64>> = (0..n)
+        .map(|j| Complex::from_polar(1.0, 2.0 * PI * 3.0 * j as f64 / n as f64))
+        .collect();
+    let spectrum = fft(&tone).unwrap();
+    println!(
+        "Magnitudes of a 16-sample tone at frequency 3: {:?}",
+        spectrum
+            .iter()
+            .map(|z| z.abs().round() as i32)
+            .collect::<Vec<_>>()
+    );
+    for (k, z) in spectrum.iter().enumerate() {
+        let expected = if k == 3 { n as f64 } else { 0.0 };
+        assert!((z.abs() - expected).abs() < 1e-12);
+    }
+
+    // Compare against the naive DFT over a range of sizes with an irregular signal, and check
+    // that the inverse restores the input and that energy is preserved (Parseval's theorem).
+    for log_n in 0..=10 {
+        let n = 1 << log_n;
+        let signal: Vec<Complex<f64>> = (0..n)
+            .map(|j| {
+                let t = j as f64;
+                Complex::new(
+                    (0.3 * t).sin() + 0.5 * (1.7 * t).cos(),
+                    (0.11 * t * t).sin(),
+                )
+            })
+            .collect();
+        let fast = fft(&signal).unwrap();
+        let naive = dft(&signal);
+        let scale = n as f64;
+        assert!(max_error(&fast, &naive) < 1e-12 * scale, "n = {}", n);
+        assert!(max_error(&ifft(&fast).unwrap(), &signal) < 1e-12);
+
+        let time_energy: f64 = signal.iter().map(|z| z.norm_sqr()).sum();
+        let frequency_energy: f64 = fast.iter().map(|z| z.norm_sqr()).sum::<f64>() / scale;
+        assert!((time_energy - frequency_energy).abs() < 1e-9 * time_energy.max(1.0));
+        if n == 1024 {
+            println!(
+                "n = {}: max |fft - dft| = {:.2e}, max |ifft(fft(x)) - x| = {:.2e}",
+                n,
+                max_error(&fast, &naive),
+                max_error(&ifft(&fast).unwrap(), &signal)
+            );
+        }
+    }
+
+    let err = fft(&vec![Complex::zero(); 6]).unwrap_err();
+    println!("fft of 6 samples: {}", err);
+    assert_eq!(err, FftError::NotPowerOfTwo(6));
+    assert_eq!(ifft(&[]), Err(FftError::NotPowerOfTwo(0)));
+}
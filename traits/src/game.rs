@@ -0,0 +1,822 @@
+/*
+    A small turn-based combat game built around drop.rs's Creature.
+
+    The World is organized as an entity/component system. An entity is just an id; what it is
+    made of lives in separate component maps keyed by that id: its Creature (the name, and the
+    Drop behaviour), its side, its Health, its Attack and its active status effects. The game
+    logic works on the components it needs, and despawning an entity removes it from every map.
+
+    Each round, every living entity takes a turn in id order:
+
+    1. Its status effects tick: poison hurts, regeneration heals, a stun costs the turn, and the
+       guard from defending wears off. Each effect lasts a number of the entity's turns.
+    2. Unless stunned, a Controller chooses its action: attack an enemy, or defend, halving the
+       damage taken until its next turn. Attacks can miss, roll their damage from a range, and
+       may inflict a status effect.
+    3. An entity whose health reaches zero dies and is despawned.
+
+    All randomness comes from one seeded XorShift owned by the World, so a game is fully
+    determined by its seed and the choices made: replaying them replays the game exactly.
+
+    Every creature in the world owns a Departure token as a guard, the same pattern as
+    lifecycle.rs's Tracked. When a creature leaves the world, whether it dies or the world itself
+    is dropped at the end of the game, its Drop runs and the token records a Left event in the
+    game's log.
+
+    `cargo run -- play [seed]` plays a game in the terminal, controlling the heroes.
+*/
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::BufRead;
+use std::mem::discriminant;
+use std::rc::{Rc, Weak};
+
+use crate::drop::Creature;
+use crate::rng::XorShift;
+
+/// The games played from the terminal end in a draw after this many rounds.
+const MAX_ROUNDS: u32 = 50;
+
+/// The duration of a status effect that never wears off.
+pub(crate) const PERMANENT: u32 = u32::MAX;
+
+pub(crate) type EntityId = u32;
+
+/// Which team an entity fights for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Side {
+    Heroes,
+    Monsters,
+}
+
+impl Side {
+    fn opponent(self) -> Side {
+        match self {
+            Side::Heroes => Side::Monsters,
+            Side::Monsters => Side::Heroes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Health {
+    pub(crate) current: u32,
+    pub(crate) max: u32,
+}
+
+/// How an entity attacks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Attack {
+    /// The damage of a hit is rolled uniformly from min..=max.
+    pub(crate) min: u32,
+    pub(crate) max: u32,
+    /// The probability that an attack hits.
+    pub(crate) hit_chance: f64,
+    /// An effect a hit may inflict, with its duration in turns and its probability.
+    pub(crate) inflicts: Option<(Effect, u32, f64)>,
+}
+
+/// What a status effect does at the start of each of its holder's turns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Effect {
+    /// Deals this much damage.
+    Poison(u32),
+    /// Heals this much, up to the maximum health.
+    Regeneration(u32),
+    /// Skips the turn.
+    Stun,
+    /// Halves incoming damage; wears off at the start of the holder's next turn.
+    Guard,
+}
+
+impl fmt::Display for Effect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Effect::Poison(damage) => write!(f, "poison ({}/turn)", damage),
+            Effect::Regeneration(amount) => write!(f, "regeneration ({}/turn)", amount),
+            Effect::Stun => write!(f, "stun"),
+            Effect::Guard => write!(f, "guard"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct StatusEffect {
+    pub(crate) effect: Effect,
+    /// How many more of the holder's turns the effect applies to, or PERMANENT.
+    pub(crate) turns: u32,
+}
+
+/// Formats a duration for messages.
+fn duration(turns: u32) -> String {
+    match turns {
+        PERMANENT => "permanently".to_string(),
+        1 => "for 1 turn".to_string(),
+        _ => format!("for {} turns", turns),
+    }
+}
+
+/// What an entity does with its turn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Action {
+    Attack(EntityId),
+    Defend,
+}
+
+/// Something that happened in the game. Events hold names rather than ids, since the entity may
+/// be gone by the time the event is read.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum GameEvent {
+    Entered {
+        name: String,
+    },
+    Hit {
+        attacker: String,
+        target: String,
+        damage: u32,
+    },
+    Missed {
+        attacker: String,
+        target: String,
+    },
+    Afflicted {
+        target: String,
+        effect: Effect,
+        turns: u32,
+    },
+    Poisoned {
+        target: String,
+        damage: u32,
+    },
+    Healed {
+        target: String,
+        amount: u32,
+    },
+    Stunned {
+        name: String,
+    },
+    Defended {
+        name: String,
+    },
+    Died {
+        name: String,
+    },
+    /// Recorded by the creature's Drop, when it leaves the world for any reason.
+    Left {
+        name: String,
+    },
+}
+
+impl fmt::Display for GameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameEvent::Entered { name } => write!(f, "{} joins the fight", name),
+            GameEvent::Hit {
+                attacker,
+                target,
+                damage,
+            } => write!(f, "{} hits {} for {} damage", attacker, target, damage),
+            GameEvent::Missed { attacker, target } => write!(f, "{} misses {}", attacker, target),
+            GameEvent::Afflicted {
+                target,
+                effect,
+                turns,
+            } => write!(f, "{} gains {} {}", target, effect, duration(*turns)),
+            GameEvent::Poisoned { target, damage } => {
+                write!(f, "{} takes {} poison damage", target, damage)
+            }
+            GameEvent::Healed { target, amount } => write!(f, "{} regenerates {}", target, amount),
+            GameEvent::Stunned { name } => write!(f, "{} is stunned", name),
+            GameEvent::Defended { name } => write!(f, "{} raises their guard", name),
+            GameEvent::Died { name } => write!(f, "{} falls", name),
+            GameEvent::Left { name } => write!(f, "{} leaves the world", name),
+        }
+    }
+}
+
+/// A shared handle to a game's events. It can outlive the World, to see the events recorded
+/// while the world is dropped.
+#[derive(Clone, Default)]
+pub(crate) struct EventLog(Rc<RefCell<Vec<GameEvent>>>);
+
+impl EventLog {
+    fn push(&self, event: GameEvent) {
+        self.0.borrow_mut().push(event);
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Returns the events from `start` on.
+    pub(crate) fn since(&self, start: usize) -> Vec<GameEvent> {
+        self.0.borrow()[start..].to_vec()
+    }
+
+    pub(crate) fn events(&self) -> Vec<GameEvent> {
+        self.since(0)
+    }
+}
+
+/// Owned by a creature in the world; dropping it records that the creature left.
+struct Departure {
+    log: Weak<RefCell<Vec<GameEvent>>>,
+    name: String,
+}
+
+impl Drop for Departure {
+    fn drop(&mut self) {
+        if let Some(log) = self.log.upgrade() {
+            log.borrow_mut().push(GameEvent::Left {
+                name: self.name.clone(),
+            });
+        }
+    }
+}
+
+/// Chooses the actions of the entities it controls.
+pub(crate) trait Controller {
+    /// Returns the action `actor` takes this turn. Attacks must target a living enemy.
+    fn choose(&mut self, world: &World, actor: EntityId) -> Action;
+}
+
+/// A simple computer player: always attacks the enemy with the least health left.
+pub(crate) struct Ai;
+
+impl Controller for Ai {
+    fn choose(&mut self, world: &World, actor: EntityId) -> Action {
+        let enemies = world.living(world.side(actor).opponent());
+        let weakest = enemies
+            .into_iter()
+            .min_by_key(|&id| world.health(id).current)
+            .expect("a turn is only played while both sides have living entities");
+        Action::Attack(weakest)
+    }
+}
+
+/// Lets a person control the heroes by typing commands, and the Ai control the monsters. Events
+/// are printed as they happen.
+pub(crate) struct Terminal<R: BufRead> {
+    input: R,
+    log: EventLog,
+    shown: usize,
+    /// Set once the input has ended; the Ai then plays the rest of the game.
+    autopilot: bool,
+}
+
+impl<R: BufRead> Terminal<R> {
+    pub(crate) fn new(input: R, log: EventLog) -> Terminal<R> {
+        Terminal {
+            input,
+            log,
+            shown: 0,
+            autopilot: false,
+        }
+    }
+
+    /// Prints the events recorded since the last call.
+    pub(crate) fn show_events(&mut self) {
+        for event in self.log.since(self.shown) {
+            println!("  {}", event);
+        }
+        self.shown = self.log.len();
+    }
+}
+
+impl<R: BufRead> Controller for Terminal<R> {
+    fn choose(&mut self, world: &World, actor: EntityId) -> Action {
+        self.show_events();
+        if world.side(actor) == Side::Monsters || self.autopilot {
+            return Ai.choose(world, actor);
+        }
+        print!("{}", world.status());
+        let enemies = world.living(Side::Monsters);
+        loop {
+            println!(
+                "{}'s turn: type a monster's number to attack it, or d to defend.",
+                world.name(actor)
+            );
+            let mut line = String::new();
+            if self.input.read_line(&mut line).unwrap_or(0) == 0 {
+                println!("No more input; the heroes fight on by themselves.");
+                self.autopilot = true;
+                return Ai.choose(world, actor);
+            }
+            match line.trim() {
+                "d" => return Action::Defend,
+                text => match text.parse() {
+                    Ok(target) if enemies.contains(&target) => return Action::Attack(target),
+                    _ => println!("'{}' is not a living monster's number.", text),
+                },
+            }
+        }
+    }
+}
+
+/// The game state: the entities and their components, the random generator and the event log.
+pub(crate) struct World {
+    // The creatures are declared first so they are dropped first, while the log still exists
+    // to receive their Left events.
+    creatures: BTreeMap<EntityId, Creature>,
+    sides: BTreeMap<EntityId, Side>,
+    health: BTreeMap<EntityId, Health>,
+    attacks: BTreeMap<EntityId, Attack>,
+    effects: BTreeMap<EntityId, Vec<StatusEffect>>,
+    next_id: EntityId,
+    round: u32,
+    rng: XorShift,
+    log: EventLog,
+}
+
+impl World {
+    /// Creates an empty world whose randomness is determined by `seed`.
+    pub(crate) fn new(seed: u64) -> World {
+        World {
+            creatures: BTreeMap::new(),
+            sides: BTreeMap::new(),
+            health: BTreeMap::new(),
+            attacks: BTreeMap::new(),
+            effects: BTreeMap::new(),
+            next_id: 1,
+            round: 0,
+            rng: XorShift::new(seed),
+            log: EventLog::default(),
+        }
+    }
+
+    /// Adds a creature with full health and returns its id.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The creature's name, used in messages.
+    /// * `side` - The team it fights for.
+    /// * `max_health` - Its starting and maximum health.
+    /// * `attack` - How it attacks.
+    pub(crate) fn spawn(
+        &mut self,
+        name: &str,
+        side: Side,
+        max_health: u32,
+        attack: Attack,
+    ) -> EntityId {
+        let id = self.next_id;
+        self.next_id += 1;
+        let departure = Departure {
+            log: Rc::downgrade(&self.log.0),
+            name: name.to_string(),
+        };
+        self.creatures
+            .insert(id, Creature::with_guard(name, departure));
+        self.sides.insert(id, side);
+        self.health.insert(
+            id,
+            Health {
+                current: max_health,
+                max: max_health,
+            },
+        );
+        self.attacks.insert(id, attack);
+        self.effects.insert(id, Vec::new());
+        self.log.push(GameEvent::Entered {
+            name: name.to_string(),
+        });
+        id
+    }
+
+    /// Removes an entity and all its components. Dropping its Creature records its departure.
+    fn despawn(&mut self, id: EntityId) {
+        self.sides.remove(&id);
+        self.health.remove(&id);
+        self.attacks.remove(&id);
+        self.effects.remove(&id);
+        self.creatures.remove(&id);
+    }
+
+    /// Returns a handle to the event log.
+    pub(crate) fn log(&self) -> EventLog {
+        self.log.clone()
+    }
+
+    /// Returns how many rounds have been played.
+    pub(crate) fn round(&self) -> u32 {
+        self.round
+    }
+
+    /// Returns whether the entity is still in the world.
+    pub(crate) fn is_alive(&self, id: EntityId) -> bool {
+        self.creatures.contains_key(&id)
+    }
+
+    pub(crate) fn name(&self, id: EntityId) -> &str {
+        self.creatures[&id].name()
+    }
+
+    pub(crate) fn side(&self, id: EntityId) -> Side {
+        self.sides[&id]
+    }
+
+    pub(crate) fn health(&self, id: EntityId) -> Health {
+        self.health[&id]
+    }
+
+    pub(crate) fn effects(&self, id: EntityId) -> &[StatusEffect] {
+        &self.effects[&id]
+    }
+
+    /// Returns the living entities of a side, in id order.
+    pub(crate) fn living(&self, side: Side) -> Vec<EntityId> {
+        self.sides
+            .iter()
+            .filter(|&(_, &s)| s == side)
+            .map(|(&id, _)| id)
+            .collect()
+    }
+
+    /// Returns the side that won, once the other has no living entities.
+    pub(crate) fn winner(&self) -> Option<Side> {
+        match (
+            self.living(Side::Heroes).is_empty(),
+            self.living(Side::Monsters).is_empty(),
+        ) {
+            (false, true) => Some(Side::Heroes),
+            (true, false) => Some(Side::Monsters),
+            _ => None,
+        }
+    }
+
+    /// Describes every living entity, one per line.
+    pub(crate) fn status(&self) -> String {
+        self.creatures
+            .keys()
+            .map(|&id| {
+                let health = self.health(id);
+                let effects: Vec<String> = self
+                    .effects(id)
+                    .iter()
+                    .map(|s| format!("{} {}", s.effect, duration(s.turns)))
+                    .collect();
+                format!(
+                    "  {}. {:<8} {:?}, {}/{} HP{}{}\n",
+                    id,
+                    self.name(id),
+                    self.side(id),
+                    health.current,
+                    health.max,
+                    if effects.is_empty() { "" } else { ", " },
+                    effects.join(", ")
+                )
+            })
+            .collect()
+    }
+
+    /// Gives an entity a status effect. An effect of the same kind is replaced, keeping the
+    /// longer duration.
+    pub(crate) fn afflict(&mut self, target: EntityId, effect: Effect, turns: u32) {
+        let effects = self.effects.get_mut(&target).unwrap();
+        match effects
+            .iter_mut()
+            .find(|s| discriminant(&s.effect) == discriminant(&effect))
+        {
+            Some(existing) => {
+                existing.effect = effect;
+                existing.turns = existing.turns.max(turns);
+            }
+            None => effects.push(StatusEffect { effect, turns }),
+        }
+        if effect != Effect::Guard {
+            self.log.push(GameEvent::Afflicted {
+                target: self.name(target).to_string(),
+                effect,
+                turns,
+            });
+        }
+    }
+
+    /// Takes health away, and despawns the entity if none is left. Returns whether it died.
+    fn hurt(&mut self, id: EntityId, damage: u32) -> bool {
+        let health = self.health.get_mut(&id).unwrap();
+        health.current = health.current.saturating_sub(damage);
+        if health.current > 0 {
+            return false;
+        }
+        self.log.push(GameEvent::Died {
+            name: self.name(id).to_string(),
+        });
+        self.despawn(id);
+        true
+    }
+
+    /// Applies the entity's status effects at the start of its turn. Returns whether it can
+    /// still act: it may have been stunned, or died of poison.
+    fn tick_effects(&mut self, id: EntityId) -> bool {
+        let name = self.name(id).to_string();
+        let effects = std::mem::take(self.effects.get_mut(&id).unwrap());
+        let mut stunned = false;
+        for status in &effects {
+            match status.effect {
+                Effect::Poison(damage) => {
+                    self.log.push(GameEvent::Poisoned {
+                        target: name.clone(),
+                        damage,
+                    });
+                    if self.hurt(id, damage) {
+                        return false;
+                    }
+                }
+                Effect::Regeneration(amount) => {
+                    let health = self.health.get_mut(&id).unwrap();
+                    let amount = amount.min(health.max - health.current);
+                    health.current += amount;
+                    if amount > 0 {
+                        self.log.push(GameEvent::Healed {
+                            target: name.clone(),
+                            amount,
+                        });
+                    }
+                }
+                Effect::Stun => stunned = true,
+                Effect::Guard => {}
+            }
+        }
+        self.effects.insert(
+            id,
+            effects
+                .into_iter()
+                .filter(|s| s.turns > 1)
+                .map(|s| StatusEffect {
+                    turns: if s.turns == PERMANENT {
+                        PERMANENT
+                    } else {
+                        s.turns - 1
+                    },
+                    ..s
+                })
+                .collect(),
+        );
+        if stunned {
+            self.log.push(GameEvent::Stunned { name });
+        }
+        !stunned
+    }
+
+    fn attack(&mut self, attacker: EntityId, target: EntityId) {
+        let attack = self.attacks[&attacker];
+        let (attacker_name, target_name) = (
+            self.name(attacker).to_string(),
+            self.name(target).to_string(),
+        );
+        if !self.rng.chance(attack.hit_chance) {
+            self.log.push(GameEvent::Missed {
+                attacker: attacker_name,
+                target: target_name,
+            });
+            return;
+        }
+        let mut damage = self.rng.range(attack.min, attack.max);
+        if self
+            .effects(target)
+            .iter()
+            .any(|s| s.effect == Effect::Guard)
+        {
+            damage /= 2;
+        }
+        self.log.push(GameEvent::Hit {
+            attacker: attacker_name,
+            target: target_name,
+            damage,
+        });
+        if !self.hurt(target, damage)
+            && let Some((effect, turns, probability)) = attack.inflicts
+            && self.rng.chance(probability)
+        {
+            self.afflict(target, effect, turns);
+        }
+    }
+
+    /// Plays one entity's turn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the controller attacks something other than a living enemy.
+    fn play_turn(&mut self, actor: EntityId, controller: &mut dyn Controller) {
+        if !self.tick_effects(actor) {
+            return;
+        }
+        match controller.choose(self, actor) {
+            Action::Defend => {
+                self.afflict(actor, Effect::Guard, 1);
+                self.log.push(GameEvent::Defended {
+                    name: self.name(actor).to_string(),
+                });
+            }
+            Action::Attack(target) => {
+                assert!(
+                    self.is_alive(target) && self.side(target) != self.side(actor),
+                    "{} cannot attack entity {}",
+                    self.name(actor),
+                    target
+                );
+                self.attack(actor, target);
+            }
+        }
+    }
+
+    /// Lets every living entity take a turn, in id order, until one side has won.
+    pub(crate) fn play_round(&mut self, controller: &mut dyn Controller) {
+        self.round += 1;
+        let order: Vec<EntityId> = self.creatures.keys().copied().collect();
+        for id in order {
+            if self.winner().is_some() {
+                break;
+            }
+            if self.is_alive(id) {
+                self.play_turn(id, controller);
+            }
+        }
+    }
+
+    /// Plays rounds until one side wins or `max_rounds` have been played, and returns the
+    /// winner.
+    pub(crate) fn run(&mut self, controller: &mut dyn Controller, max_rounds: u32) -> Option<Side> {
+        while self.winner().is_none() && self.round < max_rounds {
+            self.play_round(controller);
+        }
+        self.winner()
+    }
+}
+
+/// Creates the standard battle: a knight and a ranger against a goblin, an orc and a troll.
+pub(crate) fn standard_world(seed: u64) -> World {
+    let mut world = World::new(seed);
+    let attack = |min, max, hit_chance, inflicts| Attack {
+        min,
+        max,
+        hit_chance,
+        inflicts,
+    };
+    world.spawn("Knight", Side::Heroes, 40, attack(6, 10, 0.85, None));
+    let poison = Some((Effect::Poison(3), 3, 0.4));
+    world.spawn("Ranger", Side::Heroes, 28, attack(4, 12, 0.9, poison));
+    world.spawn("Goblin", Side::Monsters, 18, attack(3, 6, 0.8, None));
+    let stun = Some((Effect::Stun, 1, 0.25));
+    world.spawn("Orc", Side::Monsters, 30, attack(5, 9, 0.75, stun));
+    let troll = world.spawn("Troll", Side::Monsters, 36, attack(6, 11, 0.6, None));
+    world.afflict(troll, Effect::Regeneration(2), PERMANENT);
+    world
+}
+
+/// Plays the standard battle in the terminal, reading the heroes' commands from stdin.
+pub(crate) fn play(seed: u64) {
+    println!("A new battle begins (seed {}).", seed);
+    let mut world = standard_world(seed);
+    let stdin = std::io::stdin();
+    let mut terminal = Terminal::new(stdin.lock(), world.log());
+    while world.winner().is_none() && world.round() < MAX_ROUNDS {
+        println!("Round {}", world.round() + 1);
+        world.play_round(&mut terminal);
+    }
+    terminal.show_events();
+    match world.winner() {
+        Some(Side::Heroes) => println!("The heroes are victorious!"),
+        Some(Side::Monsters) => println!("The monsters win. Try another seed."),
+        None => println!("After {} rounds, both sides retreat.", MAX_ROUNDS),
+    }
+    drop(world);
+    terminal.show_events();
+}
+
+/// A plain attack that always hits for exactly `damage`.
+fn fixed(damage: u32) -> Attack {
+    Attack {
+        min: damage,
+        max: damage,
+        hit_chance: 1.0,
+        inflicts: None,
+    }
+}
+
+/// Demonstrates the game: deterministic replays, each status effect, Left events from Drop, and
+/// a scripted terminal session.
+pub fn game_demo() {
+    println!("Game:");
+
+    // The same seed replays the same game.
+    let replay = |seed| {
+        let mut world = standard_world(seed);
+        let winner = world.run(&mut Ai, MAX_ROUNDS);
+        (winner, world.round(), world.log().events())
+    };
+    let (winner, rounds, events) = replay(7);
+    println!("Seed 7: {:?} win after {} rounds", winner, rounds);
+    assert!(winner.is_some());
+    assert_eq!(replay(7), (winner, rounds, events.clone()));
+    assert_ne!(replay(8).2, events);
+
+    // Poison deals damage at the start of each of the holder's turns, then wears off.
+    let mut world = World::new(1);
+    let hero = world.spawn("Hero", Side::Heroes, 20, fixed(1));
+    let slime = world.spawn("Slime", Side::Monsters, 50, fixed(1));
+    world.afflict(hero, Effect::Poison(4), 2);
+    world.play_round(&mut Ai);
+    assert_eq!(world.health(hero).current, 20 - 4 - 1);
+    assert_eq!(world.effects(hero)[0].turns, 1);
+    world.play_round(&mut Ai);
+    world.play_round(&mut Ai);
+    assert_eq!(world.health(hero).current, 20 - 4 - 4 - 3);
+    assert!(world.effects(hero).is_empty());
+
+    // A stunned entity loses its turn; a defending one takes half damage until its next turn.
+    world.afflict(slime, Effect::Stun, 1);
+    world.play_round(&mut Ai);
+    assert_eq!(world.health(hero).current, 9);
+    assert_eq!(world.health(slime).current, 50 - 4);
+    let mut world = World::new(1);
+    let guard = world.spawn("Guard", Side::Heroes, 20, fixed(1));
+    let brute = world.spawn("Brute", Side::Monsters, 20, fixed(9));
+    struct AlwaysDefend;
+    impl Controller for AlwaysDefend {
+        fn choose(&mut self, _: &World, _: EntityId) -> Action {
+            Action::Defend
+        }
+    }
+    world.play_turn(guard, &mut AlwaysDefend);
+    world.play_turn(brute, &mut Ai);
+    world.play_round(&mut Ai);
+    // The guard wore off at the start of Guard's own turn, so only the first blow was halved.
+    assert_eq!(world.health(guard).current, 20 - 4 - 9);
+
+    // Regeneration never heals above the maximum.
+    let mut world = World::new(1);
+    let troll = world.spawn("Troll", Side::Monsters, 30, fixed(1));
+    world.spawn("Squire", Side::Heroes, 10, fixed(2));
+    world.afflict(troll, Effect::Regeneration(5), 10);
+    world.play_round(&mut Ai);
+    assert_eq!(world.health(troll).current, 28);
+    world.play_round(&mut Ai);
+    assert_eq!(world.health(troll).current, 28);
+
+    // A creature that dies leaves the world through its Drop, right after falling; the rest
+    // leave when the world is dropped. The log outlives the world to receive those events.
+    let log = world.log();
+    let mut world_2 = World::new(1);
+    world_2.spawn("Ant", Side::Monsters, 1, fixed(1));
+    let knight = world_2.spawn("Knight", Side::Heroes, 10, fixed(5));
+    let log_2 = world_2.log();
+    assert_eq!(world_2.run(&mut Ai, 10), Some(Side::Heroes));
+    let tail = log_2.events();
+    assert_eq!(
+        &tail[tail.len() - 3..],
+        [
+            GameEvent::Hit {
+                attacker: "Knight".to_string(),
+                target: "Ant".to_string(),
+                damage: 5
+            },
+            GameEvent::Died {
+                name: "Ant".to_string()
+            },
+            GameEvent::Left {
+                name: "Ant".to_string()
+            },
+        ]
+    );
+    assert!(world_2.is_alive(knight));
+    drop(world_2);
+    assert_eq!(
+        log_2.events().last(),
+        Some(&GameEvent::Left {
+            name: "Knight".to_string()
+        })
+    );
+    drop(world);
+    let left: Vec<GameEvent> = log
+        .events()
+        .into_iter()
+        .filter(|e| matches!(e, GameEvent::Left { .. }))
+        .collect();
+    assert_eq!(left.len(), 2);
+
+    // A scripted terminal session: invalid input is rejected, and the heroes fall back to the
+    // Ai when the input runs out.
+    let mut world = standard_world(7);
+    let script: &[u8] = b"9\n3\nd\nnonsense\n4\n";
+    let mut terminal = Terminal::new(script, world.log());
+    let winner = world.run(&mut terminal, MAX_ROUNDS);
+    terminal.show_events();
+    println!(
+        "Scripted game: {:?} win after {} rounds",
+        winner,
+        world.round()
+    );
+    assert!(terminal.autopilot);
+    assert!(winner.is_some());
+    let defended = world
+        .log()
+        .events()
+        .iter()
+        .filter(|e| matches!(e, GameEvent::Defended { .. }))
+        .count();
+    assert_eq!(defended, 1);
+}
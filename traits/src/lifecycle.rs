@@ -0,0 +1,276 @@
+/*
+    Recording when values are created and dropped, to make RAII and move semantics visible.
+
+    A LifecycleTracker keeps a log of lifecycle events. An instrumented type asks the tracker for a
+    Tracked token when it is created and keeps the token as a field. Rust drops a struct's fields
+    right after running the struct's own Drop impl, so the token's Drop records the owner's death
+    without the owner writing any code for it. Creature::tracked below works this way, handing the
+    token to the creature as a guard.
+
+    The tokens hold only a Weak reference to the log, so they never keep the tracker alive. When the
+    tracker itself is dropped, normally at the end of the program or test, any value that was
+    created but never dropped is a leak, and the tracker reports it on stderr; `finish` returns
+    the report instead, for leaks a test expects. Safe Rust can still leak: mem::forget, Box::leak
+    and Rc cycles all skip the destructor.
+
+    With the log in hand, the demo checks the rules for when Rust drops values:
+
+    - locals are dropped at the end of their scope, in reverse order of declaration;
+    - a moved value is dropped by its new owner, not where it was created;
+    - shadowing a variable does not drop the old value; it lives until the end of the scope;
+    - struct fields, and the elements of a Vec, are dropped in order, first to last;
+    - an Rc's value is dropped when the last clone is.
+*/
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use crate::drop::Creature;
+
+/// Something that happened to a tracked value, identified by the order it was created in.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Event {
+    Created { id: usize, name: String },
+    Dropped { id: usize, name: String },
+}
+
+#[derive(Default)]
+struct Log {
+    names: Vec<String>,
+    alive: Vec<bool>,
+    events: Vec<Event>,
+}
+
+impl Creature {
+    /// Creates a new Creature whose creation and drop are recorded by `tracker`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - A string slice that holds the name of the creature.
+    /// * `tracker` - The tracker to report to.
+    pub(crate) fn tracked(name: &str, tracker: &LifecycleTracker) -> Creature {
+        Creature::with_guard(name, tracker.track(name))
+    }
+}
+
+/// Records creation and drop events for instrumented values.
+pub(crate) struct LifecycleTracker {
+    log: Rc<RefCell<Log>>,
+}
+
+/// Held by an instrumented value for its whole life; dropping it records the value's drop.
+pub(crate) struct Tracked {
+    log: Weak<RefCell<Log>>,
+    id: usize,
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        // Values outliving the tracker have nobody left to report to.
+        if let Some(log) = self.log.upgrade() {
+            let mut log = log.borrow_mut();
+            log.alive[self.id] = false;
+            let name = log.names[self.id].clone();
+            log.events.push(Event::Dropped { id: self.id, name });
+        }
+    }
+}
+
+impl LifecycleTracker {
+    pub(crate) fn new() -> LifecycleTracker {
+        LifecycleTracker {
+            log: Rc::new(RefCell::new(Log::default())),
+        }
+    }
+
+    /// Records the creation of a value and returns the token that records its drop.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - How the value appears in the log. Names need not be unique.
+    pub(crate) fn track(&self, name: &str) -> Tracked {
+        let mut log = self.log.borrow_mut();
+        let id = log.names.len();
+        log.names.push(name.to_string());
+        log.alive.push(true);
+        log.events.push(Event::Created {
+            id,
+            name: name.to_string(),
+        });
+        Tracked {
+            log: Rc::downgrade(&self.log),
+            id,
+        }
+    }
+
+    /// Returns every event so far, oldest first.
+    pub(crate) fn events(&self) -> Vec<Event> {
+        self.log.borrow().events.clone()
+    }
+
+    /// Returns the names of the dropped values, in the order they were dropped.
+    pub(crate) fn drop_order(&self) -> Vec<String> {
+        self.log
+            .borrow()
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Dropped { name, .. } => Some(name.clone()),
+                Event::Created { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Returns the names of the values created but not yet dropped, in creation order.
+    pub(crate) fn alive(&self) -> Vec<String> {
+        let log = self.log.borrow();
+        log.names
+            .iter()
+            .zip(&log.alive)
+            .filter(|(_, alive)| **alive)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Panics unless the values dropped so far were dropped in exactly this order.
+    #[track_caller]
+    pub(crate) fn assert_drop_order(&self, expected: &[&str]) {
+        let actual = self.drop_order();
+        assert!(
+            actual == expected,
+            "expected the drop order {:?}, but it was {:?}",
+            expected,
+            actual
+        );
+    }
+
+    /// Forgets all events so far, so the next checks start from a clean log. Values still alive
+    /// stay tracked.
+    pub(crate) fn clear_events(&self) {
+        self.log.borrow_mut().events.clear();
+    }
+
+    /// Returns the report of the values never dropped, or None if there are none.
+    pub(crate) fn leak_report(&self) -> Option<String> {
+        let leaks = self.alive();
+        (!leaks.is_empty()).then(|| {
+            format!(
+                "LifecycleTracker: {} value(s) never dropped: {:?}",
+                leaks.len(),
+                leaks
+            )
+        })
+    }
+
+    /// Stops tracking and returns the leak report instead of printing it, for checking leaks that
+    /// are expected. Values still alive afterwards are no longer recorded.
+    pub(crate) fn finish(self) -> Option<String> {
+        let report = self.leak_report();
+        self.log.borrow_mut().alive.fill(false);
+        report
+    }
+}
+
+impl Drop for LifecycleTracker {
+    /// Flags every value that is still alive: its destructor never ran, or will run too late to
+    /// be recorded.
+    fn drop(&mut self) {
+        if let Some(report) = self.leak_report() {
+            eprintln!("{}", report);
+        }
+    }
+}
+
+/// Owns two creatures, to show the order struct fields are dropped in.
+struct Party {
+    _leader: Creature,
+    _follower: Creature,
+}
+
+/// Takes ownership of a creature; it is dropped when this function returns.
+fn consume(creature: Creature) {
+    println!("{} is consumed", creature.name());
+}
+
+/// Demonstrates checking Rust's drop rules with a LifecycleTracker.
+pub fn lifecycle_demo() {
+    println!("Lifecycle tracking:");
+    let tracker = LifecycleTracker::new();
+
+    // Locals are dropped in reverse order of declaration.
+    {
+        let _first = Creature::tracked("first", &tracker);
+        let _second = Creature::tracked("second", &tracker);
+        let _third = Creature::tracked("third", &tracker);
+    }
+    tracker.assert_drop_order(&["third", "second", "first"]);
+    tracker.clear_events();
+
+    // A moved value is dropped by its new owner, here at the end of `consume`.
+    {
+        let orc = Creature::tracked("orc", &tracker);
+        let _troll = Creature::tracked("troll", &tracker);
+        consume(orc);
+        tracker.assert_drop_order(&["orc"]);
+    }
+    tracker.assert_drop_order(&["orc", "troll"]);
+    tracker.clear_events();
+
+    // Shadowing hides a value but does not drop it; the hidden value lives to the end of the
+    // scope. Passing the visible one to `drop` ends it early.
+    {
+        let slime = Creature::tracked("slime", &tracker);
+        println!("{} splits", slime.name());
+        let slime = Creature::tracked("bigger slime", &tracker);
+        assert_eq!(tracker.alive(), ["slime", "bigger slime"]);
+        drop(slime);
+        tracker.assert_drop_order(&["bigger slime"]);
+    }
+    tracker.assert_drop_order(&["bigger slime", "slime"]);
+    tracker.clear_events();
+
+    // Struct fields and Vec elements are dropped first to last.
+    {
+        let _party = Party {
+            _leader: Creature::tracked("leader", &tracker),
+            _follower: Creature::tracked("follower", &tracker),
+        };
+        let _horde: Vec<Creature> = ["goblin 1", "goblin 2", "goblin 3"]
+            .iter()
+            .map(|name| Creature::tracked(name, &tracker))
+            .collect();
+    }
+    tracker.assert_drop_order(&["goblin 1", "goblin 2", "goblin 3", "leader", "follower"]);
+    tracker.clear_events();
+
+    // An Rc's value lives until its last clone is dropped.
+    {
+        let dragon = Rc::new(Creature::tracked("dragon", &tracker));
+        let lair = Rc::clone(&dragon);
+        drop(dragon);
+        assert!(tracker.drop_order().is_empty());
+        drop(lair);
+        tracker.assert_drop_order(&["dragon"]);
+    }
+
+    // Creation and drop events interleave in the log as they happen.
+    tracker.clear_events();
+    drop(Creature::tracked("moth", &tracker));
+    let events = tracker.events();
+    assert!(matches!(
+        &events[..],
+        [Event::Created { id: a, name }, Event::Dropped { id: b, .. }] if a == b && name == "moth"
+    ));
+
+    assert_eq!(tracker.leak_report(), None);
+
+    // Forgetting a value skips its destructor, so it is never recorded as dropped. Dropping its
+    // tracker would print a leak warning; finishing it returns the report to check instead.
+    let haunted = LifecycleTracker::new();
+    std::mem::forget(Creature::tracked("ghost", &haunted));
+    assert_eq!(haunted.alive(), ["ghost"]);
+    assert_eq!(
+        haunted.finish().as_deref(),
+        Some("LifecycleTracker: 1 value(s) never dropped: [\"ghost\"]")
+    );
+}
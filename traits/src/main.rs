@@ -1,19 +1,87 @@
+mod animal_registry;
+mod animal_serde;
+mod collision;
+mod complex_format;
+mod complex_math;
+mod complex_order;
+mod dispatch_benchmark;
 mod drop;
+mod fft;
+mod game;
 mod into;
+mod lifecycle;
+mod matrix;
+mod num;
 mod operator_overloading;
+mod polynomial;
+mod pretty;
+mod rng;
+mod shapes;
 mod static_and_dyn_dispatch;
+mod svg;
 mod trait_parameters;
 mod traits;
 mod vectors_of_diff_objects;
 mod why_dyn_dispatch;
 
+/// Prints what went wrong with the command line and how to use it, then exits.
+fn usage(problem: &str) -> ! {
+    eprintln!("{}", problem);
+    eprintln!("usage: traits [bench [max_exponent] | svg [path] | play [seed]]");
+    std::process::exit(2);
+}
+
 fn main() {
+    // `cargo run --release -- bench [max_exponent]` runs the dispatch benchmarks instead of the
+    // demos.
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("bench") {
+        let Ok(max_exponent) = args.get(2).map_or(Ok(7), |arg| arg.parse()) else {
+            usage("the maximum exponent must be a number");
+        };
+        dispatch_benchmark::run(max_exponent);
+        // Brute-force collision detection is quadratic, so its scenes stop at 10^4 shapes.
+        collision::run(max_exponent.min(4));
+        return;
+    }
+    // `cargo run -- svg [path]` writes the SVG demo scene to a file.
+    if args.get(1).map(String::as_str) == Some("svg") {
+        let path = args.get(2).map_or("shapes.svg", String::as_str);
+        svg::with_demo_scene(|scene| scene.save(path)).expect("could not write the SVG file");
+        println!("Wrote {}", path);
+        return;
+    }
+    // `cargo run -- play [seed]` plays the combat game in the terminal.
+    if args.get(1).map(String::as_str) == Some("play") {
+        let Ok(seed) = args.get(2).map_or(Ok(42), |arg| arg.parse()) else {
+            usage("the seed must be a number");
+        };
+        game::play(seed);
+        return;
+    }
+
     traits::traits();
     trait_parameters::trait_parameters();
     into::into();
     drop::drop_demo();
+    lifecycle::lifecycle_demo();
+    game::game_demo();
     operator_overloading::operator_overloading_demo();
+    complex_math::complex_math_demo();
+    complex_format::complex_format_demo();
+    complex_order::complex_order_demo();
+    num::num_demo();
+    fft::fft_demo();
+    polynomial::polynomial_demo();
+    matrix::matrix_demo();
     static_and_dyn_dispatch::static_and_dyn_dispatch_demo();
+    pretty::pretty_demo();
     why_dyn_dispatch::why_dyn_dispatch_demo();
+    shapes::shapes_demo();
+    svg::svg_demo();
+    collision::collision_demo();
+    dispatch_benchmark::dispatch_benchmark_demo();
     vectors_of_diff_objects::vectors_of_different_objects_demo();
+    animal_registry::animal_registry_demo();
+    animal_serde::animal_serde_demo();
 }
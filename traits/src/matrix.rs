@@ -0,0 +1,640 @@
+/*
+    A dense matrix over any Num type, with complex linear algebra on top.
+
+    Matrix<T> stores its elements in one Vec in row-major order. The arithmetic operators follow
+    the same pattern as Complex in operator_overloading: each operator is implemented once for
+    owned values, and a macro generates the reference variants (`&a * &b`, `a * &b`, `&a * b`) by
+    cloning. Because Complex<T> implements Num, Matrix<Complex<f64>> gets all of this for free.
+
+    Dimension mismatches are programming errors, so the operators panic on them like slice
+    indexing does. Operations that can fail on valid input, such as inverting a singular matrix,
+    return a MatrixError instead.
+
+    Determinants and inverses go through an LU decomposition with partial pivoting: PA = LU, where
+    P reorders the rows, L is unit lower triangular and U is upper triangular. Choosing the
+    largest remaining pivot in each column keeps the elimination numerically stable.
+*/
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::num::{Float, Num, One, Signed, Zero};
+use crate::operator_overloading::{Complex, ComplexDiv};
+
+/// The errors linear algebra operations can return.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum MatrixError {
+    /// The operation needs a square matrix.
+    NotSquare { rows: usize, cols: usize },
+    /// The matrix has no inverse because its determinant is zero.
+    Singular,
+    /// An element is NaN or infinite, so elimination would only produce NaNs.
+    NotFinite,
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatrixError::NotSquare { rows, cols } => {
+                write!(f, "expected a square matrix, got {}x{}", rows, cols)
+            }
+            MatrixError::Singular => write!(f, "the matrix is singular"),
+            MatrixError::NotFinite => write!(f, "the matrix has a NaN or infinite element"),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
+
+/// A rows x cols matrix stored in row-major order.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T: Num> Matrix<T> {
+    /// Creates a matrix from its elements in row-major order.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The number of rows.
+    /// * `cols` - The number of columns.
+    /// * `data` - The rows * cols elements, one row after another.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` does not have exactly rows * cols elements.
+    pub(crate) fn new(rows: usize, cols: usize, data: Vec<T>) -> Matrix<T> {
+        assert_eq!(
+            data.len(),
+            rows * cols,
+            "a {}x{} matrix needs {} elements",
+            rows,
+            cols,
+            rows * cols
+        );
+        Matrix { rows, cols, data }
+    }
+
+    /// Creates a matrix from a list of rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows do not all have the same length.
+    pub(crate) fn from_rows(rows: Vec<Vec<T>>) -> Matrix<T> {
+        let cols = rows.first().map_or(0, Vec::len);
+        assert!(
+            rows.iter().all(|row| row.len() == cols),
+            "all rows must have the same length"
+        );
+        Matrix::new(rows.len(), cols, rows.into_iter().flatten().collect())
+    }
+
+    /// Creates a rows x cols matrix of zeros.
+    pub(crate) fn zeros(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix::new(rows, cols, vec![T::zero(); rows * cols])
+    }
+
+    /// Creates the n x n identity matrix.
+    pub(crate) fn identity(n: usize) -> Matrix<T> {
+        let mut m = Matrix::zeros(n, n);
+        for i in 0..n {
+            m[(i, i)] = T::one();
+        }
+        m
+    }
+
+    /// The number of rows.
+    pub(crate) fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The number of columns.
+    pub(crate) fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns whether the matrix has as many rows as columns.
+    pub(crate) fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    /// Returns the transpose, with rows and columns swapped.
+    pub(crate) fn transpose(&self) -> Matrix<T> {
+        let mut data = Vec::with_capacity(self.data.len());
+        for c in 0..self.cols {
+            for r in 0..self.rows {
+                data.push(self[(r, c)].clone());
+            }
+        }
+        Matrix::new(self.cols, self.rows, data)
+    }
+
+    /// Returns the Kronecker (tensor) product, which combines the operators of two independent
+    /// systems into one operator on the joint system.
+    pub(crate) fn kronecker(&self, other: &Matrix<T>) -> Matrix<T> {
+        let mut m = Matrix::zeros(self.rows * other.rows, self.cols * other.cols);
+        for r in 0..m.rows {
+            for c in 0..m.cols {
+                m[(r, c)] = self[(r / other.rows, c / other.cols)].clone()
+                    * other[(r % other.rows, c % other.cols)].clone();
+            }
+        }
+        m
+    }
+
+    /// Panics unless both matrices have the same dimensions.
+    fn assert_same_shape(&self, other: &Matrix<T>, op: &str) {
+        assert!(
+            self.rows == other.rows && self.cols == other.cols,
+            "cannot {} a {}x{} matrix and a {}x{} matrix",
+            op,
+            self.rows,
+            self.cols,
+            other.rows,
+            other.cols
+        );
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    /// Returns the element at (row, column).
+    fn index(&self, (r, c): (usize, usize)) -> &T {
+        assert!(
+            r < self.rows && c < self.cols,
+            "index ({}, {}) out of bounds",
+            r,
+            c
+        );
+        &self.data[r * self.cols + c]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut T {
+        assert!(
+            r < self.rows && c < self.cols,
+            "index ({}, {}) out of bounds",
+            r,
+            c
+        );
+        &mut self.data[r * self.cols + c]
+    }
+}
+
+/// Element-wise addition.
+impl<T: Num> Add for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(mut self, rhs: Matrix<T>) -> Matrix<T> {
+        self += rhs;
+        self
+    }
+}
+
+impl<T: Num> AddAssign for Matrix<T> {
+    fn add_assign(&mut self, rhs: Matrix<T>) {
+        self.assert_same_shape(&rhs, "add");
+        for (a, b) in self.data.iter_mut().zip(rhs.data) {
+            *a += b;
+        }
+    }
+}
+
+/// Element-wise subtraction.
+impl<T: Num> Sub for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn sub(mut self, rhs: Matrix<T>) -> Matrix<T> {
+        self -= rhs;
+        self
+    }
+}
+
+impl<T: Num> SubAssign for Matrix<T> {
+    fn sub_assign(&mut self, rhs: Matrix<T>) {
+        self.assert_same_shape(&rhs, "subtract");
+        for (a, b) in self.data.iter_mut().zip(rhs.data) {
+            *a -= b;
+        }
+    }
+}
+
+/// Matrix multiplication: an m x n matrix times an n x p matrix gives an m x p matrix.
+impl<T: Num> Mul for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Matrix<T>) -> Matrix<T> {
+        assert_eq!(
+            self.cols, rhs.rows,
+            "cannot multiply a {}x{} matrix by a {}x{} matrix",
+            self.rows, self.cols, rhs.rows, rhs.cols
+        );
+        let mut m = Matrix::zeros(self.rows, rhs.cols);
+        for r in 0..self.rows {
+            for c in 0..rhs.cols {
+                let mut sum = T::zero();
+                for k in 0..self.cols {
+                    sum += self[(r, k)].clone() * rhs[(k, c)].clone();
+                }
+                m[(r, c)] = sum;
+            }
+        }
+        m
+    }
+}
+
+impl<T: Num> MulAssign for Matrix<T> {
+    fn mul_assign(&mut self, rhs: Matrix<T>) {
+        *self = self.clone() * rhs;
+    }
+}
+
+impl<T: Signed> Neg for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn neg(self) -> Matrix<T> {
+        Matrix::new(
+            self.rows,
+            self.cols,
+            self.data.into_iter().map(|x| -x).collect(),
+        )
+    }
+}
+
+impl<T: Signed> Neg for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn neg(self) -> Matrix<T> {
+        -self.clone()
+    }
+}
+
+/// Scales every element by a scalar.
+impl<T: Num> Mul<T> for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(mut self, rhs: T) -> Matrix<T> {
+        self *= rhs;
+        self
+    }
+}
+
+impl<T: Num> MulAssign<T> for Matrix<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        for x in &mut self.data {
+            *x *= rhs.clone();
+        }
+    }
+}
+
+/// Generates the reference variants of a binary matrix operator and its assignment operator, like
+/// forward_ref_ops does for Complex.
+macro_rules! forward_ref_matrix_ops {
+    ($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident) => {
+        impl<'a, T: Num> $Op<&'a Matrix<T>> for &'a Matrix<T> {
+            type Output = Matrix<T>;
+
+            fn $op(self, rhs: &'a Matrix<T>) -> Matrix<T> {
+                self.clone().$op(rhs.clone())
+            }
+        }
+
+        impl<'a, T: Num> $Op<&'a Matrix<T>> for Matrix<T> {
+            type Output = Matrix<T>;
+
+            fn $op(self, rhs: &'a Matrix<T>) -> Matrix<T> {
+                self.$op(rhs.clone())
+            }
+        }
+
+        impl<'a, T: Num> $Op<Matrix<T>> for &'a Matrix<T> {
+            type Output = Matrix<T>;
+
+            fn $op(self, rhs: Matrix<T>) -> Matrix<T> {
+                self.clone().$op(rhs)
+            }
+        }
+
+        impl<'a, T: Num> $OpAssign<&'a Matrix<T>> for Matrix<T> {
+            fn $op_assign(&mut self, rhs: &'a Matrix<T>) {
+                self.$op_assign(rhs.clone());
+            }
+        }
+    };
+}
+
+forward_ref_matrix_ops!(Add, add, AddAssign, add_assign);
+forward_ref_matrix_ops!(Sub, sub, SubAssign, sub_assign);
+forward_ref_matrix_ops!(Mul, mul, MulAssign, mul_assign);
+
+impl<T: Signed + ComplexDiv> Matrix<Complex<T>> {
+    /// Returns the conjugate transpose A†, the transpose with every element conjugated. For real
+    /// matrices it is just the transpose.
+    pub(crate) fn conjugate_transpose(&self) -> Matrix<Complex<T>> {
+        let mut m = self.transpose();
+        for z in &mut m.data {
+            *z = z.conj();
+        }
+        m
+    }
+}
+
+/// An LU decomposition with partial pivoting of a square complex matrix.
+pub(crate) struct Lu<T> {
+    /// L below the diagonal (its unit diagonal is implied) and U on and above it.
+    factors: Matrix<Complex<T>>,
+    /// Row i of PA is row permutation[i] of A.
+    permutation: Vec<usize>,
+    /// Whether P swaps an odd number of rows, which flips the sign of the determinant.
+    odd_swaps: bool,
+    /// Whether some pivot was zero (relative to the matrix's scale).
+    singular: bool,
+}
+
+impl<T: Float> Matrix<Complex<T>> {
+    /// Computes the LU decomposition PA = LU with partial pivoting. Matrices with a NaN or infinite
+    /// element are rejected with MatrixError::NotFinite.
+    pub(crate) fn lu(&self) -> Result<Lu<T>, MatrixError> {
+        if !self.is_square() {
+            return Err(MatrixError::NotSquare {
+                rows: self.rows,
+                cols: self.cols,
+            });
+        }
+        if !self.data.iter().all(Complex::is_finite) {
+            return Err(MatrixError::NotFinite);
+        }
+        let n = self.rows;
+        let mut a = self.clone();
+        let mut permutation: Vec<usize> = (0..n).collect();
+        let mut odd_swaps = false;
+        let mut singular = false;
+        // Pivots this small compared to the largest element are rounding noise around zero.
+        let scale = self
+            .data
+            .iter()
+            .map(|z| z.abs())
+            .fold(T::zero(), |m, x| if x > m { x } else { m });
+        let threshold = scale * T::epsilon() * T::from_f64(n as f64);
+
+        for k in 0..n {
+            // Swap the row with the largest element in column k up to the pivot position. Elements
+            // can still overflow into NaN during elimination; NaN never compares larger, so it is
+            // not picked over a number and cannot make the comparison panic.
+            let pivot_row = (k + 1..n).fold(k, |best, i| {
+                if a[(i, k)].abs() >= a[(best, k)].abs() {
+                    i
+                } else {
+                    best
+                }
+            });
+            if pivot_row != k {
+                for c in 0..n {
+                    a.data.swap(k * n + c, pivot_row * n + c);
+                }
+                permutation.swap(k, pivot_row);
+                odd_swaps = !odd_swaps;
+            }
+            if a[(k, k)].abs() <= threshold {
+                singular = true;
+                continue;
+            }
+            for r in k + 1..n {
+                let factor = &a[(r, k)] / &a[(k, k)];
+                for c in k + 1..n {
+                    let update = &factor * &a[(k, c)];
+                    a[(r, c)] -= update;
+                }
+                a[(r, k)] = factor;
+            }
+        }
+        Ok(Lu {
+            factors: a,
+            permutation,
+            odd_swaps,
+            singular,
+        })
+    }
+
+    /// Returns the determinant, the product of U's diagonal with the sign of the permutation.
+    pub(crate) fn determinant(&self) -> Result<Complex<T>, MatrixError> {
+        Ok(self.lu()?.determinant())
+    }
+
+    /// Returns the inverse matrix, or MatrixError::Singular if there is none.
+    pub(crate) fn inverse(&self) -> Result<Matrix<Complex<T>>, MatrixError> {
+        self.lu()?.inverse()
+    }
+
+    /// Returns whether every element is within `eps` of the corresponding element of `other`.
+    pub(crate) fn approx_eq(&self, other: &Matrix<Complex<T>>, eps: T) -> bool {
+        self.rows == other.rows
+            && self.cols == other.cols
+            && self
+                .data
+                .iter()
+                .zip(&other.data)
+                .all(|(a, b)| (a - b).abs() <= eps)
+    }
+
+    /// Returns whether the matrix is unitary, U†U = I, which is what makes it a valid quantum gate.
+    pub(crate) fn is_unitary(&self, eps: T) -> bool {
+        self.is_square()
+            && (&self.conjugate_transpose() * self).approx_eq(&Matrix::identity(self.rows), eps)
+    }
+}
+
+impl<T: Float> Lu<T> {
+    /// The determinant of the decomposed matrix.
+    pub(crate) fn determinant(&self) -> Complex<T> {
+        let n = self.factors.rows;
+        let product: Complex<T> = (0..n).map(|i| self.factors[(i, i)].clone()).product();
+        if self.odd_swaps { -product } else { product }
+    }
+
+    /// Solves Ax = b for x by forward substitution with L and back substitution with U.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The right-hand side, one entry per row of A.
+    pub(crate) fn solve(&self, b: &[Complex<T>]) -> Result<Vec<Complex<T>>, MatrixError> {
+        if self.singular {
+            return Err(MatrixError::Singular);
+        }
+        let n = self.factors.rows;
+        assert_eq!(b.len(), n, "the right-hand side needs {} entries", n);
+        let lu = &self.factors;
+
+        let mut y: Vec<Complex<T>> = self.permutation.iter().map(|&i| b[i].clone()).collect();
+        for r in 0..n {
+            for c in 0..r {
+                let update = &lu[(r, c)] * &y[c];
+                y[r] -= update;
+            }
+        }
+        for r in (0..n).rev() {
+            for c in r + 1..n {
+                let update = &lu[(r, c)] * &y[c];
+                y[r] -= update;
+            }
+            y[r] /= lu[(r, r)].clone();
+        }
+        Ok(y)
+    }
+
+    /// Computes the inverse by solving for each column of the identity matrix.
+    pub(crate) fn inverse(&self) -> Result<Matrix<Complex<T>>, MatrixError> {
+        let n = self.factors.rows;
+        let mut inverse = Matrix::zeros(n, n);
+        for c in 0..n {
+            let mut unit = vec![Complex::zero(); n];
+            unit[c] = Complex::one();
+            for (r, x) in self.solve(&unit)?.into_iter().enumerate() {
+                inverse[(r, c)] = x;
+            }
+        }
+        Ok(inverse)
+    }
+}
+
+/// Prints one row per line, with the formatter's flags (like `{:.3}`) applied to each element.
+impl<T: fmt::Display> fmt::Display for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.data.chunks(self.cols.max(1)) {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|x| match f.precision() {
+                    Some(p) => format!("{:.*}", p, x),
+                    None => format!("{}", x),
+                })
+                .collect();
+            writeln!(f, "[{}]", cells.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Demonstrates complex matrices by simulating a small quantum circuit.
+pub fn matrix_demo() {
+    println!("Complex matrices:");
+    const EPS: f64 = 1e-12;
+    let c = |re: f64, im: f64| Complex::new(re, im);
+    let h = std::f64::consts::FRAC_1_SQRT_2;
+
+    // Single-qubit gates.
+    let hadamard = Matrix::from_rows(vec![
+        vec![c(h, 0.0), c(h, 0.0)],
+        vec![c(h, 0.0), c(-h, 0.0)],
+    ]);
+    let pauli_x: Matrix<Complex<f64>> = Matrix::from_rows(vec![
+        vec![c(0.0, 0.0), c(1.0, 0.0)],
+        vec![c(1.0, 0.0), c(0.0, 0.0)],
+    ]);
+    let pauli_y = Matrix::from_rows(vec![
+        vec![c(0.0, 0.0), c(0.0, -1.0)],
+        vec![c(0.0, 1.0), c(0.0, 0.0)],
+    ]);
+    let pauli_z = Matrix::from_rows(vec![
+        vec![c(1.0, 0.0), c(0.0, 0.0)],
+        vec![c(0.0, 0.0), c(-1.0, 0.0)],
+    ]);
+    let identity = Matrix::identity(2);
+
+    for gate in [&hadamard, &pauli_x, &pauli_y, &pauli_z] {
+        assert!(gate.is_unitary(EPS));
+        // Each of these gates is its own inverse.
+        assert!((gate * gate).approx_eq(&identity, EPS));
+        assert!(gate.inverse().unwrap().approx_eq(gate, EPS));
+    }
+    // XY = iZ, and HZH = X.
+    assert!((&pauli_x * &pauli_y).approx_eq(&(pauli_z.clone() * Complex::i()), EPS));
+    assert!((&hadamard * &pauli_z * &hadamard).approx_eq(&pauli_x, EPS));
+    assert_eq!(pauli_y.determinant(), Ok(c(-1.0, 0.0)));
+
+    // A Bell state: H on the first qubit, then CNOT, starting from |00⟩.
+    let cnot = Matrix::from_rows(vec![
+        vec![c(1.0, 0.0), c(0.0, 0.0), c(0.0, 0.0), c(0.0, 0.0)],
+        vec![c(0.0, 0.0), c(1.0, 0.0), c(0.0, 0.0), c(0.0, 0.0)],
+        vec![c(0.0, 0.0), c(0.0, 0.0), c(0.0, 0.0), c(1.0, 0.0)],
+        vec![c(0.0, 0.0), c(0.0, 0.0), c(1.0, 0.0), c(0.0, 0.0)],
+    ]);
+    let mut zero_zero = Matrix::zeros(4, 1);
+    zero_zero[(0, 0)] = c(1.0, 0.0);
+    let circuit = &cnot * &hadamard.kronecker(&identity);
+    assert!(circuit.is_unitary(EPS));
+    let bell = &circuit * &zero_zero;
+    println!(
+        "Bell state (|00⟩ + |11⟩)/√2 as a column vector:\n{:.4}",
+        bell
+    );
+    let expected = Matrix::new(4, 1, vec![c(h, 0.0), c(0.0, 0.0), c(0.0, 0.0), c(h, 0.0)]);
+    assert!(bell.approx_eq(&expected, EPS));
+
+    // A general complex matrix: determinant, inverse and solving a linear system.
+    let a = Matrix::from_rows(vec![
+        vec![c(2.0, 1.0), c(0.0, -1.0), c(1.0, 0.0)],
+        vec![c(1.0, 0.0), c(3.0, 2.0), c(0.0, 1.0)],
+        vec![c(0.0, 2.0), c(1.0, 1.0), c(4.0, 0.0)],
+    ]);
+    let det = a.determinant().unwrap();
+    // Matches the cofactor expansion along the first row.
+    println!("det A = {:.4}", det);
+    assert!((&det - &c(24.0, 28.0)).abs() < EPS);
+    let inverse = a.inverse().unwrap();
+    assert!((&a * &inverse).approx_eq(&Matrix::identity(3), EPS));
+    assert!((&inverse * &a).approx_eq(&Matrix::identity(3), EPS));
+    // det(A†) is the conjugate of det A.
+    assert!((&a.conjugate_transpose().determinant().unwrap() - &det.conj()).abs() < EPS);
+
+    let x = vec![c(1.0, -1.0), c(0.5, 0.0), c(0.0, 2.0)];
+    let b = &a * &Matrix::new(3, 1, x.clone());
+    let solved = a.lu().unwrap().solve(&b.data).unwrap();
+    assert!(Matrix::new(3, 1, solved).approx_eq(&Matrix::new(3, 1, x), EPS));
+
+    // Element-wise arithmetic and the reference operators.
+    let mut sum = &a + &a;
+    sum -= &a;
+    assert_eq!(sum, a);
+    assert_eq!(-&a + a.clone() * c(2.0, 0.0), a);
+    let mut product = a.clone();
+    product *= &Matrix::identity(3);
+    assert_eq!(product, a);
+
+    // Singular and non-square matrices are reported as errors.
+    let singular = Matrix::from_rows(vec![
+        vec![c(1.0, 1.0), c(2.0, 2.0)],
+        vec![c(2.0, 2.0), c(4.0, 4.0)],
+    ]);
+    assert_eq!(singular.determinant(), Ok(c(0.0, 0.0)));
+    assert_eq!(singular.inverse(), Err(MatrixError::Singular));
+    let wide = Matrix::<Complex<f64>>::zeros(2, 3);
+    println!(
+        "Inverting a {}x{} matrix: {}",
+        wide.rows(),
+        wide.cols(),
+        wide.inverse().unwrap_err()
+    );
+    assert_eq!(
+        wide.inverse(),
+        Err(MatrixError::NotSquare { rows: 2, cols: 3 })
+    );
+    let poisoned = Matrix::from_rows(vec![
+        vec![c(1.0, 0.0), c(f64::NAN, 0.0)],
+        vec![c(0.0, 0.0), c(1.0, 0.0)],
+    ]);
+    assert_eq!(poisoned.determinant(), Err(MatrixError::NotFinite));
+    assert_eq!(poisoned.inverse(), Err(MatrixError::NotFinite));
+
+    // The generic operations work for integer matrices as well.
+    let m = Matrix::from_rows(vec![vec![1, 2], vec![3, 4]]);
+    assert_eq!(&m * &m, Matrix::from_rows(vec![vec![7, 10], vec![15, 22]]));
+    assert_eq!(
+        m.transpose(),
+        Matrix::from_rows(vec![vec![1, 3], vec![2, 4]])
+    );
+}
@@ -0,0 +1,318 @@
+/*
+    A numeric trait hierarchy.
+
+    Generic code over numbers tends to accumulate long bounds like
+    `T: Clone + Add<Output = T> + Sub<Output = T> + Mul<Output = T>`. Supertraits let us name such a
+    group of capabilities once and build more specific traits on top of it:
+
+        Zero, One        the additive and multiplicative identities
+          └── Num        the arithmetic operators every primitive number supports
+                └── Signed   numbers that can be negated
+                      └── Float  floating-point numbers and their math functions
+
+    Each trait is implemented for the primitive types with a macro, since the implementations only
+    differ in the type name. Complex<T> implements Zero, One, Num and Signed itself whenever T does,
+    so generic numeric code (a matrix, a polynomial) works for complex numbers too. Dividing complex
+    numbers also needs T to pick a division formula, through operator_overloading's ComplexDiv,
+    which every primitive number implements.
+*/
+
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::operator_overloading::{Complex, ComplexDiv};
+
+/// Types with an additive identity: x + zero() == x.
+pub(crate) trait Zero: Sized + Add<Output = Self> {
+    /// Returns the additive identity, 0.
+    fn zero() -> Self;
+
+    /// Returns whether `self` equals zero.
+    fn is_zero(&self) -> bool;
+}
+
+/// Types with a multiplicative identity: x * one() == x.
+pub(crate) trait One: Sized + Mul<Output = Self> {
+    /// Returns the multiplicative identity, 1.
+    fn one() -> Self;
+}
+
+/// The arithmetic shared by all primitive numbers: the four operators, their assignment forms,
+/// the identities, cloning and equality.
+pub(crate) trait Num:
+    Clone
+    + PartialEq
+    + Zero
+    + One
+    + Sub<Output = Self>
+    + Div<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+{
+}
+
+/// Numbers that have a negation, which excludes the unsigned integers.
+pub(crate) trait Signed: Num + Neg<Output = Self> {}
+
+/// Floating-point numbers. The methods mirror the inherent methods of f32 and f64 so generic code
+/// can call them, plus the constants and conversions such code needs.
+pub(crate) trait Float: Signed + Copy + PartialOrd {
+    fn nan() -> Self;
+    fn infinity() -> Self;
+    /// The difference between 1.0 and the next larger representable number.
+    fn epsilon() -> Self;
+    fn pi() -> Self;
+    /// Converts an f64 constant to this type, rounding if necessary.
+    fn from_f64(value: f64) -> Self;
+    fn is_nan(self) -> bool;
+    fn is_infinite(self) -> bool;
+    fn is_finite(self) -> bool;
+    fn abs(self) -> Self;
+    fn copysign(self, sign: Self) -> Self;
+    fn sqrt(self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn sinh(self) -> Self;
+    fn cosh(self) -> Self;
+    fn asinh(self) -> Self;
+}
+
+macro_rules! impl_num {
+    ($($t:ty),*) => {$(
+        impl Zero for $t {
+            fn zero() -> $t {
+                0 as $t
+            }
+
+            fn is_zero(&self) -> bool {
+                *self == 0 as $t
+            }
+        }
+
+        impl One for $t {
+            fn one() -> $t {
+                1 as $t
+            }
+        }
+
+        impl Num for $t {}
+    )*};
+}
+
+impl_num!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+);
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {$(
+        impl Signed for $t {}
+    )*};
+}
+
+impl_signed!(i8, i16, i32, i64, i128, isize, f32, f64);
+
+macro_rules! impl_float {
+    ($($t:ident),*) => {$(
+        impl Float for $t {
+            fn nan() -> $t {
+                $t::NAN
+            }
+
+            fn infinity() -> $t {
+                $t::INFINITY
+            }
+
+            fn epsilon() -> $t {
+                $t::EPSILON
+            }
+
+            fn pi() -> $t {
+                std::$t::consts::PI
+            }
+
+            fn from_f64(value: f64) -> $t {
+                value as $t
+            }
+
+            fn is_nan(self) -> bool {
+                $t::is_nan(self)
+            }
+
+            fn is_infinite(self) -> bool {
+                $t::is_infinite(self)
+            }
+
+            fn is_finite(self) -> bool {
+                $t::is_finite(self)
+            }
+
+            fn abs(self) -> $t {
+                $t::abs(self)
+            }
+
+            fn copysign(self, sign: $t) -> $t {
+                $t::copysign(self, sign)
+            }
+
+            fn sqrt(self) -> $t {
+                $t::sqrt(self)
+            }
+
+            fn hypot(self, other: $t) -> $t {
+                $t::hypot(self, other)
+            }
+
+            fn exp(self) -> $t {
+                $t::exp(self)
+            }
+
+            fn ln(self) -> $t {
+                $t::ln(self)
+            }
+
+            fn powf(self, n: $t) -> $t {
+                $t::powf(self, n)
+            }
+
+            fn sin(self) -> $t {
+                $t::sin(self)
+            }
+
+            fn cos(self) -> $t {
+                $t::cos(self)
+            }
+
+            fn atan2(self, other: $t) -> $t {
+                $t::atan2(self, other)
+            }
+
+            fn sinh(self) -> $t {
+                $t::sinh(self)
+            }
+
+            fn cosh(self) -> $t {
+                $t::cosh(self)
+            }
+
+            fn asinh(self) -> $t {
+                $t::asinh(self)
+            }
+        }
+    )*};
+}
+
+impl_float!(f32, f64);
+
+impl<T: Num> Zero for Complex<T> {
+    fn zero() -> Complex<T> {
+        Complex::new(T::zero(), T::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.re.is_zero() && self.im.is_zero()
+    }
+}
+
+impl<T: Num> One for Complex<T> {
+    fn one() -> Complex<T> {
+        Complex::new(T::one(), T::zero())
+    }
+}
+
+impl<T: ComplexDiv> Num for Complex<T> {}
+
+impl<T: Signed + ComplexDiv> Signed for Complex<T> {}
+
+impl<T: Num> Complex<T> {
+    /// The imaginary unit i, with i² = -1.
+    pub(crate) fn i() -> Complex<T> {
+        Complex::new(T::zero(), T::one())
+    }
+}
+
+impl<T: Signed> Complex<T> {
+    /// The complex conjugate, re - im·i.
+    pub(crate) fn conj(&self) -> Complex<T> {
+        Complex::new(self.re.clone(), -self.im.clone())
+    }
+}
+
+/// Sums an iterator of complex numbers, starting from zero. This is what `iter.sum()` calls.
+impl<T: Num> Sum for Complex<T> {
+    fn sum<I: Iterator<Item = Complex<T>>>(iter: I) -> Complex<T> {
+        iter.fold(Complex::zero(), |acc, z| acc + z)
+    }
+}
+
+impl<'a, T: Num> Sum<&'a Complex<T>> for Complex<T> {
+    fn sum<I: Iterator<Item = &'a Complex<T>>>(iter: I) -> Complex<T> {
+        iter.fold(Complex::zero(), |acc, z| acc + z)
+    }
+}
+
+/// Multiplies an iterator of complex numbers, starting from one. This is what `iter.product()`
+/// calls.
+impl<T: Num> Product for Complex<T> {
+    fn product<I: Iterator<Item = Complex<T>>>(iter: I) -> Complex<T> {
+        iter.fold(Complex::one(), |acc, z| acc * z)
+    }
+}
+
+impl<'a, T: Num> Product<&'a Complex<T>> for Complex<T> {
+    fn product<I: Iterator<Item = &'a Complex<T>>>(iter: I) -> Complex<T> {
+        iter.fold(Complex::one(), |acc, z| acc * z)
+    }
+}
+
+/// Computes a polynomial with coefficients ordered from the constant term up, using Horner's
+/// rule. It only needs Num, so it works for integers, floats and complex numbers alike.
+pub(crate) fn evaluate<T: Num>(coefficients: &[T], x: &T) -> T {
+    coefficients
+        .iter()
+        .rev()
+        .fold(T::zero(), |acc, c| acc * x.clone() + c.clone())
+}
+
+/// Demonstrates the numeric traits and the Complex impls built on them.
+pub fn num_demo() {
+    println!("Numeric traits:");
+
+    // One generic function, four number types.
+    assert_eq!(evaluate(&[1, 2, 3], &2), 17);
+    assert_eq!(evaluate(&[1u8, 0, 1], &3), 10);
+    assert_eq!(evaluate(&[0.5, 0.5], &3.0), 2.0);
+    // x² + 1 has roots ±i.
+    let x_squared_plus_one = [Complex::one(), Complex::zero(), Complex::one()];
+    assert!(evaluate(&x_squared_plus_one, &Complex::<i32>::i()).is_zero());
+    println!(
+        "x² + 1 at i = {}",
+        evaluate(&x_squared_plus_one, &Complex::i())
+    );
+
+    assert_eq!(Complex::<i64>::i() * Complex::i(), -Complex::one());
+    assert_eq!(Complex::<f32>::zero(), Complex::new(0.0, 0.0));
+
+    let roots_of_unity: Vec<Complex<i32>> =
+        vec![Complex::one(), Complex::i(), -Complex::one(), -Complex::i()];
+    let sum: Complex<i32> = roots_of_unity.iter().sum();
+    let product: Complex<i32> = roots_of_unity.iter().product();
+    println!("Sum of the fourth roots of unity: {}", sum);
+    println!("Product of the fourth roots of unity: {}", product);
+    assert!(sum.is_zero());
+    assert_eq!(product, -Complex::one());
+    assert_eq!(
+        roots_of_unity.into_iter().sum::<Complex<i32>>(),
+        Complex::zero()
+    );
+
+    let empty: Vec<Complex<f64>> = Vec::new();
+    assert_eq!(empty.iter().sum::<Complex<f64>>(), Complex::zero());
+    assert_eq!(empty.iter().product::<Complex<f64>>(), Complex::one());
+}
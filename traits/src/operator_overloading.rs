@@ -4,25 +4,35 @@
    In Rust, operator overloading is achieved by implementing specific traits defined in the standard library.
    This allows developers to define custom behavior for operators (e.g., +, +=, -) on user-defined types.
    In this module, we illustrate operator overloading through a generic Complex number type.
+
+   Each binary operator is implemented once for owned values. The variants taking references
+   (`&a + &b`, `a + &b`, `&a + b`) are generated by a macro that clones the operands and forwards to
+   the owned implementation, so callers can keep using their values after an operation.
 */
 
 use std::cmp::PartialEq;
 use std::fmt::Debug;
-use std::ops::{Add, AddAssign, Neg};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::num::{Float, Num, Signed};
 
 /// A generic Complex number struct representing a number with a real and imaginary part.
 ///
-/// The #[derive] attribute automatically implements common traits such as Debug, PartialEq, Eq, Ord, and PartialOrd,
-/// allowing for easy printing, comparison, and ordering of Complex numbers.
-#[derive(Debug, PartialEq, Eq, Ord, PartialOrd)]
-struct Complex<T> {
-    re: T,
-    im: T,
+/// The #[derive] attribute automatically implements common traits such as Debug, Clone, PartialEq, Eq, and Hash,
+/// allowing for easy printing, cloning, equality comparison, and hashing of Complex numbers.
+///
+/// Complex numbers have no natural ordering, so Ord and PartialOrd are deliberately not derived: the derived
+/// versions would compare the real parts first, which says nothing about which number is "bigger". The
+/// complex_order module provides explicit comparators to sort by instead.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Complex<T> {
+    pub(crate) re: T,
+    pub(crate) im: T,
 }
 
 impl<T> Complex<T> {
     /// Constructs a new Complex number with the provided real (re) and imaginary (im) components.
-    fn new(re: T, im: T) -> Complex<T> {
+    pub(crate) fn new(re: T, im: T) -> Complex<T> {
         Complex { re, im }
     }
 }
@@ -30,10 +40,10 @@ impl<T> Complex<T> {
 /// Implements addition for Complex numbers by overloading the `+` operator through the Add trait.
 ///
 /// This lets us add two Complex values with the `+` operator. The generic type T must implement
-/// the Add trait where T + T yields a T.
+/// Num (see the num module), which includes the Add trait where T + T yields a T.
 impl<T> Add for Complex<T>
 where
-    T: Add<Output = T>, // T must support addition with an output of the same type.
+    T: Num, // T must support addition with an output of the same type, among other operations.
 {
     type Output = Complex<T>;
 
@@ -52,7 +62,7 @@ where
 /// This allows an existing Complex value to be incremented by another Complex value using the `+=` syntax.
 impl<T> AddAssign for Complex<T>
 where
-    T: AddAssign<T>,
+    T: Num,
 {
     /// Modifies `self` by adding the corresponding parts from `rhs`.
     fn add_assign(&mut self, rhs: Self) {
@@ -67,7 +77,7 @@ where
 /// the real and imaginary parts negated.
 impl<T> Neg for Complex<T>
 where
-    T: Neg<Output = T>,
+    T: Signed,
 {
     type Output = Complex<T>;
 
@@ -80,6 +90,360 @@ where
     }
 }
 
+/// Implements subtraction for Complex numbers via the Sub trait. Like addition, it works
+/// component-wise.
+impl<T> Sub for Complex<T>
+where
+    T: Num,
+{
+    type Output = Complex<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Complex {
+            re: self.re - rhs.re,
+            im: self.im - rhs.im,
+        }
+    }
+}
+
+/// Implements multiplication for Complex numbers via the Mul trait.
+///
+/// (a + bi)(c + di) = (ac - bd) + (ad + bc)i, so T must support multiplication, addition and
+/// subtraction. Num also provides Clone, which is needed because each component is used twice.
+impl<T> Mul for Complex<T>
+where
+    T: Num,
+{
+    type Output = Complex<T>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Complex {
+            re: self.re.clone() * rhs.re.clone() - self.im.clone() * rhs.im.clone(),
+            im: self.re * rhs.im + self.im * rhs.re,
+        }
+    }
+}
+
+/// The component types Complex can divide with, and the formula each one uses.
+///
+/// The default multiplies top and bottom of (a + bi) / (c + di) by the conjugate (c - di), giving
+/// ((ac + bd) + (bc - ad)i) / (c² + d²). For integer types each component is truncated, just like
+/// integer division. Squaring c and d would overflow for floats around 1e154, so floats use
+/// Smith's algorithm instead.
+pub(crate) trait ComplexDiv: Num {
+    /// Divides a + bi by c + di, returning the real and imaginary parts of the quotient.
+    fn complex_div(a: Self, b: Self, c: Self, d: Self) -> (Self, Self) {
+        let denom = c.clone() * c.clone() + d.clone() * d.clone();
+        (
+            (a.clone() * c.clone() + b.clone() * d.clone()) / denom.clone(),
+            (b * c - a * d) / denom,
+        )
+    }
+}
+
+macro_rules! conjugate_div {
+    ($($t:ty),*) => {$(
+        impl ComplexDiv for $t {}
+    )*};
+}
+
+conjugate_div!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+impl<T: ComplexDiv> ComplexDiv for Complex<T> {}
+
+/// Smith's algorithm: divides through by the larger of |c| and |d| first, so the intermediate
+/// values stay close in magnitude to the operands and the result.
+impl<T: Float> ComplexDiv for T {
+    fn complex_div(a: T, b: T, c: T, d: T) -> (T, T) {
+        if c.abs() >= d.abs() {
+            let r = d / c;
+            let denom = c + d * r;
+            ((a + b * r) / denom, (b - a * r) / denom)
+        } else {
+            let r = c / d;
+            let denom = c * r + d;
+            ((a * r + b) / denom, (b * r - a) / denom)
+        }
+    }
+}
+
+/// Implements division for Complex numbers via the Div trait, with the formula ComplexDiv picks
+/// for T.
+impl<T> Div for Complex<T>
+where
+    T: ComplexDiv,
+{
+    type Output = Complex<T>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let (re, im) = T::complex_div(self.re, self.im, rhs.re, rhs.im);
+        Complex { re, im }
+    }
+}
+
+/// Implements the subtraction assignment operator (`-=`) via the SubAssign trait.
+impl<T> SubAssign for Complex<T>
+where
+    T: Num,
+{
+    fn sub_assign(&mut self, rhs: Self) {
+        self.re -= rhs.re;
+        self.im -= rhs.im;
+    }
+}
+
+/// Implements the multiplication assignment operator (`*=`) via the MulAssign trait.
+///
+/// Unlike `+=`, the new components depend on both old components, so the product is computed with
+/// the Mul implementation and then written back.
+impl<T> MulAssign for Complex<T>
+where
+    T: Num,
+{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = self.clone() * rhs;
+    }
+}
+
+/// Implements the division assignment operator (`/=`) via the DivAssign trait.
+impl<T> DivAssign for Complex<T>
+where
+    T: ComplexDiv,
+{
+    fn div_assign(&mut self, rhs: Self) {
+        *self = self.clone() / rhs;
+    }
+}
+
+/// Generates the reference variants of a binary operator (`&a op &b`, `a op &b`, `&a op b`) and
+/// the reference variant of its assignment operator (`a op= &b`). They clone the borrowed operands
+/// and forward to the owned implementation, so they share its bounds plus T: Clone.
+macro_rules! forward_ref_ops {
+    ($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident) => {
+        impl<'a, T> $Op<&'a Complex<T>> for &'a Complex<T>
+        where
+            T: Clone,
+            Complex<T>: $Op<Output = Complex<T>>,
+        {
+            type Output = Complex<T>;
+
+            fn $op(self, rhs: &'a Complex<T>) -> Complex<T> {
+                self.clone().$op(rhs.clone())
+            }
+        }
+
+        impl<'a, T> $Op<&'a Complex<T>> for Complex<T>
+        where
+            T: Clone,
+            Complex<T>: $Op<Output = Complex<T>>,
+        {
+            type Output = Complex<T>;
+
+            fn $op(self, rhs: &'a Complex<T>) -> Complex<T> {
+                self.$op(rhs.clone())
+            }
+        }
+
+        impl<'a, T> $Op<Complex<T>> for &'a Complex<T>
+        where
+            T: Clone,
+            Complex<T>: $Op<Output = Complex<T>>,
+        {
+            type Output = Complex<T>;
+
+            fn $op(self, rhs: Complex<T>) -> Complex<T> {
+                self.clone().$op(rhs)
+            }
+        }
+
+        impl<'a, T> $OpAssign<&'a Complex<T>> for Complex<T>
+        where
+            T: Clone,
+            Complex<T>: $OpAssign,
+        {
+            fn $op_assign(&mut self, rhs: &'a Complex<T>) {
+                self.$op_assign(rhs.clone());
+            }
+        }
+    };
+}
+
+forward_ref_ops!(Add, add, AddAssign, add_assign);
+forward_ref_ops!(Sub, sub, SubAssign, sub_assign);
+forward_ref_ops!(Mul, mul, MulAssign, mul_assign);
+forward_ref_ops!(Div, div, DivAssign, div_assign);
+
+/// Implements negation for a borrowed Complex number, leaving the original untouched.
+impl<T> Neg for &Complex<T>
+where
+    T: Signed,
+{
+    type Output = Complex<T>;
+
+    fn neg(self) -> Complex<T> {
+        -self.clone()
+    }
+}
+
+/// Mixed scalar operations treat a scalar `s` as the complex number `s + 0i`.
+///
+/// Adding or subtracting a scalar only touches the real part, while multiplying or dividing scales
+/// both parts. Because T is generic, only `Complex<T> op T` can be written here; `T op Complex<T>`
+/// is implemented for the primitive types further below.
+impl<T> Add<T> for Complex<T>
+where
+    T: Num,
+{
+    type Output = Complex<T>;
+
+    fn add(self, rhs: T) -> Complex<T> {
+        Complex {
+            re: self.re + rhs,
+            im: self.im,
+        }
+    }
+}
+
+impl<T> Sub<T> for Complex<T>
+where
+    T: Num,
+{
+    type Output = Complex<T>;
+
+    fn sub(self, rhs: T) -> Complex<T> {
+        Complex {
+            re: self.re - rhs,
+            im: self.im,
+        }
+    }
+}
+
+impl<T> Mul<T> for Complex<T>
+where
+    T: Num,
+{
+    type Output = Complex<T>;
+
+    fn mul(self, rhs: T) -> Complex<T> {
+        Complex {
+            re: self.re * rhs.clone(),
+            im: self.im * rhs,
+        }
+    }
+}
+
+impl<T> Div<T> for Complex<T>
+where
+    T: Num,
+{
+    type Output = Complex<T>;
+
+    fn div(self, rhs: T) -> Complex<T> {
+        Complex {
+            re: self.re / rhs.clone(),
+            im: self.im / rhs,
+        }
+    }
+}
+
+impl<T> AddAssign<T> for Complex<T>
+where
+    T: Num,
+{
+    fn add_assign(&mut self, rhs: T) {
+        self.re += rhs;
+    }
+}
+
+impl<T> SubAssign<T> for Complex<T>
+where
+    T: Num,
+{
+    fn sub_assign(&mut self, rhs: T) {
+        self.re -= rhs;
+    }
+}
+
+impl<T> MulAssign<T> for Complex<T>
+where
+    T: Num,
+{
+    fn mul_assign(&mut self, rhs: T) {
+        self.re *= rhs.clone();
+        self.im *= rhs;
+    }
+}
+
+impl<T> DivAssign<T> for Complex<T>
+where
+    T: Num,
+{
+    fn div_assign(&mut self, rhs: T) {
+        self.re /= rhs.clone();
+        self.im /= rhs;
+    }
+}
+
+/// Implements `scalar op Complex` for the primitive numeric types. The orphan rule forbids a
+/// blanket `impl<T> Mul<Complex<T>> for T`, so each scalar type gets its own impls. Only
+/// subtraction differs between the two lists: unsigned types have no negation, so `s - z` computes
+/// `0 - z.im` instead, which overflows exactly when unsigned subtraction does.
+macro_rules! scalar_lhs_ops {
+    (@common $t:ty) => {
+        impl Add<Complex<$t>> for $t {
+            type Output = Complex<$t>;
+
+            fn add(self, rhs: Complex<$t>) -> Complex<$t> {
+                Complex::new(self + rhs.re, rhs.im)
+            }
+        }
+
+        impl Mul<Complex<$t>> for $t {
+            type Output = Complex<$t>;
+
+            fn mul(self, rhs: Complex<$t>) -> Complex<$t> {
+                Complex::new(self * rhs.re, self * rhs.im)
+            }
+        }
+
+        impl Div<Complex<$t>> for $t {
+            type Output = Complex<$t>;
+
+            fn div(self, rhs: Complex<$t>) -> Complex<$t> {
+                Complex::new(self, 0 as $t) / rhs
+            }
+        }
+    };
+    ($($t:ty),*; unsigned $($u:ty),*) => {
+        $(
+            scalar_lhs_ops!(@common $t);
+
+            impl Sub<Complex<$t>> for $t {
+                type Output = Complex<$t>;
+
+                fn sub(self, rhs: Complex<$t>) -> Complex<$t> {
+                    Complex::new(self - rhs.re, -rhs.im)
+                }
+            }
+        )*
+        $(
+            scalar_lhs_ops!(@common $u);
+
+            impl Sub<Complex<$u>> for $u {
+                type Output = Complex<$u>;
+
+                fn sub(self, rhs: Complex<$u>) -> Complex<$u> {
+                    Complex::new(self - rhs.re, 0 - rhs.im)
+                }
+            }
+        )*
+    };
+}
+
+scalar_lhs_ops!(i8, i16, i32, i64, i128, isize, f32, f64; unsigned u8, u16, u32, u64, u128, usize);
+
 // Partial Equality Explanation:
 // The PartialEq trait is used to compare instances for equality. For the Complex struct, equality is based on
 // the condition that both the real part and imaginary part of two Complex numbers are equal.
@@ -130,4 +494,45 @@ pub fn operator_overloading_demo() {
     let j = Complex::new(1, 2);
     println!("h: {:?} , i: {:?} => h == i: {}", h, i, h == i);
     println!("h: {:?} , j: {:?} => h == j: {}", h, j, h == j);
+
+    // The remaining arithmetic operators. Using references keeps `p` and `q` usable afterwards.
+    let p = Complex::new(3.0, 4.0);
+    let q = Complex::new(1.0, -2.0);
+    println!("p - q: {:?}", &p - &q);
+    println!("p * q: {:?}", &p * &q);
+    println!("p / q: {:?}", &p / &q);
+    assert_eq!(&(&p / &q) * &q, p);
+    assert_eq!(-&p + &p, Complex::new(0.0, 0.0));
+
+    let mut r = Complex::new(2, 1);
+    r -= Complex::new(1, 1);
+    r *= &Complex::new(0, 1);
+    r /= Complex::new(0, 1);
+    println!("After r -= 1+1i, r *= i, r /= i, r: {:?}", r);
+
+    // Mixed scalar operations on either side.
+    let s = Complex::new(1.5, -0.5);
+    println!("s * 2: {:?}", s.clone() * 2.0);
+    println!("2 * s: {:?}", 2.0 * s.clone());
+    println!("s + 1: {:?}", s + 1.0);
+    println!("1 / i: {:?}", 1.0 / Complex::new(0.0, 1.0));
+    let mut t = Complex::new(4, 6);
+    t /= 2;
+    println!("After t /= 2, t: {:?}", t);
+    t += 1;
+    t -= 3;
+    assert_eq!(t, Complex::new(0, 3));
+    assert_eq!(2u32 * Complex::new(1u32, 2), Complex::new(2, 4));
+    assert_eq!(5u8 - Complex::new(2u8, 0), Complex::new(3, 0));
+    assert_eq!(6usize / Complex::new(3usize, 0), Complex::new(2, 0));
+
+    // Smith's algorithm keeps float division finite where c² + d² would overflow or underflow.
+    let huge = Complex::new(1e300, 1e300);
+    assert_eq!(huge.clone() / huge, Complex::new(1.0, 0.0));
+    let tiny = Complex::new(1e-300, -1e-300);
+    assert_eq!(Complex::new(2e-300, -2e-300) / tiny, Complex::new(2.0, 0.0));
+    assert_eq!(
+        Complex::new(1e300, 0.0) / Complex::new(0.0, 1e300),
+        Complex::new(0.0, -1.0)
+    );
 }
@@ -0,0 +1,231 @@
+/*
+    Finding the roots of polynomials with the Durand-Kerner method.
+
+    By the fundamental theorem of algebra, a polynomial of degree n with complex coefficients has
+    exactly n complex roots (counted with multiplicity), even when all coefficients are real. The
+    Durand-Kerner (Weierstrass) method finds all of them at once. Starting from n distinct guesses
+    z_1..z_n, it repeatedly refines each one as
+
+        z_i ← z_i - p(z_i) / Π_{j≠i} (z_i - z_j)
+
+    for a monic p. Near the answer the guesses converge quadratically for simple roots; repeated
+    roots converge only linearly, and rounding limits a root of multiplicity m to roughly the m-th
+    root of machine precision (about 1e-5 for a triple root). Around such a root the steps never
+    settle below the tolerance, so the iteration also stops once every |p(z_i)| is within the
+    rounding error of evaluating p: from there on, the steps are driven by noise.
+
+    Coefficients are ordered from the constant term up, the same order num::evaluate uses, so
+    [-6, 11, -6, 1] is x³ - 6x² + 11x - 6.
+*/
+
+use std::fmt;
+
+use crate::num::{Zero, evaluate};
+use crate::operator_overloading::Complex;
+
+/// How close successive iterations must get before the roots count as converged.
+const TOLERANCE: f64 = 1e-14;
+/// How many refinement passes to try before giving up.
+const MAX_ITERATIONS: usize = 1000;
+
+/// The errors the root finder can return.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RootsError {
+    /// Every coefficient was zero, so every number is a root.
+    ZeroPolynomial,
+    /// The iteration did not settle within the iteration limit.
+    NoConvergence { iterations: usize },
+}
+
+impl fmt::Display for RootsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RootsError::ZeroPolynomial => {
+                write!(f, "the zero polynomial has infinitely many roots")
+            }
+            RootsError::NoConvergence { iterations } => {
+                write!(
+                    f,
+                    "the roots did not converge after {} iterations",
+                    iterations
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RootsError {}
+
+/// Finds all complex roots of a polynomial with the Durand-Kerner method.
+///
+/// # Arguments
+///
+/// * `coefficients` - The coefficients from the constant term up. Trailing zeros (zero leading
+///   coefficients) are ignored, so the degree is that of the highest non-zero coefficient.
+pub(crate) fn roots(coefficients: &[Complex<f64>]) -> Result<Vec<Complex<f64>>, RootsError> {
+    let degree = coefficients
+        .iter()
+        .rposition(|c| !c.is_zero())
+        .ok_or(RootsError::ZeroPolynomial)?;
+    if degree == 0 {
+        return Ok(Vec::new());
+    }
+
+    // Divide through by the leading coefficient so the polynomial is monic.
+    let leading = &coefficients[degree];
+    let monic: Vec<Complex<f64>> = coefficients[..=degree]
+        .iter()
+        .map(|c| c / leading)
+        .collect();
+
+    // Every root lies within the Cauchy bound 1 + max |a_i|. The initial guesses are powers of
+    // 0.4 + 0.9i, which is neither real nor a root of unity, so the guesses are distinct and do
+    // not share a symmetry with real polynomials.
+    let bound = 1.0 + monic[..degree].iter().map(|c| c.abs()).fold(0.0, f64::max);
+    let seed = Complex::new(0.4, 0.9);
+    let mut guesses: Vec<Complex<f64>> = (0..degree as i32)
+        .map(|k| seed.powf(k as f64) * bound)
+        .collect();
+
+    // Horner's rule evaluates p(z) with an error of at most about 2nε Σ |a_k| |z|^k; the extra
+    // factor of 2 covers the complex multiplications.
+    let magnitudes: Vec<f64> = monic.iter().map(|c| c.abs()).collect();
+    let rounding_error =
+        |z: &Complex<f64>| 4.0 * degree as f64 * f64::EPSILON * evaluate(&magnitudes, &z.abs());
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut largest_step: f64 = 0.0;
+        let mut all_within_rounding = true;
+        for i in 0..degree {
+            let denominator: Complex<f64> = (0..degree)
+                .filter(|&j| j != i)
+                .map(|j| &guesses[i] - &guesses[j])
+                .product();
+            let value = evaluate(&monic, &guesses[i]);
+            all_within_rounding &= value.abs() <= rounding_error(&guesses[i]);
+            let step = value / denominator;
+            largest_step = largest_step.max(step.abs());
+            guesses[i] -= step;
+        }
+        if largest_step <= TOLERANCE * bound || all_within_rounding {
+            return Ok(guesses);
+        }
+    }
+    Err(RootsError::NoConvergence {
+        iterations: MAX_ITERATIONS,
+    })
+}
+
+/// Builds the coefficients of the monic polynomial with the given roots, Π (x - r).
+fn from_roots(roots: &[Complex<f64>]) -> Vec<Complex<f64>> {
+    let mut coefficients = vec![Complex::new(1.0, 0.0)];
+    for r in roots {
+        // Multiplying by (x - r) shifts every coefficient up a degree and subtracts r times it.
+        let mut next = vec![Complex::zero(); coefficients.len() + 1];
+        for (k, c) in coefficients.iter().enumerate() {
+            next[k + 1] += c.clone();
+            next[k] -= r * c;
+        }
+        coefficients = next;
+    }
+    coefficients
+}
+
+/// Returns whether two sets of roots match within `eps`, in any order.
+fn same_roots(found: &[Complex<f64>], expected: &[Complex<f64>], eps: f64) -> bool {
+    let mut remaining = expected.to_vec();
+    found.len() == expected.len()
+        && found.iter().all(
+            |z| match remaining.iter().position(|r| (z - r).abs() < eps) {
+                Some(i) => {
+                    remaining.swap_remove(i);
+                    true
+                }
+                None => false,
+            },
+        )
+}
+
+/// Demonstrates the root finder on polynomials with known roots.
+pub fn polynomial_demo() {
+    println!("Polynomial roots:");
+    let real = |coefficients: &[f64]| -> Vec<Complex<f64>> {
+        coefficients.iter().map(|&c| Complex::new(c, 0.0)).collect()
+    };
+
+    // x² + 1 has the roots ±i even though its coefficients are real.
+    let found = roots(&real(&[1.0, 0.0, 1.0])).unwrap();
+    println!(
+        "Roots of x² + 1: {:?}",
+        found
+            .iter()
+            .map(|z| format!("{:.6}", z))
+            .collect::<Vec<_>>()
+    );
+    assert!(same_roots(&found, &[Complex::i(), -Complex::i()], 1e-12));
+
+    // x³ - 6x² + 11x - 6 = (x - 1)(x - 2)(x - 3).
+    let found = roots(&real(&[-6.0, 11.0, -6.0, 1.0])).unwrap();
+    println!(
+        "Roots of x³ - 6x² + 11x - 6: {:?}",
+        found
+            .iter()
+            .map(|z| format!("{:.6}", z))
+            .collect::<Vec<_>>()
+    );
+    assert!(same_roots(&found, &real(&[1.0, 2.0, 3.0]), 1e-12));
+
+    // The leading coefficient does not matter: 2x² - 8 has roots ±2. Zero leading terms are
+    // dropped.
+    let found = roots(&real(&[-8.0, 0.0, 2.0, 0.0])).unwrap();
+    assert!(same_roots(&found, &real(&[2.0, -2.0]), 1e-12));
+
+    // z⁸ - 1 has the eighth roots of unity as its roots.
+    let mut unity = real(&[-1.0]);
+    unity.extend(real(&[0.0; 7]));
+    unity.push(Complex::new(1.0, 0.0));
+    let expected: Vec<Complex<f64>> = (0..8)
+        .map(|k| Complex::from_polar(1.0, std::f64::consts::PI * k as f64 / 4.0))
+        .collect();
+    assert!(same_roots(&roots(&unity).unwrap(), &expected, 1e-12));
+
+    // Complex coefficients, built from known complex roots.
+    let expected = vec![
+        Complex::new(1.0, 1.0),
+        Complex::new(2.0, -3.0),
+        Complex::new(-0.5, 0.25),
+        Complex::new(0.0, 4.0),
+        Complex::new(-3.0, -1.0),
+    ];
+    let coefficients = from_roots(&expected);
+    let found = roots(&coefficients).unwrap();
+    assert!(same_roots(&found, &expected, 1e-10));
+    for z in &found {
+        assert!(evaluate(&coefficients, z).abs() < 1e-10);
+    }
+
+    // A double root converges more slowly and less precisely, but is still found.
+    let found = roots(&real(&[1.0, -2.0, 1.0])).unwrap();
+    assert!(same_roots(&found, &real(&[1.0, 1.0]), 1e-6));
+
+    // Higher multiplicities stop once p(z) is down to rounding noise, about ε^(1/m) from the
+    // root.
+    let found = roots(&from_roots(&real(&[1.0; 3]))).unwrap();
+    assert!(same_roots(&found, &real(&[1.0; 3]), 1e-4));
+    let found = roots(&from_roots(&real(&[1.0; 4]))).unwrap();
+    assert!(same_roots(&found, &real(&[1.0; 4]), 1e-3));
+    println!(
+        "Roots of (x - 1)⁴: {:?}",
+        found
+            .iter()
+            .map(|z| format!("{:.6}", z))
+            .collect::<Vec<_>>()
+    );
+    let mixed = from_roots(&real(&[2.0, 2.0, 2.0, -1.0, 0.5]));
+    let found = roots(&mixed).unwrap();
+    assert!(same_roots(&found, &real(&[2.0, 2.0, 2.0, -1.0, 0.5]), 1e-4));
+
+    assert_eq!(roots(&real(&[5.0])), Ok(Vec::new()));
+    assert_eq!(roots(&real(&[0.0, 0.0])), Err(RootsError::ZeroPolynomial));
+    println!("Roots of 0: {}", roots(&[]).unwrap_err());
+}
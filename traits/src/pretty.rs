@@ -0,0 +1,439 @@
+/*
+    A document-based pretty-printer for Printable values.
+
+    Instead of writing text directly, a Printable value builds a Doc: a tree of text pieces,
+    possible line breaks, indentation and groups. The renderer then chooses the layout. For each
+    group it first tries to print the whole group on the rest of the current line; only if it does
+    not fit within the width does the group break, turning each of its own line breaks into a
+    newline. Nested groups decide separately, so a long list breaks into one element per line while
+    its short elements stay on one line each:
+
+        Team {
+            name: "Platform",
+            members: [
+                Employee { name: "Ana", age: 34, skills: ["sql"], manager: None },
+                ...
+
+    This is the design of Wadler's "A prettier printer", and of tools like rustfmt and Prettier.
+
+    Printable impls for the standard collections live here; structs get theirs from
+    `#[derive(Printable)]` in the printable_derive crate, whose generated code refers to the
+    trait and Doc through this module.
+*/
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use printable_derive::Printable;
+
+pub(crate) use crate::static_and_dyn_dispatch::Printable;
+
+/// The width Printable::format fits its output to.
+pub(crate) const DEFAULT_WIDTH: usize = 80;
+/// How far the contents of a broken group are indented.
+const INDENT: usize = 4;
+
+/// A document describing the possible layouts of some text.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Doc {
+    /// Text printed as is. It must not contain newlines, or the width calculations go wrong.
+    Text(Cow<'static, str>),
+    /// A space if the enclosing group fits on one line, and a newline otherwise.
+    Line,
+    /// Nothing if the enclosing group fits on one line, and a newline otherwise.
+    SoftLine,
+    /// The first document if the enclosing group breaks, the second if it does not.
+    IfBreak(Box<Doc>, Box<Doc>),
+    /// Indents the lines started inside the document by this many more columns.
+    Nest(usize, Box<Doc>),
+    /// A unit that is laid out flat or broken as a whole.
+    Group(Box<Doc>),
+    Concat(Vec<Doc>),
+}
+
+/// Whether the enclosing group is printed on one line or broken.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+impl Doc {
+    pub(crate) fn text(text: impl Into<Cow<'static, str>>) -> Doc {
+        Doc::Text(text.into())
+    }
+
+    pub(crate) fn nest(indent: usize, doc: Doc) -> Doc {
+        Doc::Nest(indent, Box::new(doc))
+    }
+
+    pub(crate) fn group(doc: Doc) -> Doc {
+        Doc::Group(Box::new(doc))
+    }
+
+    pub(crate) fn if_break(broken: Doc, flat: Doc) -> Doc {
+        Doc::IfBreak(Box::new(broken), Box::new(flat))
+    }
+
+    /// Puts `separator` between consecutive documents.
+    pub(crate) fn join(docs: Vec<Doc>, separator: Doc) -> Doc {
+        let mut parts = Vec::with_capacity(docs.len() * 2);
+        for (i, doc) in docs.into_iter().enumerate() {
+            if i > 0 {
+                parts.push(separator.clone());
+            }
+            parts.push(doc);
+        }
+        Doc::Concat(parts)
+    }
+
+    /// Lays out items between brackets: all on one line as `[a, b]`, or one per line with a
+    /// trailing comma when they do not fit. `padding` is the line break just inside the
+    /// brackets: SoftLine for `[a]`, Line for `{ a }`.
+    fn bracketed(open: Doc, items: Vec<Doc>, close: &'static str, padding: Doc) -> Doc {
+        if items.is_empty() {
+            return Doc::Concat(vec![open, Doc::text(close)]);
+        }
+        Doc::group(Doc::Concat(vec![
+            open,
+            Doc::nest(
+                INDENT,
+                Doc::Concat(vec![
+                    padding.clone(),
+                    Doc::join(items, Doc::Concat(vec![Doc::text(","), Doc::Line])),
+                    Doc::if_break(Doc::text(","), Doc::text("")),
+                ]),
+            ),
+            padding,
+            Doc::text(close),
+        ]))
+    }
+
+    /// Lays out a sequence, such as a Vec, as `[a, b, c]`.
+    pub(crate) fn list(items: Vec<Doc>) -> Doc {
+        Doc::bracketed(Doc::text("["), items, "]", Doc::SoftLine)
+    }
+
+    /// Lays out a tuple as `(a, b)`. A single element keeps its comma, `(a,)`, as in Rust.
+    pub(crate) fn tuple(items: Vec<Doc>) -> Doc {
+        match items.len() {
+            1 => Doc::Concat(vec![
+                Doc::text("("),
+                items.into_iter().next().unwrap(),
+                Doc::text(",)"),
+            ]),
+            _ => Doc::bracketed(Doc::text("("), items, ")", Doc::SoftLine),
+        }
+    }
+
+    /// Lays out key-value pairs as `{k: v, ...}`.
+    pub(crate) fn map(entries: Vec<(Doc, Doc)>) -> Doc {
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| Doc::Concat(vec![key, Doc::text(": "), value]))
+            .collect();
+        Doc::bracketed(Doc::text("{"), entries, "}", Doc::SoftLine)
+    }
+
+    /// Lays out a struct with named fields as `Name { field: value, ... }`.
+    pub(crate) fn record(name: &'static str, fields: Vec<(&'static str, Doc)>) -> Doc {
+        if fields.is_empty() {
+            return Doc::text(name);
+        }
+        let fields = fields
+            .into_iter()
+            .map(|(field, value)| Doc::Concat(vec![Doc::text(field), Doc::text(": "), value]))
+            .collect();
+        Doc::bracketed(Doc::text(format!("{} {{", name)), fields, "}", Doc::Line)
+    }
+
+    /// Lays out a tuple struct as `Name(a, b)`.
+    pub(crate) fn tuple_struct(name: &'static str, fields: Vec<Doc>) -> Doc {
+        Doc::bracketed(Doc::text(format!("{}(", name)), fields, ")", Doc::SoftLine)
+    }
+
+    /// Lays the document out within `width` columns where possible. Text longer than the width
+    /// still has to go somewhere, so lines can overflow when no break point is left.
+    pub(crate) fn render(&self, width: usize) -> String {
+        let mut out = String::new();
+        let mut column = 0;
+        // Documents still to print, last one first, with their indentation and mode.
+        let mut stack: Vec<(usize, Mode, &Doc)> = vec![(0, Mode::Break, self)];
+        while let Some((indent, mode, doc)) = stack.pop() {
+            match doc {
+                Doc::Text(text) => {
+                    out.push_str(text);
+                    column += text.chars().count();
+                }
+                Doc::Line | Doc::SoftLine if mode == Mode::Break => {
+                    // The finished line keeps no trailing spaces.
+                    out.truncate(out.trim_end_matches(' ').len());
+                    out.push('\n');
+                    out.push_str(&" ".repeat(indent));
+                    column = indent;
+                }
+                Doc::Line => {
+                    out.push(' ');
+                    column += 1;
+                }
+                Doc::SoftLine => {}
+                Doc::IfBreak(broken, flat) => stack.push((
+                    indent,
+                    mode,
+                    match mode {
+                        Mode::Break => broken,
+                        Mode::Flat => flat,
+                    },
+                )),
+                Doc::Nest(extra, doc) => stack.push((indent + extra, mode, doc)),
+                Doc::Group(doc) => {
+                    let remaining = width.min(isize::MAX as usize) as isize - column as isize;
+                    let fits = mode == Mode::Flat || fits(remaining, doc, &stack);
+                    stack.push((indent, if fits { Mode::Flat } else { Mode::Break }, doc));
+                }
+                Doc::Concat(docs) => {
+                    stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Returns whether `group`, printed flat, and whatever follows it up to the next line break fit
+/// in `remaining` columns. The text after the group counts because a group ending in `]` that
+/// is followed by `,` needs room for both.
+fn fits(mut remaining: isize, group: &Doc, rest: &[(usize, Mode, &Doc)]) -> bool {
+    let mut pending: Vec<(Mode, &Doc)> = vec![(Mode::Flat, group)];
+    let mut rest = rest.iter().rev();
+    while remaining >= 0 {
+        let (mode, doc) = match pending.pop() {
+            Some(next) => next,
+            None => match rest.next() {
+                Some(&(_, mode, doc)) => (mode, doc),
+                None => return true,
+            },
+        };
+        match doc {
+            Doc::Text(text) => remaining -= text.chars().count() as isize,
+            Doc::Line | Doc::SoftLine if mode == Mode::Break => return true,
+            Doc::Line => remaining -= 1,
+            Doc::SoftLine => {}
+            Doc::IfBreak(broken, flat) => pending.push((
+                mode,
+                match mode {
+                    Mode::Break => broken,
+                    Mode::Flat => flat,
+                },
+            )),
+            // Later groups get their own decision; measuring them flat is the optimistic guess
+            // that keeps this group from breaking because of them.
+            Doc::Nest(_, doc) => pending.push((mode, doc)),
+            Doc::Group(doc) => pending.push((Mode::Flat, doc)),
+            Doc::Concat(docs) => pending.extend(docs.iter().rev().map(|doc| (mode, doc))),
+        }
+    }
+    false
+}
+
+/// Integers and booleans print as their Display form.
+macro_rules! impl_printable_display {
+    ($($t:ty),*) => {
+        $(
+            impl Printable for $t {
+                fn to_doc(&self) -> Doc {
+                    Doc::text(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_printable_display!(i64, u32, u64, usize, bool);
+
+/// Floats use Debug, which keeps the ".0" of whole numbers so they do not read as integers.
+impl Printable for f64 {
+    fn to_doc(&self) -> Doc {
+        Doc::text(format!("{:?}", self))
+    }
+}
+
+impl Printable for str {
+    fn to_doc(&self) -> Doc {
+        Doc::text(format!("{:?}", self))
+    }
+}
+
+impl<T: Printable + ?Sized> Printable for &T {
+    fn to_doc(&self) -> Doc {
+        (**self).to_doc()
+    }
+}
+
+impl<T: Printable> Printable for Vec<T> {
+    fn to_doc(&self) -> Doc {
+        Doc::list(self.iter().map(Printable::to_doc).collect())
+    }
+}
+
+impl<T: Printable> Printable for Option<T> {
+    fn to_doc(&self) -> Doc {
+        match self {
+            Some(value) => Doc::tuple_struct("Some", vec![value.to_doc()]),
+            None => Doc::text("None"),
+        }
+    }
+}
+
+/// The entries are sorted by their printed keys, so the output does not depend on the map's
+/// iteration order, which changes from run to run.
+impl<K: Printable, V: Printable, S> Printable for HashMap<K, V, S> {
+    fn to_doc(&self) -> Doc {
+        let mut entries: Vec<(String, &V)> = self
+            .iter()
+            .map(|(key, value)| (key.to_doc().render(usize::MAX), value))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Doc::map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (Doc::text(key), value.to_doc()))
+                .collect(),
+        )
+    }
+}
+
+macro_rules! impl_printable_tuple {
+    ($(($($name:ident $index:tt),+)),*) => {
+        $(
+            impl<$($name: Printable),+> Printable for ($($name,)+) {
+                fn to_doc(&self) -> Doc {
+                    Doc::tuple(vec![$(self.$index.to_doc()),+])
+                }
+            }
+        )*
+    };
+}
+
+impl_printable_tuple!((A 0), (A 0, B 1), (A 0, B 1, C 2), (A 0, B 1, C 2, D 3));
+
+#[derive(Printable)]
+struct Employee {
+    name: String,
+    age: u32,
+    skills: Vec<&'static str>,
+    manager: Option<String>,
+}
+
+#[derive(Printable)]
+struct Team {
+    name: String,
+    members: Vec<Employee>,
+    budget: HashMap<String, i32>,
+}
+
+#[derive(Printable)]
+struct Meters(f64);
+
+#[derive(Printable)]
+struct Labeled<T> {
+    label: &'static str,
+    value: T,
+}
+
+#[derive(Printable)]
+struct Empty;
+
+/// Demonstrates the pretty-printer on collections and derived structs at several widths.
+pub fn pretty_demo() {
+    println!("Pretty printing:");
+    let employee = |name: &str, age, skills: Vec<&'static str>, manager: Option<&str>| Employee {
+        name: name.to_string(),
+        age,
+        skills,
+        manager: manager.map(str::to_string),
+    };
+    let team = Team {
+        name: "Platform".to_string(),
+        members: vec![
+            employee("Ana", 34, vec!["sql"], None),
+            employee(
+                "Kenji",
+                28,
+                vec!["go", "kubernetes", "terraform", "rust"],
+                Some("Ana"),
+            ),
+        ],
+        budget: HashMap::from([("tools".to_string(), 1200), ("travel".to_string(), 800)]),
+    };
+    println!("{}", team.format());
+    assert_eq!(
+        team.format(),
+        r#"Team {
+    name: "Platform",
+    members: [
+        Employee { name: "Ana", age: 34, skills: ["sql"], manager: None },
+        Employee {
+            name: "Kenji",
+            age: 28,
+            skills: ["go", "kubernetes", "terraform", "rust"],
+            manager: Some("Ana"),
+        },
+    ],
+    budget: {"tools": 1200, "travel": 800},
+}"#
+    );
+
+    // The same document adapts to the width: everything on one line when there is room, and
+    // more breaks as the width shrinks.
+    let ana = &team.members[0];
+    let flat = r#"Employee { name: "Ana", age: 34, skills: ["sql"], manager: None }"#;
+    assert_eq!(ana.to_doc().render(flat.len()), flat);
+    assert_eq!(
+        ana.to_doc().render(flat.len() - 1),
+        "Employee {\n    name: \"Ana\",\n    age: 34,\n    skills: [\"sql\"],\n    manager: None,\n}"
+    );
+    let skills = &team.members[1].skills;
+    assert_eq!(
+        skills.to_doc().render(12),
+        "[\n    \"go\",\n    \"kubernetes\",\n    \"terraform\",\n    \"rust\",\n]"
+    );
+
+    // Collections, options and tuples nest freely.
+    let nested = vec![(1, Some("one")), (2, None)];
+    assert_eq!(nested.format(), r#"[(1, Some("one")), (2, None)]"#);
+    assert_eq!(
+        ((true,), (1.5, "x".to_string(), 7u64)).format(),
+        r#"((true,), (1.5, "x", 7))"#
+    );
+    assert_eq!(Vec::<i32>::new().format(), "[]");
+    assert_eq!(HashMap::<i32, i32>::new().format(), "{}");
+    let grid: Vec<Vec<i32>> = (0..3)
+        .map(|row| (0..12).map(|c| row * 12 + c).collect())
+        .collect();
+    println!("{}", grid.to_doc().render(60));
+    assert_eq!(
+        grid.to_doc().render(60),
+        "[\n    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],\n    [12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23],\n    [24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35],\n]"
+    );
+
+    // Derived impls for tuple, generic and unit structs.
+    assert_eq!(Meters(2.5).format(), "Meters(2.5)");
+    let labeled = Labeled {
+        label: "distance",
+        value: Meters(10.0),
+    };
+    assert_eq!(Empty.format(), "Empty");
+
+    // Printable values still work as trait objects.
+    let values: [&dyn Printable; 3] = [&42, &Meters(1.0), &labeled];
+    let formatted: Vec<String> = values.iter().map(|v| v.format()).collect();
+    assert_eq!(
+        formatted,
+        [
+            "42",
+            "Meters(1.0)",
+            r#"Labeled { label: "distance", value: Meters(10.0) }"#
+        ]
+    );
+}
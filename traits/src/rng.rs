@@ -0,0 +1,51 @@
+/*
+    A small deterministic random number generator.
+
+    The benchmarks, the collision checks and the game all need numbers that look random but are
+    the same on every run, so results can be compared and bugs replayed. Marsaglia's xorshift
+    generator does this in three shifts and XORs per number. It is not suitable for cryptography,
+    and its lowest bits are weaker than its highest, so the helpers below use the high bits.
+*/
+
+/// A xorshift64 generator. The state must never be zero, or every output is zero.
+pub(crate) struct XorShift(u64);
+
+impl XorShift {
+    /// Creates a generator from any seed, replacing the one invalid seed, zero.
+    pub(crate) fn new(seed: u64) -> XorShift {
+        XorShift(if seed == 0 {
+            0x2545_f491_4f6c_dd1d
+        } else {
+            seed
+        })
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Returns a number in [0, 1), from the top 53 bits of the next output.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a number in the inclusive range `low..=high`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low > high`.
+    pub(crate) fn range(&mut self, low: u32, high: u32) -> u32 {
+        assert!(low <= high, "the range {}..={} is empty", low, high);
+        let span = (high - low) as u64 + 1;
+        // Multiplying the high 32 bits by the span maps them evenly onto 0..span.
+        low + (((self.next() >> 32) * span) >> 32) as u32
+    }
+
+    /// Returns true with the given probability.
+    pub(crate) fn chance(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+}
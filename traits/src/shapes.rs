@@ -0,0 +1,850 @@
+/*
+    The Shape trait from why_dyn_dispatch, grown into a small 2D shape library.
+
+    Every shape answers the same geometric questions through Shape: its area, perimeter, axis-aligned
+    bounding box, centroid, and whether it contains a point. These methods only take &self, so Shape
+    stays object safe and mixed collections of &dyn Shape keep working.
+
+    Transforms are a separate trait. Moving, rotating or scaling a shape returns a new shape of the
+    same type, and a method returning Self cannot be called through a trait object, so Transform
+    requires Sized. Only similarity transforms (translation, rotation and uniform scaling, in any
+    combination) are offered: they map every circle to a circle and every square to a square.
+    Scaling x and y by different factors would turn a circle into an ellipse and a rotated square
+    into a parallelogram, so it would not be closed over the types.
+
+    Circle, Square, Rectangle and Ellipse store a center and, where it matters, a rotation angle in
+    radians (counter-clockwise). Triangle and Polygon store their vertices. Polygon areas use the
+    shoelace formula, which works for any simple polygon, convex or not, in either winding order.
+
+    Points on a shape's boundary count as contained, within a small tolerance for rounding.
+*/
+
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+/// How far outside a boundary a point may be and still count as on it.
+pub(crate) const TOLERANCE: f64 = 1e-9;
+
+/// A point, or a vector between two points, in the plane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Point {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+}
+
+impl Point {
+    pub(crate) const ORIGIN: Point = Point { x: 0.0, y: 0.0 };
+
+    pub(crate) fn new(x: f64, y: f64) -> Point {
+        Point { x, y }
+    }
+
+    /// Returns the dot product with `other`.
+    pub(crate) fn dot(self, other: Point) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Returns the z component of the cross product with `other`: positive if `other` is
+    /// counter-clockwise from `self`.
+    pub(crate) fn cross(self, other: Point) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Returns the distance to `other`.
+    pub(crate) fn distance(self, other: Point) -> f64 {
+        (self - other).length()
+    }
+
+    /// Returns the length of the vector from the origin to this point.
+    pub(crate) fn length(self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    /// Rotates the point about the origin by `angle` radians, counter-clockwise.
+    pub(crate) fn rotated(self, angle: f64) -> Point {
+        let (sin, cos) = angle.sin_cos();
+        Point::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// Returns whether both coordinates are within `eps` of `other`'s.
+    pub(crate) fn approx_eq(self, other: Point, eps: f64) -> bool {
+        (self.x - other.x).abs() <= eps && (self.y - other.y).abs() <= eps
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f64> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: f64) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+/// An axis-aligned rectangle, given by its lower-left and upper-right corners.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BoundingBox {
+    pub(crate) min: Point,
+    pub(crate) max: Point,
+}
+
+impl BoundingBox {
+    /// Returns the smallest box containing all the points.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` is empty.
+    pub(crate) fn from_points(points: &[Point]) -> BoundingBox {
+        assert!(
+            !points.is_empty(),
+            "a bounding box needs at least one point"
+        );
+        points.iter().skip(1).fold(
+            BoundingBox {
+                min: points[0],
+                max: points[0],
+            },
+            |b, p| BoundingBox {
+                min: Point::new(b.min.x.min(p.x), b.min.y.min(p.y)),
+                max: Point::new(b.max.x.max(p.x), b.max.y.max(p.y)),
+            },
+        )
+    }
+
+    pub(crate) fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    pub(crate) fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+
+    /// Returns whether the point lies inside the box or on its edge.
+    pub(crate) fn contains(&self, point: Point) -> bool {
+        (self.min.x - TOLERANCE..=self.max.x + TOLERANCE).contains(&point.x)
+            && (self.min.y - TOLERANCE..=self.max.y + TOLERANCE).contains(&point.y)
+    }
+
+    /// Returns whether the boxes overlap or touch.
+    pub(crate) fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min.x <= other.max.x + TOLERANCE
+            && other.min.x <= self.max.x + TOLERANCE
+            && self.min.y <= other.max.y + TOLERANCE
+            && other.min.y <= self.max.y + TOLERANCE
+    }
+
+    /// Returns the smallest box containing both boxes.
+    pub(crate) fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Point::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    /// Returns the box moved by `offset`.
+    pub(crate) fn translated(&self, offset: Point) -> BoundingBox {
+        BoundingBox {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
+    /// Returns whether the boxes are within `eps` of each other in every coordinate.
+    pub(crate) fn approx_eq(&self, other: &BoundingBox, eps: f64) -> bool {
+        self.min.approx_eq(other.min, eps) && self.max.approx_eq(other.max, eps)
+    }
+}
+
+/// The geometric questions every shape can answer.
+pub(crate) trait Shape {
+    /// Calculates and returns the area of the shape.
+    fn area(&self) -> f64;
+
+    /// Calculates and returns the length of the shape's boundary.
+    fn perimeter(&self) -> f64;
+
+    /// Returns the smallest axis-aligned box containing the shape.
+    fn bounding_box(&self) -> BoundingBox;
+
+    /// Returns the center of mass of the shape, assuming uniform density.
+    fn centroid(&self) -> Point;
+
+    /// Returns whether the point lies inside the shape or on its boundary.
+    fn contains(&self, point: Point) -> bool;
+
+    /// Describes the shape's boundary, for code that needs its exact geometry.
+    fn outline(&self) -> Outline;
+}
+
+/// The boundary of a shape, reduced to the three kinds of curve the library needs.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Outline {
+    Circle {
+        center: Point,
+        radius: f64,
+    },
+    Ellipse {
+        center: Point,
+        radius_x: f64,
+        radius_y: f64,
+        angle: f64,
+    },
+    /// A closed polygon through the vertices, in order.
+    Polygon(Vec<Point>),
+}
+
+/// A similarity transform: p ↦ factor · rotate(p, angle) + offset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Similarity {
+    pub(crate) angle: f64,
+    pub(crate) factor: f64,
+    pub(crate) offset: Point,
+}
+
+impl Similarity {
+    /// Applies the transform to a point.
+    pub(crate) fn apply(&self, point: Point) -> Point {
+        point.rotated(self.angle) * self.factor + self.offset
+    }
+}
+
+/// Transforms returning a new shape of the same type.
+pub(crate) trait Transform: Sized {
+    /// Applies a similarity transform to every point of the shape.
+    fn transform(&self, similarity: &Similarity) -> Self;
+
+    /// Moves the shape by `offset`.
+    fn translate(&self, offset: Point) -> Self {
+        self.transform(&Similarity {
+            angle: 0.0,
+            factor: 1.0,
+            offset,
+        })
+    }
+
+    /// Rotates the shape by `angle` radians counter-clockwise about `center`.
+    fn rotate(&self, angle: f64, center: Point) -> Self {
+        self.transform(&Similarity {
+            angle,
+            factor: 1.0,
+            offset: center - center.rotated(angle),
+        })
+    }
+
+    /// Scales the shape by `factor` towards or away from `center`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is not positive.
+    fn scale(&self, factor: f64, center: Point) -> Self {
+        assert!(factor > 0.0, "the scale factor must be positive");
+        self.transform(&Similarity {
+            angle: 0.0,
+            factor,
+            offset: center - center * factor,
+        })
+    }
+}
+
+/// Returns twice the signed area of a polygon: positive for counter-clockwise vertices.
+fn doubled_signed_area(vertices: &[Point]) -> f64 {
+    edges(vertices).map(|(a, b)| a.cross(b)).sum()
+}
+
+/// Returns each edge of a closed polygon as a pair of its endpoints.
+pub(crate) fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+fn polygon_area(vertices: &[Point]) -> f64 {
+    doubled_signed_area(vertices).abs() / 2.0
+}
+
+fn polygon_perimeter(vertices: &[Point]) -> f64 {
+    edges(vertices).map(|(a, b)| a.distance(b)).sum()
+}
+
+/// The centroid of a polygon's area: each edge contributes the triangle it forms with the origin,
+/// weighted by that triangle's signed area.
+fn polygon_centroid(vertices: &[Point]) -> Point {
+    let six_area = 3.0 * doubled_signed_area(vertices);
+    let sum = edges(vertices).fold(Point::ORIGIN, |sum, (a, b)| sum + (a + b) * a.cross(b));
+    sum * (1.0 / six_area)
+}
+
+/// Returns whether `point` lies on the segment from `a` to `b`.
+fn on_segment(point: Point, a: Point, b: Point) -> bool {
+    let (edge, offset) = (b - a, point - a);
+    let length = edge.length();
+    (edge.cross(offset) / length).abs() <= TOLERANCE
+        && (-TOLERANCE..=length * length + TOLERANCE).contains(&edge.dot(offset))
+}
+
+/// Even-odd test: a ray from the point to the right crosses the boundary an odd number of times
+/// exactly when the point is inside. Points on an edge are checked separately, since the ray test
+/// can go either way for them.
+pub(crate) fn polygon_contains(vertices: &[Point], point: Point) -> bool {
+    let mut inside = false;
+    for (a, b) in edges(vertices) {
+        if on_segment(point, a, b) {
+            return true;
+        }
+        if (a.y > point.y) != (b.y > point.y) {
+            let crossing = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if point.x < crossing {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// The corners of a rectangle, counter-clockwise from the lower left before rotation.
+fn rectangle_corners(center: Point, width: f64, height: f64, angle: f64) -> [Point; 4] {
+    let (w, h) = (width / 2.0, height / 2.0);
+    [(-w, -h), (w, -h), (w, h), (-w, h)].map(|(x, y)| center + Point::new(x, y).rotated(angle))
+}
+
+/// Expresses a point in the frame of a shape centered at `center` and rotated by `angle`.
+pub(crate) fn to_local(point: Point, center: Point, angle: f64) -> Point {
+    (point - center).rotated(-angle)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Circle {
+    pub(crate) center: Point,
+    pub(crate) radius: f64,
+}
+
+impl Circle {
+    pub(crate) fn new(center: Point, radius: f64) -> Circle {
+        Circle { center, radius }
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        self.radius * self.radius * PI
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * PI * self.radius
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let r = Point::new(self.radius, self.radius);
+        BoundingBox {
+            min: self.center - r,
+            max: self.center + r,
+        }
+    }
+
+    fn centroid(&self) -> Point {
+        self.center
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        self.center.distance(point) <= self.radius + TOLERANCE
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Circle {
+            center: self.center,
+            radius: self.radius,
+        }
+    }
+}
+
+impl Transform for Circle {
+    fn transform(&self, similarity: &Similarity) -> Circle {
+        Circle::new(
+            similarity.apply(self.center),
+            self.radius * similarity.factor,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Square {
+    pub(crate) center: Point,
+    pub(crate) side: f64,
+    pub(crate) angle: f64,
+}
+
+impl Square {
+    /// Creates an axis-aligned square.
+    pub(crate) fn new(center: Point, side: f64) -> Square {
+        Square {
+            center,
+            side,
+            angle: 0.0,
+        }
+    }
+
+    pub(crate) fn corners(&self) -> [Point; 4] {
+        rectangle_corners(self.center, self.side, self.side, self.angle)
+    }
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+
+    fn perimeter(&self) -> f64 {
+        4.0 * self.side
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::from_points(&self.corners())
+    }
+
+    fn centroid(&self) -> Point {
+        self.center
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        let local = to_local(point, self.center, self.angle);
+        local.x.abs().max(local.y.abs()) <= self.side / 2.0 + TOLERANCE
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Polygon(self.corners().to_vec())
+    }
+}
+
+impl Transform for Square {
+    fn transform(&self, similarity: &Similarity) -> Square {
+        Square {
+            center: similarity.apply(self.center),
+            side: self.side * similarity.factor,
+            angle: self.angle + similarity.angle,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Rectangle {
+    pub(crate) center: Point,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    pub(crate) angle: f64,
+}
+
+impl Rectangle {
+    /// Creates an axis-aligned rectangle.
+    pub(crate) fn new(center: Point, width: f64, height: f64) -> Rectangle {
+        Rectangle {
+            center,
+            width,
+            height,
+            angle: 0.0,
+        }
+    }
+
+    pub(crate) fn corners(&self) -> [Point; 4] {
+        rectangle_corners(self.center, self.width, self.height, self.angle)
+    }
+}
+
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.width + self.height)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::from_points(&self.corners())
+    }
+
+    fn centroid(&self) -> Point {
+        self.center
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        let local = to_local(point, self.center, self.angle);
+        local.x.abs() <= self.width / 2.0 + TOLERANCE
+            && local.y.abs() <= self.height / 2.0 + TOLERANCE
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Polygon(self.corners().to_vec())
+    }
+}
+
+impl Transform for Rectangle {
+    fn transform(&self, similarity: &Similarity) -> Rectangle {
+        Rectangle {
+            center: similarity.apply(self.center),
+            width: self.width * similarity.factor,
+            height: self.height * similarity.factor,
+            angle: self.angle + similarity.angle,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Ellipse {
+    pub(crate) center: Point,
+    /// The semi-axis along the ellipse's own x axis, before rotation.
+    pub(crate) radius_x: f64,
+    /// The semi-axis along the ellipse's own y axis, before rotation.
+    pub(crate) radius_y: f64,
+    pub(crate) angle: f64,
+}
+
+impl Ellipse {
+    /// Creates an ellipse with its axes along the coordinate axes.
+    pub(crate) fn new(center: Point, radius_x: f64, radius_y: f64) -> Ellipse {
+        Ellipse {
+            center,
+            radius_x,
+            radius_y,
+            angle: 0.0,
+        }
+    }
+}
+
+impl Shape for Ellipse {
+    fn area(&self) -> f64 {
+        PI * self.radius_x * self.radius_y
+    }
+
+    /// The perimeter of an ellipse has no closed form. Ramanujan's second approximation is exact
+    /// for circles and within 0.4% even for a degenerate, flat ellipse.
+    fn perimeter(&self) -> f64 {
+        let (a, b) = (self.radius_x, self.radius_y);
+        let h = ((a - b) / (a + b)).powi(2);
+        PI * (a + b) * (1.0 + 3.0 * h / (10.0 + (4.0 - 3.0 * h).sqrt()))
+    }
+
+    /// The extreme x and y of a rotated ellipse are reached where its tangent is vertical or
+    /// horizontal, which gives the half-widths below.
+    fn bounding_box(&self) -> BoundingBox {
+        let (sin, cos) = self.angle.sin_cos();
+        let (a, b) = (self.radius_x, self.radius_y);
+        let half = Point::new((a * cos).hypot(b * sin), (a * sin).hypot(b * cos));
+        BoundingBox {
+            min: self.center - half,
+            max: self.center + half,
+        }
+    }
+
+    fn centroid(&self) -> Point {
+        self.center
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        let local = to_local(point, self.center, self.angle);
+        (local.x / self.radius_x).powi(2) + (local.y / self.radius_y).powi(2) <= 1.0 + TOLERANCE
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Ellipse {
+            center: self.center,
+            radius_x: self.radius_x,
+            radius_y: self.radius_y,
+            angle: self.angle,
+        }
+    }
+}
+
+impl Transform for Ellipse {
+    fn transform(&self, similarity: &Similarity) -> Ellipse {
+        Ellipse {
+            center: similarity.apply(self.center),
+            radius_x: self.radius_x * similarity.factor,
+            radius_y: self.radius_y * similarity.factor,
+            angle: self.angle + similarity.angle,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Triangle {
+    pub(crate) vertices: [Point; 3],
+}
+
+impl Triangle {
+    pub(crate) fn new(a: Point, b: Point, c: Point) -> Triangle {
+        Triangle {
+            vertices: [a, b, c],
+        }
+    }
+}
+
+impl Shape for Triangle {
+    fn area(&self) -> f64 {
+        polygon_area(&self.vertices)
+    }
+
+    fn perimeter(&self) -> f64 {
+        polygon_perimeter(&self.vertices)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::from_points(&self.vertices)
+    }
+
+    /// A triangle's centroid is simply the average of its vertices.
+    fn centroid(&self) -> Point {
+        let [a, b, c] = self.vertices;
+        (a + b + c) * (1.0 / 3.0)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        polygon_contains(&self.vertices, point)
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Polygon(self.vertices.to_vec())
+    }
+}
+
+impl Transform for Triangle {
+    fn transform(&self, similarity: &Similarity) -> Triangle {
+        Triangle {
+            vertices: self.vertices.map(|p| similarity.apply(p)),
+        }
+    }
+}
+
+/// A simple polygon: its edges join consecutive vertices, and the last vertex to the first, without
+/// crossing each other.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Polygon {
+    pub(crate) vertices: Vec<Point>,
+}
+
+impl Polygon {
+    /// Creates a polygon from its vertices, in either winding order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than three vertices.
+    pub(crate) fn new(vertices: Vec<Point>) -> Polygon {
+        assert!(
+            vertices.len() >= 3,
+            "a polygon needs at least three vertices"
+        );
+        Polygon { vertices }
+    }
+
+    /// Creates a regular polygon with `sides` vertices at distance `radius` from `center`, the first
+    /// one straight to the right of it.
+    pub(crate) fn regular(center: Point, radius: f64, sides: usize) -> Polygon {
+        Polygon::new(
+            (0..sides)
+                .map(|k| {
+                    center + Point::new(radius, 0.0).rotated(2.0 * PI * k as f64 / sides as f64)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Shape for Polygon {
+    /// Uses the shoelace formula: the signed areas of the triangles each edge forms with the origin
+    /// add up to the polygon's area, whatever its shape.
+    fn area(&self) -> f64 {
+        polygon_area(&self.vertices)
+    }
+
+    fn perimeter(&self) -> f64 {
+        polygon_perimeter(&self.vertices)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::from_points(&self.vertices)
+    }
+
+    fn centroid(&self) -> Point {
+        polygon_centroid(&self.vertices)
+    }
+
+    fn contains(&self, point: Point) -> bool {
+        polygon_contains(&self.vertices, point)
+    }
+
+    fn outline(&self) -> Outline {
+        Outline::Polygon(self.vertices.clone())
+    }
+}
+
+impl Transform for Polygon {
+    fn transform(&self, similarity: &Similarity) -> Polygon {
+        Polygon::new(self.vertices.iter().map(|&p| similarity.apply(p)).collect())
+    }
+}
+
+/// Returns whether two numbers are within `eps` of each other.
+fn close(a: f64, b: f64, eps: f64) -> bool {
+    (a - b).abs() <= eps
+}
+
+/// Checks that a transform kept the shape's size and moved its centroid as expected.
+fn check_moved<S: Shape>(before: &S, after: &S, scale: f64, centroid: Point) {
+    assert!(close(after.area(), before.area() * scale * scale, 1e-9));
+    assert!(close(after.perimeter(), before.perimeter() * scale, 1e-9));
+    assert!(after.centroid().approx_eq(centroid, 1e-9));
+    assert!(after.contains(centroid));
+}
+
+/// Demonstrates the shape library on every shape type.
+pub fn shapes_demo() {
+    println!("Shapes:");
+    let circle = Circle::new(Point::new(1.0, 1.0), 2.0);
+    let square = Square::new(Point::ORIGIN, 2.0);
+    let rectangle = Rectangle::new(Point::new(3.0, 1.0), 4.0, 2.0);
+    let ellipse = Ellipse::new(Point::new(-2.0, 0.0), 3.0, 1.0);
+    let triangle = Triangle::new(Point::ORIGIN, Point::new(4.0, 0.0), Point::new(0.0, 3.0));
+    // An L shape: a 2×2 square with its upper-right quarter cut out.
+    let l_shape = Polygon::new(vec![
+        Point::new(0.0, 0.0),
+        Point::new(2.0, 0.0),
+        Point::new(2.0, 1.0),
+        Point::new(1.0, 1.0),
+        Point::new(1.0, 2.0),
+        Point::new(0.0, 2.0),
+    ]);
+
+    let shapes: [(&str, &dyn Shape); 6] = [
+        ("circle", &circle),
+        ("square", &square),
+        ("rectangle", &rectangle),
+        ("ellipse", &ellipse),
+        ("triangle", &triangle),
+        ("L shape", &l_shape),
+    ];
+    for (name, shape) in &shapes {
+        let b = shape.bounding_box();
+        let c = shape.centroid();
+        println!(
+            "{:<9} area {:>7.3}  perimeter {:>7.3}  centroid ({:.3}, {:.3})  box {:.1}×{:.1}",
+            name,
+            shape.area(),
+            shape.perimeter(),
+            c.x,
+            c.y,
+            b.width(),
+            b.height()
+        );
+        // Every shape lies within its box, and contains its centroid (all these are convex, or
+        // like the L shape, thick enough around the centroid).
+        assert!(b.contains(c) && shape.contains(c));
+        assert!(!shape.contains(b.max + Point::new(0.1, 0.1)));
+    }
+
+    assert!(close(circle.perimeter(), 4.0 * PI, 1e-12));
+    assert!(close(triangle.area(), 6.0, 1e-12) && close(triangle.perimeter(), 12.0, 1e-12));
+    assert!(close(l_shape.area(), 3.0, 1e-12) && close(l_shape.perimeter(), 8.0, 1e-12));
+    // The L shape's centroid is the area-weighted mean of its three unit squares' centers.
+    assert!(
+        l_shape
+            .centroid()
+            .approx_eq(Point::new(5.0 / 6.0, 5.0 / 6.0), 1e-12)
+    );
+    assert!(l_shape.contains(Point::new(0.5, 1.5)));
+    assert!(!l_shape.contains(Point::new(1.5, 1.5)));
+    // Points on edges and corners count as inside.
+    assert!(l_shape.contains(Point::new(1.0, 1.5)) && l_shape.contains(Point::new(2.0, 0.0)));
+    assert!(triangle.contains(Point::new(2.0, 1.5)) && !triangle.contains(Point::new(2.0, 1.6)));
+    assert!(rectangle.contains(Point::new(5.0, 2.0)) && !rectangle.contains(Point::new(5.0, 2.1)));
+    assert!(ellipse.contains(Point::new(1.0, 0.0)) && !ellipse.contains(Point::new(0.0, 1.0)));
+
+    // A circle is an ellipse with equal radii, and a square a rectangle with equal sides.
+    let round = Ellipse::new(circle.center, 2.0, 2.0);
+    assert!(close(round.perimeter(), circle.perimeter(), 1e-12));
+    assert!(
+        round
+            .bounding_box()
+            .approx_eq(&circle.bounding_box(), 1e-12)
+    );
+    let block = Rectangle::new(Point::ORIGIN, 2.0, 2.0);
+    assert!(close(block.perimeter(), square.perimeter(), 1e-12));
+
+    // The shoelace formula agrees with the closed forms: a regular polygon with many sides is
+    // close to its circumscribed circle, and a square polygon equals the square.
+    let hexagon = Polygon::regular(Point::ORIGIN, 1.0, 6);
+    assert!(close(hexagon.area(), 3.0 * 3f64.sqrt() / 2.0, 1e-12));
+    let many = Polygon::regular(circle.center, circle.radius, 1000);
+    assert!(close(many.area(), circle.area(), 1e-3));
+    assert!(many.centroid().approx_eq(circle.center, 1e-9));
+    assert!(close(
+        Polygon::new(square.corners().to_vec()).area(),
+        4.0,
+        1e-12
+    ));
+
+    // Transforms return new shapes; the originals are unchanged.
+    let moved = circle.translate(Point::new(3.0, -1.0));
+    check_moved(&circle, &moved, 1.0, Point::new(4.0, 0.0));
+    assert_eq!(circle.center, Point::new(1.0, 1.0));
+
+    // Rotating a square by 45° makes its bounding box √2 times wider.
+    let diamond = square.rotate(PI / 4.0, Point::ORIGIN);
+    check_moved(&square, &diamond, 1.0, Point::ORIGIN);
+    assert!(close(
+        diamond.bounding_box().width(),
+        2.0 * 2f64.sqrt(),
+        1e-12
+    ));
+    assert!(diamond.contains(Point::new(1.4, 0.0)) && !square.contains(Point::new(1.4, 0.0)));
+
+    // A quarter turn about the origin swaps a rectangle's extents.
+    let turned = rectangle.rotate(PI / 2.0, Point::ORIGIN);
+    check_moved(&rectangle, &turned, 1.0, Point::new(-1.0, 3.0));
+    let b = turned.bounding_box();
+    assert!(close(b.width(), 2.0, 1e-12) && close(b.height(), 4.0, 1e-12));
+
+    let tilted = ellipse.rotate(PI / 2.0, ellipse.center);
+    check_moved(&ellipse, &tilted, 1.0, ellipse.center);
+    assert!(close(tilted.bounding_box().height(), 6.0, 1e-12));
+    assert!(tilted.contains(Point::new(-2.0, 2.5)) && !ellipse.contains(Point::new(-2.0, 2.5)));
+
+    // Scaling about a point multiplies areas by the square of the factor.
+    let big = triangle.scale(2.0, Point::ORIGIN);
+    check_moved(&triangle, &big, 2.0, triangle.centroid() * 2.0);
+    assert_eq!(big.vertices[1], Point::new(8.0, 0.0));
+    let small = l_shape.scale(0.5, Point::new(2.0, 2.0));
+    check_moved(&l_shape, &small, 0.5, Point::new(17.0 / 12.0, 17.0 / 12.0));
+
+    // Transforms compose: rotating a full turn in quarter steps returns to the start.
+    let mut spun = l_shape.clone();
+    for _ in 0..4 {
+        spun = spun.rotate(PI / 2.0, Point::new(1.0, -1.0));
+    }
+    for (a, b) in spun.vertices.iter().zip(&l_shape.vertices) {
+        assert!(a.approx_eq(*b, 1e-12));
+    }
+    println!(
+        "L shape rotated 90° about (1, -1): {:?}",
+        l_shape
+            .rotate(PI / 2.0, Point::new(1.0, -1.0))
+            .vertices
+            .iter()
+            .map(|p| format!("({:.1}, {:.1})", p.x, p.y))
+            .collect::<Vec<_>>()
+    );
+}
@@ -13,35 +13,68 @@
        a minor runtime cost for pointer indirection. This approach offers flexibility when working with heterogeneous types.
 */
 
-trait Printable {
-    fn format(&self) -> String;
+use crate::pretty::{DEFAULT_WIDTH, Doc};
+
+/// Values that can be pretty-printed. Implementors describe their layout as a Doc, and the pretty
+/// module decides where to break lines; see pretty.rs for the impls on collections.
+pub(crate) trait Printable {
+    /// Builds the document describing how to lay the value out.
+    fn to_doc(&self) -> Doc;
+
+    /// Renders the value, breaking lines to fit DEFAULT_WIDTH columns where possible.
+    fn format(&self) -> String {
+        self.to_doc().render(DEFAULT_WIDTH)
+    }
 }
 
 impl Printable for i32 {
-    fn format(&self) -> String {
-        format!("i32: {}", *self)
+    fn to_doc(&self) -> Doc {
+        Doc::text(self.to_string())
     }
 }
 
 impl Printable for String {
-    fn format(&self) -> String {
-        format!("String: {}", *self)
+    /// Strings are quoted and escaped, so they stay recognizable inside collections.
+    fn to_doc(&self) -> Doc {
+        Doc::text(format!("{:?}", self))
+    }
+}
+
+/// Names the implementing type, so the demo's output shows which impl each call reached.
+trait Tagged: Printable {
+    fn tag(&self) -> &'static str;
+}
+
+impl Tagged for i32 {
+    fn tag(&self) -> &'static str {
+        "i32"
+    }
+}
+
+impl Tagged for String {
+    fn tag(&self) -> &'static str {
+        "String"
     }
 }
 
+/// Formats a value behind its type's tag, e.g. `i32: 123`.
+fn tagged(z: &(impl Tagged + ?Sized)) -> String {
+    format!("{}: {}", z.tag(), z.format())
+}
+
 // Example of Dynamic Dispatch:
 // The function below demonstrates dynamic dispatch using a trait object.
-// When invoked, the formatting method is resolved at runtime via the vtable.
-fn print_dynamic_dispatch(z: &dyn Printable) {
-    println!("{}", z.format());
+// When invoked, the tag and formatting methods are resolved at runtime via the vtable.
+fn print_dynamic_dispatch(z: &dyn Tagged) {
+    println!("{}", tagged(z));
 }
 
 /// Static Dispatch via Monomorphisation:
-/// This generic function, print_static_dispatch, works with any type implementing Printable.
+/// This generic function, print_static_dispatch, works with any type implementing Tagged.
 /// For each unique type passed, Rust generates a specific instance of this function at compile time,
 /// enabling efficient inlined code without runtime dispatch overhead.
-fn print_static_dispatch<T: Printable>(z: T) {
-    println!("{}", z.format());
+fn print_static_dispatch<T: Tagged>(z: T) {
+    println!("{}", tagged(&z));
 }
 
 pub fn static_and_dyn_dispatch_demo() {
@@ -56,4 +89,6 @@ pub fn static_and_dyn_dispatch_demo() {
     // Using dynamic dispatch
     print_dynamic_dispatch(&a);
     print_dynamic_dispatch(&b);
+    assert_eq!(tagged(&a), "i32: 123");
+    assert_eq!(tagged(&b as &dyn Tagged), r#"String: "Hello""#);
 }
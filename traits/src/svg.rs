@@ -0,0 +1,382 @@
+/*
+    Rendering collections of shapes as SVG.
+
+    A Scene is a tree in the composite pattern: each Item is either a single &dyn Shape or a group
+    of further items, and both kinds carry a style (fill, stroke) and a position. Groups render as
+    SVG <g> elements, so styles set on a group are inherited by its children unless they override
+    them, and positions add up down the tree.
+
+    The renderer only sees trait objects, so it asks each shape for its Shape::outline and draws
+    that: circles and ellipses as <circle> and <ellipse>, everything else as a <polygon>. A new
+    shape type needs no changes here as long as its outline is one of those three.
+
+    The shapes use mathematical coordinates, with y pointing up, while SVG's y axis points down.
+    The whole scene is therefore drawn inside a group that flips y, and the viewBox is fitted to
+    the flipped bounding box of the content.
+
+    The output is deterministic (coordinates are rounded to three decimals), so a rendered scene can
+    be compared as text against a snapshot file.
+*/
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use crate::shapes::{
+    BoundingBox, Circle, Ellipse, Outline, Point, Polygon, Rectangle, Shape, Square, Transform,
+    Triangle,
+};
+
+/// Colors given to the shapes of Scene::row, in turn.
+const PALETTE: [&str; 4] = ["#e76f51", "#2a9d8f", "#e9c46a", "#264653"];
+
+/// How a shape or group is painted. Unset properties are inherited from the enclosing group.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Style {
+    pub(crate) fill: Option<Cow<'static, str>>,
+    pub(crate) stroke: Option<(Cow<'static, str>, f64)>,
+}
+
+/// What an item draws: one shape, or a group of items.
+pub(crate) enum Element<'a> {
+    Shape(&'a dyn Shape),
+    Group(Vec<Item<'a>>),
+}
+
+/// A styled, positioned node of a scene.
+pub(crate) struct Item<'a> {
+    pub(crate) element: Element<'a>,
+    pub(crate) style: Style,
+    /// Added to the coordinates of everything the item draws.
+    pub(crate) position: Point,
+}
+
+impl<'a> Item<'a> {
+    /// Creates an unstyled item drawing one shape where it is.
+    pub(crate) fn shape(shape: &'a dyn Shape) -> Item<'a> {
+        Item {
+            element: Element::Shape(shape),
+            style: Style::default(),
+            position: Point::ORIGIN,
+        }
+    }
+
+    /// Creates an unstyled group of items.
+    pub(crate) fn group(items: Vec<Item<'a>>) -> Item<'a> {
+        Item {
+            element: Element::Group(items),
+            style: Style::default(),
+            position: Point::ORIGIN,
+        }
+    }
+
+    /// Sets the fill color, such as "red" or "#2a9d8f".
+    pub(crate) fn fill(mut self, color: impl Into<Cow<'static, str>>) -> Item<'a> {
+        self.style.fill = Some(color.into());
+        self
+    }
+
+    /// Sets the stroke color and width.
+    pub(crate) fn stroke(mut self, color: impl Into<Cow<'static, str>>, width: f64) -> Item<'a> {
+        self.style.stroke = Some((color.into(), width));
+        self
+    }
+
+    /// Moves the item by `position`.
+    pub(crate) fn at(mut self, position: Point) -> Item<'a> {
+        self.position = position;
+        self
+    }
+
+    /// Returns the box containing everything the item draws, in its parent's coordinates, or None
+    /// for an empty group.
+    pub(crate) fn bounding_box(&self) -> Option<BoundingBox> {
+        let own = match &self.element {
+            Element::Shape(shape) => Some(shape.bounding_box()),
+            Element::Group(items) => union(items),
+        };
+        own.map(|b| b.translated(self.position))
+    }
+
+    fn render(&self, depth: usize, out: &mut String) {
+        let indent = "  ".repeat(depth);
+        let mut attributes = String::new();
+        if let Some(fill) = &self.style.fill {
+            write!(attributes, r#" fill="{}""#, escape(fill)).unwrap();
+        }
+        if let Some((stroke, width)) = &self.style.stroke {
+            write!(
+                attributes,
+                r#" stroke="{}" stroke-width="{}""#,
+                escape(stroke),
+                number(*width)
+            )
+            .unwrap();
+        }
+        let mut transforms = Vec::new();
+        if self.position != Point::ORIGIN {
+            transforms.push(format!(
+                "translate({} {})",
+                number(self.position.x),
+                number(self.position.y)
+            ));
+        }
+
+        match &self.element {
+            Element::Shape(shape) => {
+                let geometry = match shape.outline() {
+                    Outline::Circle { center, radius } => format!(
+                        r#"circle cx="{}" cy="{}" r="{}""#,
+                        number(center.x),
+                        number(center.y),
+                        number(radius)
+                    ),
+                    Outline::Ellipse {
+                        center,
+                        radius_x,
+                        radius_y,
+                        angle,
+                    } => {
+                        if angle != 0.0 {
+                            transforms.push(format!(
+                                "rotate({} {} {})",
+                                number(angle.to_degrees()),
+                                number(center.x),
+                                number(center.y)
+                            ));
+                        }
+                        format!(
+                            r#"ellipse cx="{}" cy="{}" rx="{}" ry="{}""#,
+                            number(center.x),
+                            number(center.y),
+                            number(radius_x),
+                            number(radius_y)
+                        )
+                    }
+                    Outline::Polygon(vertices) => format!(
+                        r#"polygon points="{}""#,
+                        vertices
+                            .iter()
+                            .map(|p| format!("{},{}", number(p.x), number(p.y)))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    ),
+                };
+                writeln!(
+                    out,
+                    "{}<{}{}{}/>",
+                    indent,
+                    geometry,
+                    attributes,
+                    transform_attribute(&transforms)
+                )
+                .unwrap();
+            }
+            Element::Group(items) => {
+                writeln!(
+                    out,
+                    "{}<g{}{}>",
+                    indent,
+                    attributes,
+                    transform_attribute(&transforms)
+                )
+                .unwrap();
+                for item in items {
+                    item.render(depth + 1, out);
+                }
+                writeln!(out, "{}</g>", indent).unwrap();
+            }
+        }
+    }
+}
+
+/// Returns the box containing all the items, or None if none of them draws anything.
+fn union(items: &[Item]) -> Option<BoundingBox> {
+    items
+        .iter()
+        .filter_map(Item::bounding_box)
+        .reduce(|a, b| a.union(&b))
+}
+
+fn transform_attribute(transforms: &[String]) -> String {
+    match transforms.is_empty() {
+        true => String::new(),
+        false => format!(r#" transform="{}""#, transforms.join(" ")),
+    }
+}
+
+/// Formats a coordinate with at most three decimals and no trailing zeros.
+fn number(x: f64) -> String {
+    let text = format!("{:.3}", x);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        _ => text.to_string(),
+    }
+}
+
+/// Escapes the characters that would end or break an attribute value.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+}
+
+/// A drawing, made of items, that renders as a standalone SVG document.
+pub(crate) struct Scene<'a> {
+    pub(crate) items: Vec<Item<'a>>,
+    /// The empty space left around the content, in shape units.
+    pub(crate) margin: f64,
+    /// The size of one shape unit in the rendered image.
+    pub(crate) pixels_per_unit: f64,
+}
+
+impl<'a> Scene<'a> {
+    pub(crate) fn new(items: Vec<Item<'a>>) -> Scene<'a> {
+        Scene {
+            items,
+            margin: 0.5,
+            pixels_per_unit: 20.0,
+        }
+    }
+
+    /// Lays the shapes out from left to right, one unit apart and resting on the x axis, each
+    /// filled with the next color of a palette.
+    ///
+    /// # Arguments
+    ///
+    /// * `shapes` - The shapes to draw, wherever they are; each is moved into its place.
+    pub(crate) fn row(shapes: &[&'a dyn Shape]) -> Scene<'a> {
+        let mut x = 0.0;
+        let items = shapes
+            .iter()
+            .zip(PALETTE.iter().cycle())
+            .map(|(&shape, &color)| {
+                let b = shape.bounding_box();
+                let item = Item::shape(shape)
+                    .fill(color)
+                    .stroke("black", 0.05)
+                    .at(Point::new(x, 0.0) - b.min);
+                x += b.width() + 1.0;
+                item
+            })
+            .collect();
+        Scene::new(items)
+    }
+
+    /// Renders the scene as an SVG document.
+    pub(crate) fn render(&self) -> String {
+        let b = union(&self.items).unwrap_or(BoundingBox {
+            min: Point::ORIGIN,
+            max: Point::ORIGIN,
+        });
+        let (width, height) = (
+            b.width() + 2.0 * self.margin,
+            b.height() + 2.0 * self.margin,
+        );
+        let mut out = String::new();
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+            number(width * self.pixels_per_unit),
+            number(height * self.pixels_per_unit),
+            number(b.min.x - self.margin),
+            // After flipping, the top of the content is at -max.y.
+            number(-b.max.y - self.margin),
+            number(width),
+            number(height)
+        )
+        .unwrap();
+        writeln!(out, r#"  <g transform="scale(1 -1)">"#).unwrap();
+        for item in &self.items {
+            item.render(2, &mut out);
+        }
+        writeln!(out, "  </g>\n</svg>").unwrap();
+        out
+    }
+
+    /// Renders the scene and writes it to a file.
+    pub(crate) fn save(&self, path: &str) -> std::io::Result<()> {
+        std::fs::write(path, self.render())
+    }
+}
+
+/// Builds the demo scene and passes it to `f`: the shapes of why_dyn_dispatch_demo in a row,
+/// followed by a group showing style inheritance and nested positions. The scene only borrows its
+/// shapes, so it cannot outlive this function.
+pub(crate) fn with_demo_scene<R>(f: impl FnOnce(&Scene) -> R) -> R {
+    let (circle, square) = (
+        Circle::new(Point::ORIGIN, 1.0),
+        Square::new(Point::ORIGIN, 3.0),
+    );
+    let (big_circle, big_square) = (
+        Circle::new(Point::ORIGIN, 2.0),
+        Square::new(Point::ORIGIN, 4.0),
+    );
+    let mut scene = Scene::row(&[&circle, &square, &big_circle, &big_square]);
+
+    let rectangle = Rectangle::new(Point::ORIGIN, 3.0, 1.0).rotate(0.3, Point::ORIGIN);
+    let center = Point::new(4.0, 0.0);
+    let ellipse = Ellipse::new(center, 1.5, 0.75).rotate(std::f64::consts::PI / 6.0, center);
+    let triangle = Triangle::new(Point::ORIGIN, Point::new(2.0, 0.0), Point::new(1.0, 1.5));
+    let pentagon = Polygon::regular(Point::new(4.0, 0.5), 1.0, 5);
+    scene.items.push(
+        // The group's stroke applies to every child; the ellipse overrides the group's fill.
+        Item::group(vec![
+            Item::shape(&rectangle),
+            Item::shape(&ellipse).fill("#f4a261"),
+            Item::group(vec![
+                Item::shape(&triangle),
+                Item::shape(&pentagon).fill("white"),
+            ])
+            .at(Point::new(0.0, -3.0)),
+        ])
+        .fill("#8ab17d")
+        .stroke("#264653", 0.1)
+        .at(Point::new(20.0, 3.0)),
+    );
+    f(&scene)
+}
+
+/// Demonstrates rendering shapes and groups as SVG, checked against a snapshot. Run
+/// `cargo run -- svg [path]` to write the scene to a file.
+pub fn svg_demo() {
+    println!("SVG rendering:");
+    with_demo_scene(check_demo_scene);
+
+    // Attribute values are escaped, and an empty scene is still a valid document.
+    let circle = Circle::new(Point::ORIGIN, 1.0);
+    let odd = Scene::new(vec![Item::shape(&circle).fill("url(\"#a&b\")")]).render();
+    assert!(odd.contains(r#"fill="url(&quot;#a&amp;b&quot;)""#));
+    assert_eq!(
+        Scene::new(Vec::new()).render(),
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"20\" height=\"20\" viewBox=\"-0.5 -0.5 1 1\">\n  <g transform=\"scale(1 -1)\">\n  </g>\n</svg>\n"
+    );
+    assert_eq!(number(-0.0001), "0");
+    assert_eq!(number(2.50), "2.5");
+}
+
+fn check_demo_scene(scene: &Scene) {
+    let svg = scene.render();
+    print!("{}", svg);
+    assert_eq!(svg, include_str!("../data/shapes.svg"));
+
+    // The row places the shapes side by side, without overlap.
+    let boxes: Vec<BoundingBox> = scene.items[..4]
+        .iter()
+        .map(|item| item.bounding_box().unwrap())
+        .collect();
+    for pair in boxes.windows(2) {
+        assert!((pair[1].min.x - pair[0].max.x - 1.0).abs() < 1e-12);
+        assert!(pair[0].min.y.abs() < 1e-12);
+    }
+    // A group's box is its children's, moved by the group's position.
+    let group = &scene.items[4];
+    let children = match &group.element {
+        Element::Group(items) => union(items).unwrap(),
+        Element::Shape(_) => unreachable!(),
+    };
+    assert_eq!(
+        group.bounding_box(),
+        Some(children.translated(group.position))
+    );
+}
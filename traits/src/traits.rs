@@ -18,49 +18,203 @@ These features make traits a cornerstone of Rust's type system, empowering devel
 flexible, and maintainable code.
 */
 
-trait Animal {
-    fn name(&self) -> &'static str;
+use std::any::Any;
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+// Animal is shared with the other modules of the crate, which store animals as trait objects
+// (Box<dyn Animal>). A trait can only be used that way if it is "object safe" (dyn compatible):
+// every method must be callable through a pointer to an unknown type. `create` returns Self by
+// value, whose size is unknown behind a pointer, so it is marked `where Self: Sized`. That excludes
+// it from trait objects while keeping it available on concrete types like Human and Cat.
+//
+// Names are stored as Cow<'static, str> ("clone on write"), which holds either a borrowed string
+// literal or an owned String. Literals cost no allocation, and names read at runtime (from stdin or
+// a file) can still be stored. `create` accepts anything convertible into one: a &'static str, a
+// String, or a Cow.
+//
+// The Any supertrait lets a &dyn Animal be upcast to &dyn Any to ask for its concrete type's
+// TypeId, which the animal registry uses to find the kind an animal was registered under.
+pub(crate) trait Animal: Any {
+    fn name(&self) -> &str;
+    // The kind of animal, used by the default descriptions.
+    fn species(&self) -> &'static str {
+        "animal"
+    }
     // Provides a default implementation for talking.
     fn talk(&self) {
         println!("{} cannot talk", self.name());
     }
+    fn eat(&self) {
+        println!("{} eats", self.name());
+    }
+    fn sleep(&self) {
+        println!("{} sleeps", self.name());
+    }
+    // Picks "a" or "an" from the species' first letter, which is right for the English words
+    // species are named with ("a cat", "an animal").
+    fn describe(&self) -> String {
+        let species = self.species();
+        let article = if species.starts_with(['a', 'e', 'i', 'o', 'u']) {
+            "an"
+        } else {
+            "a"
+        };
+        format!("{} is {} {}", self.name(), article, species)
+    }
+    // Extra state beyond the name, as (key, value) pairs, for saving an animal. Most animals have
+    // none.
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+    // Restores one attribute returned by `attributes` when loading an animal.
+    fn set_attribute(&mut self, key: &str, _value: &str) -> Result<(), String> {
+        Err(format!("a {} has no attribute '{}'", self.species(), key))
+    }
     // Factory method for creating an instance of the implementing type.
-    fn create(name: &'static str) -> Self;
+    fn create(name: impl Into<Cow<'static, str>>) -> Self
+    where
+        Self: Sized;
 }
 
-struct Human {
-    name: &'static str,
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Human {
+    name: Cow<'static, str>,
 }
 
 impl Animal for Human {
-    fn name(&self) -> &'static str {
-        self.name
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn species(&self) -> &'static str {
+        "human"
     }
 
     fn talk(&self) {
         println!("{} says hello", self.name);
     }
 
-    fn create(name: &'static str) -> Human {
-        Human { name: name }
+    fn eat(&self) {
+        println!("{} sits down at the table to eat", self.name);
+    }
+
+    fn create(name: impl Into<Cow<'static, str>>) -> Human {
+        Human { name: name.into() }
     }
 }
 
-struct Cat {
-    name: &'static str,
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Cat {
+    name: Cow<'static, str>,
 }
 
 impl Animal for Cat {
-    fn name(&self) -> &'static str {
-        self.name
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn species(&self) -> &'static str {
+        "cat"
     }
 
     fn talk(&self) {
         println!("{} says meow", self.name);
     }
 
-    fn create(name: &'static str) -> Cat {
-        Cat { name: name }
+    fn sleep(&self) {
+        println!("{} curls up in a sunny spot and sleeps", self.name);
+    }
+
+    fn create(name: impl Into<Cow<'static, str>>) -> Cat {
+        Cat { name: name.into() }
+    }
+}
+
+pub(crate) struct Dog {
+    name: Cow<'static, str>,
+}
+
+impl Animal for Dog {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn species(&self) -> &'static str {
+        "dog"
+    }
+
+    fn talk(&self) {
+        println!("{} says woof", self.name);
+    }
+
+    fn eat(&self) {
+        println!("{} empties the bowl in seconds", self.name);
+    }
+
+    fn describe(&self) -> String {
+        format!("{} is a good dog", self.name)
+    }
+
+    fn create(name: impl Into<Cow<'static, str>>) -> Dog {
+        Dog { name: name.into() }
+    }
+}
+
+pub(crate) struct Parrot {
+    name: Cow<'static, str>,
+    phrase: Cow<'static, str>,
+}
+
+impl Parrot {
+    /// Creates a parrot that has learned to say `phrase`.
+    pub(crate) fn with_phrase(
+        name: impl Into<Cow<'static, str>>,
+        phrase: impl Into<Cow<'static, str>>,
+    ) -> Parrot {
+        Parrot {
+            name: name.into(),
+            phrase: phrase.into(),
+        }
+    }
+}
+
+impl Animal for Parrot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn species(&self) -> &'static str {
+        "parrot"
+    }
+
+    fn talk(&self) {
+        println!("{} squawks \"{}\"", self.name, self.phrase);
+    }
+
+    fn describe(&self) -> String {
+        format!("{} is a parrot that says \"{}\"", self.name, self.phrase)
+    }
+
+    fn attributes(&self) -> Vec<(&'static str, String)> {
+        vec![("phrase", self.phrase.to_string())]
+    }
+
+    fn set_attribute(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "phrase" => {
+                self.phrase = Cow::Owned(value.to_string());
+                Ok(())
+            }
+            _ => Err(format!("a parrot has no attribute '{}'", key)),
+        }
+    }
+
+    // A parrot created without a phrase only knows its own name.
+    fn create(name: impl Into<Cow<'static, str>>) -> Parrot {
+        let name = name.into();
+        Parrot::with_phrase(name.clone(), name)
     }
 }
 
@@ -75,16 +229,20 @@ impl Summable<i32> for Vec<i32> {
         for num in self {
             result += *num;
         }
-        return result;
+        result
     }
 }
 
 pub fn traits() {
     println!("Traits:");
-    let h = Human { name: "Kenry" };
+    let h = Human {
+        name: Cow::Borrowed("Kenry"),
+    };
     h.talk();
 
-    let c = Cat { name: "Meeku" };
+    let c = Cat {
+        name: Cow::Borrowed("Meeku"),
+    };
     c.talk();
 
     // Using the creation method to instantiate a Human.
@@ -95,6 +253,33 @@ pub fn traits() {
     let h_3: Human = Animal::create("Kenji");
     h_3.talk();
 
+    // Names built at runtime are owned Strings; literals stay borrowed.
+    let owned_name = format!("{}-{}", "Kenji", 2);
+    let h_4 = Human::create(owned_name);
+    assert_eq!(h_4.name(), "Kenji-2");
+    assert!(matches!(h_3.name, Cow::Borrowed(_)));
+    assert!(matches!(h_4.name, Cow::Owned(_)));
+
+    // Default behaviours, and the ones each species overrides.
+    let animals: Vec<Box<dyn Animal>> = vec![
+        Box::new(h_4),
+        Box::new(Cat::create("Tama")),
+        Box::new(Dog::create("Pochi")),
+        Box::new(Parrot::with_phrase("Polly", "Polly wants a cracker")),
+    ];
+    for animal in &animals {
+        println!("{}", animal.describe());
+        animal.talk();
+        animal.eat();
+        animal.sleep();
+    }
+    assert_eq!(animals[1].describe(), "Tama is a cat");
+    assert_eq!(animals[2].describe(), "Pochi is a good dog");
+    assert_eq!(
+        Parrot::create("Kiwi").describe(),
+        "Kiwi is a parrot that says \"Kiwi\""
+    );
+
     let a = vec![1, 2, 3];
     println!("sum = {}", a.sum());
 }
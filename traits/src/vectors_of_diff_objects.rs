@@ -8,64 +8,52 @@
     2. Using dynamic dispatch with trait objects: By storing Box<dyn Animal> in a vector, any type implementing
        the Animal trait can be added directly. This method simplifies code by allowing direct method calls
        without needing to unwrap an enum.
-*/
-
-trait Animal {
-    fn name(&self) -> &'static str;
-
-    fn talk(&self) {
-        println!("{} cannot talk", self.name());
-    }
-}
-
-struct Human {
-    name: &'static str,
-}
-
-impl Animal for Human {
-    fn name(&self) -> &'static str {
-        self.name
-    }
 
-    fn talk(&self) {
-        println!("Hello, my name is {}", self.name());
-    }
-}
-
-struct Cat {
-    name: &'static str,
-}
+    Both approaches use the Animal trait, Human and Cat from the traits module. Animal is object safe,
+    so it works as `dyn Animal` even though it also has a `create` factory method.
+*/
 
-impl Animal for Cat {
-    fn name(&self) -> &'static str {
-        self.name
-    }
+use serde::{Deserialize, Serialize};
 
-    fn talk(&self) {
-        println!("{} says meow", self.name());
-    }
-}
+use crate::traits::{Animal, Cat, Human};
 
 /// Enum wrapper for different Animal implementations.
 /// This allows us to store both Human and Cat in a single vector using explicit variants.
-enum Creature {
+///
+/// Because every variant is known at compile time, serde can derive serialization for the enum. The
+/// `tag` attribute stores the variant name inside each object, as in {"type": "cat", "name": "Meeku"},
+/// the same layout the animal_serde module writes for trait objects.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub(crate) enum Creature {
     Human(Human),
     Cat(Cat),
 }
 
+// `first_vector` is deliberately built with push to show how its element type gets inferred.
+#[allow(clippy::vec_init_then_push)]
 pub fn vectors_of_different_objects_demo() {
     println!("Vectors storing heterogeneous objects:");
 
     // Direct vector creation with a concrete type is inflexible:
     let mut first_vector = Vec::new();
-    first_vector.push(Human { name: "Kenry" });
+    first_vector.push(Human::create("Kenry"));
     // The following would fail because first_vector is inferred as Vec<Human>:
-    // first_vector.push(Cat { name: "Meeku" });
+    // first_vector.push(Cat::create("Meeku"));
 
     // Approach 1: Using an enum to wrap different types.
-    let mut enum_vector = Vec::new();
-    enum_vector.push(Creature::Human(Human { name: "Kary" }));
-    enum_vector.push(Creature::Cat(Cat { name: "Meeku" }));
+    let enum_vector = vec![
+        Creature::Human(Human::create("Kary")),
+        Creature::Cat(Cat::create("Meeku")),
+    ];
+
+    let json = serde_json::to_string(&enum_vector).unwrap();
+    println!("{}", json);
+    assert_eq!(
+        json,
+        r#"[{"type":"human","name":"Kary"},{"type":"cat","name":"Meeku"}]"#
+    );
+    let enum_vector: Vec<Creature> = serde_json::from_str(&json).unwrap();
 
     for creature in enum_vector {
         match creature {
@@ -77,8 +65,8 @@ pub fn vectors_of_different_objects_demo() {
     // Approach 2: Using dynamic dispatch with trait objects.
     // This allows us to store any type that implements Animal without the need for an enum.
     let mut animals: Vec<Box<dyn Animal>> = Vec::new();
-    animals.push(Box::new(Human { name: "Kary" }));
-    animals.push(Box::new(Cat { name: "Meeku" }));
+    animals.push(Box::new(Human::create("Kary")));
+    animals.push(Box::new(Cat::create("Meeku")));
 
     for animal in animals.iter() {
         animal.talk();
@@ -6,30 +6,7 @@
     crucial when the exact types may vary or be unknown at compile time.
 */
 
-struct Circle {
-    radius: f64,
-}
-
-struct Square {
-    side: f64,
-}
-
-trait Shape {
-    /// Calculates and returns the area of the shape.
-    fn area(&self) -> f64;
-}
-
-impl Shape for Square {
-    fn area(&self) -> f64 {
-        self.side * self.side
-    }
-}
-
-impl Shape for Circle {
-    fn area(&self) -> f64 {
-        self.radius * self.radius * std::f64::consts::PI
-    }
-}
+use crate::shapes::{Circle, Point, Shape, Square};
 
 pub fn why_dyn_dispatch_demo() {
     println!("Why Dynamic Dispatch:");
@@ -37,10 +14,10 @@ pub fn why_dyn_dispatch_demo() {
     // Here we create an array of trait objects (&dyn Shape) containing both circles and squares.
     // Since the concrete types vary, the method calls for calculating the area are resolved at runtime.
     let shapes: [&dyn Shape; 4] = [
-        &Circle { radius: 1.0 },
-        &Square { side: 3.0 },
-        &Circle { radius: 2.0 },
-        &Square { side: 4.0 },
+        &Circle::new(Point::ORIGIN, 1.0),
+        &Square::new(Point::ORIGIN, 3.0),
+        &Circle::new(Point::ORIGIN, 2.0),
+        &Square::new(Point::ORIGIN, 4.0),
     ];
 
     // Iterate over the shapes, using dynamic dispatch to call each shape's area method.